@@ -28,7 +28,15 @@ impl fmt::Display for InternalError {
 }
 
 impl Error {
-    pub(crate) fn from_internal(err: imp::InternalError) -> Error {
-        Error::Internal(InternalError(err))
+    /// Wraps an error from a [`BackendImpl`](crate::platform::BackendImpl) provided outside of the crate.
+    pub fn custom<E>(err: E) -> Error
+    where
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        Error::from_internal(imp::InternalError::Custom(err.into()))
+    }
+
+    pub(crate) fn from_internal(err: impl Into<imp::InternalError>) -> Error {
+        Error::Internal(InternalError(err.into()))
     }
 }
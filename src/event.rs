@@ -1,6 +1,8 @@
 //! the event api
 
-#[derive(Copy, Clone, Debug)]
+use crate::monitor::{Point, Scale, Size};
+
+#[derive(Clone, Debug)]
 pub enum Event {
     /// The window has requested to close.
     /// For more information on why, see the associated [`CloseReason`].
@@ -8,6 +10,126 @@ pub enum Event {
 
     /// The window focus has been updated: `true` if focused, `false` if unfocused.
     Focus(bool),
+
+    /// The inner area of the window has been resized, to the same size and scale
+    /// [`Window::inner_size`](crate::window::Window::inner_size) now returns.
+    ///
+    /// Minimizing a window doesn't resize it.
+    Resize((Size, Scale)),
+
+    /// The window has been moved, to the same position
+    /// [`Window::outer_position`](crate::window::Window::outer_position) now returns.
+    Move(Point),
+
+    /// The window has been maximized (`true`) or restored from being maximized (`false`).
+    ///
+    /// Minimizing a maximized window doesn't count as restoring it, as that's what it goes back to.
+    Maximize(bool),
+
+    /// The window has been minimized (`true`) or restored from being minimized (`false`).
+    ///
+    /// Wayland doesn't tell clients when they're minimized, so this isn't received there.
+    Minimize(bool),
+
+    /// The scale factor of the window has changed, usually from being moved onto another monitor.
+    ///
+    /// Windows created with a logical inner size are resized to keep that size,
+    /// which is followed by a [`Resize`](Self::Resize) with the new scale.
+    ScaleFactor(Scale),
+
+    /// A monitor has been connected, disconnected or reconfigured, such as by changing its resolution.
+    ///
+    /// Any [`Monitor`](crate::monitor::Monitor) data kept around should be queried again.
+    MonitorsChanged,
+
+    /// A key has been pressed.
+    ///
+    /// `virtual_key` is the OS representation of the key after the keyboard layout has been applied:
+    /// - Win32: The [virtual-key code](https://docs.microsoft.com/en-us/windows/win32/inputdev/virtual-key-codes)
+    /// - X11 & Wayland: The keysym, without modifiers
+    KeyboardDown { key: Key, virtual_key: u32 },
+
+    /// A key is being held down and the OS has sent a repeat. See [`KeyboardDown`](Self::KeyboardDown).
+    KeyboardRepeat { key: Key, virtual_key: u32 },
+
+    /// A key has been released. See [`KeyboardDown`](Self::KeyboardDown).
+    KeyboardUp { key: Key, virtual_key: u32 },
+
+    /// A character has been typed, after the keyboard layout, dead keys and compose sequences are applied.
+    ///
+    /// This is separate from the keyboard events and is what text fields should use.
+    /// Holding down a key repeats its character, and control characters such as backspace (`'\u{8}'`),
+    /// enter (`'\r'`) or Ctrl+C (`'\u{3}'`) are included as the OS sends them.
+    Input(char),
+
+    /// The text being composed with an input method (IME) has changed.
+    ///
+    /// This text isn't final and should be displayed in place, usually underlined, until an
+    /// [`ImeCommit`](Self::ImeCommit) replaces it. Empty text means composition has ended or was cancelled.
+    /// `cursor_range` is the selection within it, as byte indices into `text`, or `None` to hide the cursor.
+    ///
+    /// Only received while the IME is allowed, see [`Window::set_ime_allowed`](crate::window::Window::set_ime_allowed).
+    ImePreedit { text: String, cursor_range: Option<(usize, usize)> },
+
+    /// An input method (IME) has finished composing text, which should be inserted as-is.
+    ///
+    /// Only received while the IME is allowed, see [`Window::set_ime_allowed`](crate::window::Window::set_ime_allowed).
+    ImeCommit(String),
+
+    /// The mouse cursor has entered the inner area of the window.
+    MouseEnter,
+
+    /// The mouse cursor has left the inner area of the window.
+    MouseLeave,
+
+    /// The mouse cursor has moved within the window, to the given position relative to the top-left of the inner area.
+    ///
+    /// Positions are logical if the window was created with a logical inner size, otherwise physical.
+    MouseMove(Point),
+
+    /// The mouse has moved, as reported by the device itself.
+    ///
+    /// Unlike [`MouseMove`](Self::MouseMove) there's no acceleration applied and it doesn't stop at the edges
+    /// of the screen, which is what camera controls should use, especially with `CursorLock::Center`.
+    /// The distance is in device-specific units, with right and down being positive.
+    ///
+    /// Only received while the window is focused.
+    RawMouseMotion { dx: f64, dy: f64 },
+
+    /// A mouse button has been pressed.
+    MouseDown(MouseButton),
+
+    /// A mouse button has been released.
+    MouseUp(MouseButton),
+
+    /// The mouse wheel has been scrolled.
+    ///
+    /// `delta` is the horizontal and vertical distance, measured in wheel notches,
+    /// which are positive when scrolling right and up (away from the user) respectively.
+    /// `precise` is set when the device reports finer steps than whole notches, such as touchpads
+    /// and high-resolution wheels, in which case `delta` can be fractional.
+    MouseWheel { delta: (f64, f64), precise: bool },
+}
+
+/// A button on the mouse, for the `MouseDown` and `MouseUp` [`Event`]s.
+///
+/// The OS's button mapping is already applied, so `Left` is the primary button even when it's been swapped.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum MouseButton {
+    /// The primary button.
+    Left,
+
+    /// The secondary button, which usually opens context menus.
+    Right,
+
+    /// The wheel button.
+    Middle,
+
+    /// The first side button, usually bound to "back".
+    X1,
+
+    /// The second side button, usually bound to "forward".
+    X2,
 }
 
 /// Details why a `CloseRequest` [`Event`] was received.
@@ -28,3 +150,475 @@ pub enum CloseReason {
     /// Likely reasons include external programs sending the signal.
     Unknown,
 }
+
+/// A physical key on the keyboard, named after its position on a US QWERTY layout.
+///
+/// These refer to where the key is and not what it's labelled as - if you're looking for the latter,
+/// see the `virtual_key` of the keyboard [`Event`]s. This makes them suitable for game controls,
+/// as `W`, `A`, `S` and `D` are always in the same place, even on an AZERTY keyboard.
+///
+/// Names follow the [W3C `code` values](https://www.w3.org/TR/uievents-code/),
+/// except for letters, which drop the `Key` prefix.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum Key {
+    // Writing system keys
+    Backquote,
+    Backslash,
+    BracketLeft,
+    BracketRight,
+    Comma,
+    Digit0,
+    Digit1,
+    Digit2,
+    Digit3,
+    Digit4,
+    Digit5,
+    Digit6,
+    Digit7,
+    Digit8,
+    Digit9,
+    Equal,
+    IntlBackslash,
+    IntlRo,
+    IntlYen,
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+    I,
+    J,
+    K,
+    L,
+    M,
+    N,
+    O,
+    P,
+    Q,
+    R,
+    S,
+    T,
+    U,
+    V,
+    W,
+    X,
+    Y,
+    Z,
+    Minus,
+    Period,
+    Quote,
+    Semicolon,
+    Slash,
+
+    // Functional keys
+    AltLeft,
+    AltRight,
+    Backspace,
+    CapsLock,
+    ContextMenu,
+    ControlLeft,
+    ControlRight,
+    Enter,
+    MetaLeft,
+    MetaRight,
+    ShiftLeft,
+    ShiftRight,
+    Space,
+    Tab,
+    Convert,
+    KanaMode,
+    Lang1,
+    Lang2,
+    NonConvert,
+
+    // Control pad & arrow keys
+    Delete,
+    End,
+    Home,
+    Insert,
+    PageDown,
+    PageUp,
+    ArrowDown,
+    ArrowLeft,
+    ArrowRight,
+    ArrowUp,
+
+    // Numpad
+    NumLock,
+    Numpad0,
+    Numpad1,
+    Numpad2,
+    Numpad3,
+    Numpad4,
+    Numpad5,
+    Numpad6,
+    Numpad7,
+    Numpad8,
+    Numpad9,
+    NumpadAdd,
+    NumpadComma,
+    NumpadDecimal,
+    NumpadDivide,
+    NumpadEnter,
+    NumpadEqual,
+    NumpadMultiply,
+    NumpadSubtract,
+
+    // Function section
+    Escape,
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+    F13,
+    F14,
+    F15,
+    F16,
+    F17,
+    F18,
+    F19,
+    F20,
+    F21,
+    F22,
+    F23,
+    F24,
+    PrintScreen,
+    ScrollLock,
+    Pause,
+
+    // Media keys
+    AudioVolumeDown,
+    AudioVolumeMute,
+    AudioVolumeUp,
+    MediaPlayPause,
+    MediaStop,
+    MediaTrackNext,
+    MediaTrackPrevious,
+
+    /// A key not listed here, with the platform scancode as passed to [`from_scancode`](Self::from_scancode)
+    /// or [`from_evdev`](Self::from_evdev).
+    Unknown(u32),
+}
+
+impl Key {
+    /// Converts a PC "Scan Code Set 1" scancode, as found in Win32 keyboard messages.
+    ///
+    /// Extended keys (those prefixed with `0xE0`) are passed as `0xE000 | scancode`.
+    pub fn from_scancode(scancode: u32) -> Self {
+        match scancode {
+            0x0001 => Self::Escape,
+            0x0002 => Self::Digit1,
+            0x0003 => Self::Digit2,
+            0x0004 => Self::Digit3,
+            0x0005 => Self::Digit4,
+            0x0006 => Self::Digit5,
+            0x0007 => Self::Digit6,
+            0x0008 => Self::Digit7,
+            0x0009 => Self::Digit8,
+            0x000A => Self::Digit9,
+            0x000B => Self::Digit0,
+            0x000C => Self::Minus,
+            0x000D => Self::Equal,
+            0x000E => Self::Backspace,
+            0x000F => Self::Tab,
+            0x0010 => Self::Q,
+            0x0011 => Self::W,
+            0x0012 => Self::E,
+            0x0013 => Self::R,
+            0x0014 => Self::T,
+            0x0015 => Self::Y,
+            0x0016 => Self::U,
+            0x0017 => Self::I,
+            0x0018 => Self::O,
+            0x0019 => Self::P,
+            0x001A => Self::BracketLeft,
+            0x001B => Self::BracketRight,
+            0x001C => Self::Enter,
+            0x001D => Self::ControlLeft,
+            0x001E => Self::A,
+            0x001F => Self::S,
+            0x0020 => Self::D,
+            0x0021 => Self::F,
+            0x0022 => Self::G,
+            0x0023 => Self::H,
+            0x0024 => Self::J,
+            0x0025 => Self::K,
+            0x0026 => Self::L,
+            0x0027 => Self::Semicolon,
+            0x0028 => Self::Quote,
+            0x0029 => Self::Backquote,
+            0x002A => Self::ShiftLeft,
+            0x002B => Self::Backslash,
+            0x002C => Self::Z,
+            0x002D => Self::X,
+            0x002E => Self::C,
+            0x002F => Self::V,
+            0x0030 => Self::B,
+            0x0031 => Self::N,
+            0x0032 => Self::M,
+            0x0033 => Self::Comma,
+            0x0034 => Self::Period,
+            0x0035 => Self::Slash,
+            0x0036 => Self::ShiftRight,
+            0x0037 => Self::NumpadMultiply,
+            0x0038 => Self::AltLeft,
+            0x0039 => Self::Space,
+            0x003A => Self::CapsLock,
+            0x003B => Self::F1,
+            0x003C => Self::F2,
+            0x003D => Self::F3,
+            0x003E => Self::F4,
+            0x003F => Self::F5,
+            0x0040 => Self::F6,
+            0x0041 => Self::F7,
+            0x0042 => Self::F8,
+            0x0043 => Self::F9,
+            0x0044 => Self::F10,
+            // Pause is really `E1 1D 45`, but Win32 reports it as `45` and moves NumLock to `E0 45`
+            0x0045 => Self::Pause,
+            0x0046 => Self::ScrollLock,
+            0x0047 => Self::Numpad7,
+            0x0048 => Self::Numpad8,
+            0x0049 => Self::Numpad9,
+            0x004A => Self::NumpadSubtract,
+            0x004B => Self::Numpad4,
+            0x004C => Self::Numpad5,
+            0x004D => Self::Numpad6,
+            0x004E => Self::NumpadAdd,
+            0x004F => Self::Numpad1,
+            0x0050 => Self::Numpad2,
+            0x0051 => Self::Numpad3,
+            0x0052 => Self::Numpad0,
+            0x0053 => Self::NumpadDecimal,
+            0x0054 => Self::PrintScreen, // Alt+PrintScreen (SysRq)
+            0x0056 => Self::IntlBackslash,
+            0x0057 => Self::F11,
+            0x0058 => Self::F12,
+            0x0059 => Self::NumpadEqual,
+            0x0064 => Self::F13,
+            0x0065 => Self::F14,
+            0x0066 => Self::F15,
+            0x0067 => Self::F16,
+            0x0068 => Self::F17,
+            0x0069 => Self::F18,
+            0x006A => Self::F19,
+            0x006B => Self::F20,
+            0x006C => Self::F21,
+            0x006D => Self::F22,
+            0x006E => Self::F23,
+            0x0070 => Self::KanaMode,
+            0x0071 => Self::Lang2,
+            0x0072 => Self::Lang1,
+            0x0073 => Self::IntlRo,
+            0x0076 => Self::F24,
+            0x0079 => Self::Convert,
+            0x007B => Self::NonConvert,
+            0x007D => Self::IntlYen,
+            0x007E => Self::NumpadComma,
+            0xE010 => Self::MediaTrackPrevious,
+            0xE019 => Self::MediaTrackNext,
+            0xE01C => Self::NumpadEnter,
+            0xE01D => Self::ControlRight,
+            0xE020 => Self::AudioVolumeMute,
+            0xE022 => Self::MediaPlayPause,
+            0xE024 => Self::MediaStop,
+            0xE02E => Self::AudioVolumeDown,
+            0xE030 => Self::AudioVolumeUp,
+            0xE035 => Self::NumpadDivide,
+            0xE037 => Self::PrintScreen,
+            0xE038 => Self::AltRight,
+            0xE045 => Self::NumLock,
+            0xE046 => Self::Pause, // Ctrl+Pause (Break)
+            0xE047 => Self::Home,
+            0xE048 => Self::ArrowUp,
+            0xE049 => Self::PageUp,
+            0xE04B => Self::ArrowLeft,
+            0xE04D => Self::ArrowRight,
+            0xE04F => Self::End,
+            0xE050 => Self::ArrowDown,
+            0xE051 => Self::PageDown,
+            0xE052 => Self::Insert,
+            0xE053 => Self::Delete,
+            0xE05B => Self::MetaLeft,
+            0xE05C => Self::MetaRight,
+            0xE05D => Self::ContextMenu,
+            x => Self::Unknown(x),
+        }
+    }
+
+    /// Converts a Linux evdev key code, as found in Wayland keyboard events.
+    ///
+    /// X11 keycodes are these plus 8 on pretty much any modern X server.
+    pub fn from_evdev(code: u32) -> Self {
+        match code {
+            1 => Self::Escape,
+            2 => Self::Digit1,
+            3 => Self::Digit2,
+            4 => Self::Digit3,
+            5 => Self::Digit4,
+            6 => Self::Digit5,
+            7 => Self::Digit6,
+            8 => Self::Digit7,
+            9 => Self::Digit8,
+            10 => Self::Digit9,
+            11 => Self::Digit0,
+            12 => Self::Minus,
+            13 => Self::Equal,
+            14 => Self::Backspace,
+            15 => Self::Tab,
+            16 => Self::Q,
+            17 => Self::W,
+            18 => Self::E,
+            19 => Self::R,
+            20 => Self::T,
+            21 => Self::Y,
+            22 => Self::U,
+            23 => Self::I,
+            24 => Self::O,
+            25 => Self::P,
+            26 => Self::BracketLeft,
+            27 => Self::BracketRight,
+            28 => Self::Enter,
+            29 => Self::ControlLeft,
+            30 => Self::A,
+            31 => Self::S,
+            32 => Self::D,
+            33 => Self::F,
+            34 => Self::G,
+            35 => Self::H,
+            36 => Self::J,
+            37 => Self::K,
+            38 => Self::L,
+            39 => Self::Semicolon,
+            40 => Self::Quote,
+            41 => Self::Backquote,
+            42 => Self::ShiftLeft,
+            43 => Self::Backslash,
+            44 => Self::Z,
+            45 => Self::X,
+            46 => Self::C,
+            47 => Self::V,
+            48 => Self::B,
+            49 => Self::N,
+            50 => Self::M,
+            51 => Self::Comma,
+            52 => Self::Period,
+            53 => Self::Slash,
+            54 => Self::ShiftRight,
+            55 => Self::NumpadMultiply,
+            56 => Self::AltLeft,
+            57 => Self::Space,
+            58 => Self::CapsLock,
+            59 => Self::F1,
+            60 => Self::F2,
+            61 => Self::F3,
+            62 => Self::F4,
+            63 => Self::F5,
+            64 => Self::F6,
+            65 => Self::F7,
+            66 => Self::F8,
+            67 => Self::F9,
+            68 => Self::F10,
+            69 => Self::NumLock,
+            70 => Self::ScrollLock,
+            71 => Self::Numpad7,
+            72 => Self::Numpad8,
+            73 => Self::Numpad9,
+            74 => Self::NumpadSubtract,
+            75 => Self::Numpad4,
+            76 => Self::Numpad5,
+            77 => Self::Numpad6,
+            78 => Self::NumpadAdd,
+            79 => Self::Numpad1,
+            80 => Self::Numpad2,
+            81 => Self::Numpad3,
+            82 => Self::Numpad0,
+            83 => Self::NumpadDecimal,
+            86 => Self::IntlBackslash,
+            87 => Self::F11,
+            88 => Self::F12,
+            89 => Self::IntlRo,
+            92 => Self::Convert,
+            93 => Self::KanaMode,
+            94 => Self::NonConvert,
+            96 => Self::NumpadEnter,
+            97 => Self::ControlRight,
+            98 => Self::NumpadDivide,
+            99 => Self::PrintScreen,
+            100 => Self::AltRight,
+            102 => Self::Home,
+            103 => Self::ArrowUp,
+            104 => Self::PageUp,
+            105 => Self::ArrowLeft,
+            106 => Self::ArrowRight,
+            107 => Self::End,
+            108 => Self::ArrowDown,
+            109 => Self::PageDown,
+            110 => Self::Insert,
+            111 => Self::Delete,
+            113 => Self::AudioVolumeMute,
+            114 => Self::AudioVolumeDown,
+            115 => Self::AudioVolumeUp,
+            117 => Self::NumpadEqual,
+            119 => Self::Pause,
+            121 => Self::NumpadComma,
+            122 => Self::Lang1,
+            123 => Self::Lang2,
+            124 => Self::IntlYen,
+            125 => Self::MetaLeft,
+            126 => Self::MetaRight,
+            127 => Self::ContextMenu,
+            163 => Self::MediaTrackNext,
+            164 => Self::MediaPlayPause,
+            165 => Self::MediaTrackPrevious,
+            166 => Self::MediaStop,
+            183 => Self::F13,
+            184 => Self::F14,
+            185 => Self::F15,
+            186 => Self::F16,
+            187 => Self::F17,
+            188 => Self::F18,
+            189 => Self::F19,
+            190 => Self::F20,
+            191 => Self::F21,
+            192 => Self::F22,
+            193 => Self::F23,
+            194 => Self::F24,
+            x => Self::Unknown(x),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Key;
+
+    #[test]
+    fn scancode_tables_agree() {
+        // Evdev codes were originally the set 1 scancodes, so the main block should line up
+        for code in 0x01..=0x53 {
+            if code == 0x45 {
+                continue // NumLock & Pause, see `from_scancode`
+            }
+            assert_eq!(Key::from_scancode(code), Key::from_evdev(code), "code {:#04X}", code);
+        }
+    }
+
+    #[test]
+    fn unknown_keys_keep_scancode() {
+        assert_eq!(Key::from_scancode(0xE0FF), Key::Unknown(0xE0FF));
+        assert_eq!(Key::from_evdev(0x2FF), Key::Unknown(0x2FF));
+    }
+}
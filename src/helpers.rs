@@ -1,6 +1,6 @@
 //! Helper types and methods for use mainly within `pub(crate)` context.
 
-use std::{cell::UnsafeCell, ops::Deref, ptr, sync::{Arc, Once}};
+use std::{cell::UnsafeCell, fmt, ops::Deref, ptr, sync::{Arc, Once}};
 
 /// Helps you create C-compatible string literals, like `c_string!("Hello!")` -> `b"Hello!\0"`.
 macro_rules! c_string {
@@ -17,11 +17,46 @@ macro_rules! document {
     };
 }
 
+/// Declares a struct of function pointers loaded at runtime from dynamic libraries.
+///
+/// Each library can be given several names to try in order, such as `"libX11.so.6" | "libX11.so"`.
+/// The calling convention defaults to `extern "system"`, and can be set like `struct X(...) extern "C" { ... }`.
+///
+/// With `static NAME` after the calling convention, the struct is also instantiated lazily in a static,
+/// along with free functions of the same names and signatures as the linked ones, calling through it.
+/// These panic if their symbol is missing, so check the [`LinkReport`] beforehand.
+#[allow(unused_macros)]
 macro_rules! dyn_link {
     (
         $(#[$outer:meta])*
-        $s_vis:vis struct $s_ident:ident($dlopen:expr => $dlopen_ty:ty | $dlsym:expr) {
-            $($($module_name:literal)|+ {
+        $s_vis:vis struct $s_ident:ident($dlopen:expr => $dlopen_ty:ty | $dlsym:expr) $body:tt
+    ) => {
+        dyn_link! {
+            $(#[$outer])*
+            $s_vis struct $s_ident($dlopen => $dlopen_ty | $dlsym) extern "system" $body
+        }
+    };
+
+    (
+        $(#[$outer:meta])*
+        $s_vis:vis struct $s_ident:ident($dlopen:expr => $dlopen_ty:ty | $dlsym:expr)
+            extern $abi:literal static $global:ident $body:tt
+    ) => {
+        dyn_link! {
+            $(#[$outer])*
+            $s_vis struct $s_ident($dlopen => $dlopen_ty | $dlsym) extern $abi $body
+        }
+
+        $s_vis static $global: $crate::helpers::LazyCell<$s_ident> =
+            $crate::helpers::LazyCell::new(|| unsafe { $s_ident::_link() });
+
+        dyn_link!(@globals $global $abi $body);
+    };
+
+    (
+        $(#[$outer:meta])*
+        $s_vis:vis struct $s_ident:ident($dlopen:expr => $dlopen_ty:ty | $dlsym:expr) extern $abi:literal {
+            $($first_module_name:literal $(| $module_name:literal)* {
                 $(
                     $(#[$fn_outer:meta])*
                     fn $sym_fn:ident($($name:ident : $ty:ty),*$(,)?) -> $ret:ty;
@@ -34,19 +69,26 @@ macro_rules! dyn_link {
             $($(
                 $(#[$fn_outer])*
                 $sym_fn : ::std::option::Option<
-                    unsafe extern "system" fn($($name : $ty ,)*) -> $ret
+                    unsafe extern $abi fn($($name : $ty ,)*) -> $ret
                 > ,
             )*)*
+            _report: $crate::helpers::LinkReport,
         }
 
+        #[allow(dead_code, clippy::too_many_arguments)]
         impl $s_ident {
-            #[allow(unused_doc_comments)]
+            #[allow(unused_doc_comments, clippy::zero_ptr, clippy::missing_transmute_annotations)]
             unsafe fn _link() -> Self {
-                let mut inst = ::std::mem::MaybeUninit::<Self>::uninit();
-                let mut inst_ref = &mut *(inst.as_mut_ptr());
+                let mut inst = Self {
+                    $($(
+                        $(#[$fn_outer])*
+                        $sym_fn: None,
+                    )*)*
+                    _report: $crate::helpers::LinkReport::default(),
+                };
                 $(
                     let mut handle = 0 as $dlopen_ty;
-                    for name in &[$(c_string!($module_name) ,)*] {
+                    for name in &[c_string!($first_module_name), $(c_string!($module_name) ,)*] {
                         handle = $dlopen(name.as_ptr().cast());
                         if handle != 0 as $dlopen_ty {
                             break
@@ -56,23 +98,27 @@ macro_rules! dyn_link {
                         $(
                             $(#[$fn_outer])*
                             {
-                                inst_ref.$sym_fn =
+                                inst.$sym_fn =
                                     ::std::mem::transmute::<_,
-                                        Option<unsafe extern "system" fn($($name : $ty ,)*) -> $ret>>
+                                        Option<unsafe extern $abi fn($($name : $ty ,)*) -> $ret>>
                                     ($dlsym(handle, c_string!(stringify!($sym_fn)).as_ptr().cast()));
+                                if inst.$sym_fn.is_none() {
+                                    inst._report.missing_symbols.push(stringify!($sym_fn));
+                                }
                             }
                         )*
                     } else {
-                        $(
-                            $(#[$fn_outer])*
-                            {
-                                inst_ref.$sym_fn = None;
-                            }
-                        )*
+                        inst._report.missing_libraries.push($first_module_name);
                     }
                 )*
-                inst.assume_init()
+                inst
+            }
+
+            /// Gets which of the libraries and symbols couldn't be loaded.
+            $s_vis fn link_report(&self) -> &$crate::helpers::LinkReport {
+                &self._report
             }
+
             $($(
                 $(#[$fn_outer])*
                 $s_vis unsafe fn $sym_fn(&self, $($name : $ty ,)*) -> ::std::option::Option<$ret> {
@@ -81,6 +127,65 @@ macro_rules! dyn_link {
             )*)*
         }
     };
+
+    (@globals $global:ident $abi:literal {
+        $($first_module_name:literal $(| $module_name:literal)* {
+            $(
+                $(#[$fn_outer:meta])*
+                fn $sym_fn:ident($($name:ident : $ty:ty),*$(,)?) -> $ret:ty;
+            )*
+        }),* $(,)?
+    }) => {
+        $($(
+            $(#[$fn_outer])*
+            #[inline]
+            #[allow(clippy::too_many_arguments)]
+            pub unsafe fn $sym_fn($($name : $ty ,)*) -> $ret {
+                match $global.$sym_fn {
+                    Some(f) => f($($name ,)*),
+                    None => $crate::helpers::missing_symbol(stringify!($sym_fn)),
+                }
+            }
+        )*)*
+    };
+}
+
+/// Which libraries and symbols a struct declared with `dyn_link!` couldn't load.
+///
+/// Symbols belonging to missing libraries aren't listed, as none of them could have been loaded.
+#[derive(Clone, Debug, Default)]
+pub struct LinkReport {
+    pub missing_libraries: Vec<&'static str>,
+    pub missing_symbols: Vec<&'static str>,
+}
+
+impl LinkReport {
+    /// Whether everything was loaded.
+    pub fn is_complete(&self) -> bool {
+        self.missing_libraries.is_empty() && self.missing_symbols.is_empty()
+    }
+}
+
+impl fmt::Display for LinkReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.missing_libraries.is_empty() {
+            write!(f, "Missing libraries: {}", self.missing_libraries.join(", "))?;
+            if !self.missing_symbols.is_empty() {
+                f.write_str("; ")?;
+            }
+        }
+        if !self.missing_symbols.is_empty() {
+            write!(f, "Missing symbols: {}", self.missing_symbols.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cold]
+#[inline(never)]
+#[allow(dead_code)]
+pub(crate) fn missing_symbol(name: &str) -> ! {
+    panic!("dynamically linked function {} is missing (this is a bug)", name)
 }
 
 /// Used to const initialize fields which don't necessarily need allocation (ex. str).
@@ -108,7 +213,7 @@ impl<T: ?Sized + 'static> Clone for MaybeStatic<T> {
 impl<T: ?Sized + 'static> AsRef<T> for MaybeStatic<T> {
     #[inline]
     fn as_ref(&self) -> &T {
-        &*self
+        self
     }
 }
 
@@ -189,6 +294,12 @@ pub(crate) mod sync {
         cvar.notify_one();
     }
 
+    #[inline]
+    #[cfg_attr(windows, allow(dead_code))]
+    pub fn condvar_notify_all(cvar: &Condvar) {
+        cvar.notify_all();
+    }
+
     pub fn condvar_wait<T>(cvar: &Condvar, guard: &mut MutexGuard<T>) {
         // The signature in `std` is quite terrible and CONSUMES the guard
         // We "move it out" for the duration of the wait
@@ -199,7 +310,7 @@ pub(crate) mod sync {
         }
     }
 
-    pub fn mutex_lock<T>(mtx: &Mutex<T>) -> MutexGuard<T> {
+    pub fn mutex_lock<T>(mtx: &Mutex<T>) -> MutexGuard<'_, T> {
         mtx.lock().expect("mutex poisoned (this is a bug)")
     }
 }
@@ -212,13 +323,58 @@ pub(crate) mod sync {
         let _ = cvar.notify_one();
     }
 
+    #[inline]
+    #[cfg_attr(windows, allow(dead_code))]
+    pub fn condvar_notify_all(cvar: &Condvar) {
+        let _ = cvar.notify_all();
+    }
+
     #[inline]
     pub fn condvar_wait<T>(cvar: &Condvar, guard: &mut MutexGuard<T>) {
         cvar.wait(guard);
     }
 
     #[inline]
-    pub fn mutex_lock<T>(mtx: &Mutex<T>) -> MutexGuard<T> {
+    pub fn mutex_lock<T>(mtx: &Mutex<T>) -> MutexGuard<'_, T> {
         mtx.lock()
     }
 }
+
+#[cfg(all(test, unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+mod tests {
+    use crate::platform::posix::{dlopen_lazy, dlsym};
+    use std::os::raw::{c_char, c_void};
+
+    dyn_link! {
+        struct Libs(dlopen_lazy => *mut c_void | dlsym) extern "C" {
+            "libramen-missing.so.1" | "libramen-missing.so" {
+                fn ramen_missing_library_fn() -> ();
+            },
+            // The first name can't be loaded, so this also goes through the fallbacks
+            "libramen-missing.so.2" | "libc.so.6" | "libc.so.7" {
+                fn strlen(s: *const c_char) -> usize;
+                fn ramen_missing_symbol() -> ();
+            },
+        }
+    }
+
+    #[test]
+    fn link_report_lists_what_is_missing() {
+        let libs = unsafe { Libs::_link() };
+        let report = libs.link_report();
+        assert!(!report.is_complete());
+        assert_eq!(report.missing_libraries, ["libramen-missing.so.1"]);
+        assert_eq!(report.missing_symbols, ["ramen_missing_symbol"]);
+        assert_eq!(
+            report.to_string(),
+            "Missing libraries: libramen-missing.so.1; Missing symbols: ramen_missing_symbol",
+        );
+
+        // Missing functions are `None` rather than a panic, and the rest still work
+        unsafe {
+            assert_eq!(libs.strlen(c_string!("ramen").as_ptr().cast()), Some(5));
+            assert_eq!(libs.ramen_missing_symbol(), None);
+            assert_eq!(libs.ramen_missing_library_fn(), None);
+        }
+    }
+}
@@ -2,10 +2,12 @@
 //!
 //! # Features
 //! - `cursor-lock`: Adds the ability to constrain the cursor
-//! to the inner bounds of the window or lock it to the center.
+//!   to the inner bounds of the window or lock it to the center.
+//! - `headless`: Makes the [headless](platform::headless) backend the default
+//!   instead of the native one, for testing without a display server.
 //! - `parking-lot`: Replaces the `std` for synchronization primitives
-//! with the [`parking_lot`](https://crates.io/crates/parking_lot) crate.
-//! Highly recommended, at least for release builds.
+//!   with the [`parking_lot`](https://crates.io/crates/parking_lot) crate.
+//!   Highly recommended, at least for release builds.
 
 #![cfg_attr(feature = "nightly-docs", feature(doc_cfg))]
 #![deny(unused_results)]
@@ -1,7 +1,13 @@
 //! Monitor related stuff!!
 
+use crate::{
+    error::Error,
+    platform::{imp, Backend},
+};
+use std::hash;
+
 macro_rules! dpi_vec2_impl {
-    ($($t_ident:ident ($m1:ident, $m2:ident) $name:literal),* $(,)?) => {
+    ($($t_ident:ident ($m1:ident, $m2:ident: $t_phys:ty) $name:literal),* $(,)?) => {
         $(
             // Type definition
             document!(
@@ -11,7 +17,7 @@ macro_rules! dpi_vec2_impl {
                     #[doc = "Logical"] #[doc = $name] #[doc = "that is scalable to monitor DPI."]
                     Logical(f64, f64),
                     #[doc = "Physical"] #[doc = $name] #[doc = "in absolute values regardless of DPI."]
-                    Physical(u32, u32),
+                    Physical($t_phys, $t_phys),
                 }
             );
 
@@ -40,10 +46,10 @@ macro_rules! dpi_vec2_impl {
                         "If `self` is already physical, no upscaling is done."
                     ),
                     #[inline]
-                    pub fn physical(self, scale: Scale) -> (u32, u32) {
+                    pub fn physical(self, scale: Scale) -> ($t_phys, $t_phys) {
                         // NOTE: `const fn` doesn't have floating point arithmetic yet.
                         match self {
-                            Self::Logical($m1, $m2) => (($m1 * scale) as u32, ($m2 * scale) as u32),
+                            Self::Logical($m1, $m2) => (($m1 * scale) as $t_phys, ($m2 * scale) as $t_phys),
                             Self::Physical($m1, $m2) => ($m1, $m2),
                         }
                     }
@@ -78,6 +84,7 @@ macro_rules! dpi_vec2_impl {
                 );
 
                 // Private implementations
+                #[allow(dead_code)]
                 pub(crate) fn scale_if_logical(self, scale: Scale) -> (f64, f64) {
                     match self {
                         Self::Logical($m1, $m2) => ($m1 * scale, $m2 * scale),
@@ -91,8 +98,178 @@ macro_rules! dpi_vec2_impl {
 
 // This is where the magic happens.
 dpi_vec2_impl! {
-    Point(x, y) "point",
-    Size(width, height) "size",
+    // Points can be left of or above the origin, such as on a monitor to the left of the primary one
+    Point(x, y: i32) "point",
+    Size(width, height: u32) "size",
 }
 
 pub type Scale = f64;
+
+/// A monitor (or any other kind of display) that windows can be shown on.
+///
+/// This is a snapshot of the monitor's settings at the time it was queried, it isn't updated as they change.
+/// Two `Monitor`s are equal if they refer to the same monitor, even if they were queried at different times.
+///
+/// ```no_run
+/// use ramen::monitor::Monitor;
+///
+/// # fn main() -> Result<(), ramen::error::Error> {
+/// for monitor in Monitor::all()? {
+///     println!("{} at {:?}, {:?} @ {:?}Hz", monitor.name(), monitor.position(), monitor.size(), monitor.refresh_rate());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Monitor {
+    pub(crate) backend: Backend,
+    pub(crate) id: u64,
+    pub(crate) name: String,
+    pub(crate) is_primary: bool,
+    pub(crate) position: Point,
+    pub(crate) size: Size,
+    pub(crate) work_area: (Point, Size),
+    pub(crate) scale: Scale,
+    pub(crate) refresh_rate: Option<f64>,
+}
+
+impl Monitor {
+    /// Gets every monitor connected to the system, on the backend picked by [`Backend::detect`].
+    ///
+    /// For a specific backend, use [`Backend::monitors`].
+    pub fn all() -> Result<Vec<Self>, Error> {
+        Backend::detect()?.monitors()
+    }
+
+    /// Gets the primary monitor, on the backend picked by [`Backend::detect`].
+    ///
+    /// Wayland has no such concept, so it's whichever one the compositor listed first there.
+    /// This is `None` only if there are no monitors at all, such as on the headless backend.
+    pub fn primary() -> Result<Option<Self>, Error> {
+        let mut monitors = Self::all()?;
+        let index = monitors.iter().position(|monitor| monitor.is_primary).unwrap_or(0);
+        Ok((index < monitors.len()).then(|| monitors.swap_remove(index)))
+    }
+
+    /// Gets the human-readable name of the monitor, such as `"DP-1"` or `"Generic PnP Monitor"`.
+    ///
+    /// This is for display purposes, it's not guaranteed to be unique.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets whether this is the primary monitor.
+    #[inline]
+    pub fn is_primary(&self) -> bool {
+        self.is_primary
+    }
+
+    /// Gets the position of the top-left corner of the monitor on the desktop.
+    ///
+    /// This is physical, except on Wayland where the desktop is laid out in logical coordinates.
+    #[inline]
+    pub fn position(&self) -> Point {
+        self.position
+    }
+
+    /// Gets the physical size of the monitor, which is its resolution.
+    #[inline]
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Gets the position and size of the area not covered by taskbars, docks and the like.
+    ///
+    /// Wayland doesn't let clients know about those, so it's the whole monitor there.
+    #[inline]
+    pub fn work_area(&self) -> (Point, Size) {
+        self.work_area
+    }
+
+    /// Gets the scale factor of the monitor, which windows on it are scaled by.
+    #[inline]
+    pub fn scale_factor(&self) -> Scale {
+        self.scale
+    }
+
+    /// Gets the refresh rate of the monitor in hertz, if it's known.
+    #[inline]
+    pub fn refresh_rate(&self) -> Option<f64> {
+        self.refresh_rate
+    }
+
+    /// Gets every video mode the monitor supports, for [`Fullscreen::Exclusive`](crate::window::Fullscreen::Exclusive).
+    ///
+    /// Wayland doesn't let clients change video modes, so this is only the current mode there.
+    /// It's empty if the monitor has been disconnected since it was queried.
+    ///
+    /// ```no_run
+    /// use ramen::monitor::Monitor;
+    ///
+    /// # fn main() -> Result<(), ramen::error::Error> {
+    /// if let Some(monitor) = Monitor::primary()? {
+    ///     for mode in monitor.video_modes() {
+    ///         println!("{:?} {}bpp @ {}Hz", mode.size(), mode.bit_depth(), mode.refresh_rate());
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn video_modes(&self) -> Vec<VideoMode> {
+        imp::video_modes(self)
+    }
+}
+
+impl PartialEq for Monitor {
+    fn eq(&self, other: &Self) -> bool {
+        self.backend == other.backend && self.id == other.id
+    }
+}
+
+impl Eq for Monitor {}
+
+impl hash::Hash for Monitor {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.backend.hash(state);
+        self.id.hash(state);
+    }
+}
+
+/// A resolution, bit depth and refresh rate combination a [`Monitor`] can be switched to.
+///
+/// To get these, use [`Monitor::video_modes`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct VideoMode {
+    pub(crate) monitor: Monitor,
+    pub(crate) id: u64, // backend-specific, such as the `RRMode` on X11
+    pub(crate) size: (u32, u32),
+    pub(crate) bit_depth: u16,
+    pub(crate) refresh_rate: f64,
+}
+
+impl VideoMode {
+    /// Gets the monitor this video mode is for.
+    #[inline]
+    pub fn monitor(&self) -> &Monitor {
+        &self.monitor
+    }
+
+    /// Gets the physical size of the video mode, which is its resolution.
+    #[inline]
+    pub fn size(&self) -> Size {
+        Size::Physical(self.size.0, self.size.1)
+    }
+
+    /// Gets the number of bits per pixel.
+    #[inline]
+    pub fn bit_depth(&self) -> u16 {
+        self.bit_depth
+    }
+
+    /// Gets the refresh rate in hertz.
+    #[inline]
+    pub fn refresh_rate(&self) -> f64 {
+        self.refresh_rate
+    }
+}
@@ -10,8 +10,318 @@
 // - The type `WindowRepr` that is callable as `WindowImpl` and is `Send + Sync`
 //
 // - The function `make_window` of type `fn(&WindowBuilder) -> Result<WindowRepr, Error>`
+//
+// Then add a `Backend` variant for it and hook it up in `imp`, which dispatches to the chosen backend.
+// Platforms living outside the crate go through `BackendImpl` & `Backend::Custom` instead.
+
+use crate::{
+    error::Error,
+    monitor::Monitor,
+    window::{WindowBuilder, WindowImpl},
+};
+use std::{env, ffi::OsString, fmt, hash, mem, ptr};
+
+pub mod headless;
 
 #[cfg(windows)]
 pub mod win32;
-#[cfg(windows)]
-pub(crate) use win32 as imp;
+
+#[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+pub(crate) mod posix;
+#[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+pub mod wayland;
+#[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+pub mod x11;
+
+pub(crate) mod imp;
+
+/// The environment variable that overrides backend auto-detection, see [`Backend::detect`].
+pub const BACKEND_ENV_VAR: &str = "RAMEN_BACKEND";
+
+/// A window system implementation that windows can be created with.
+///
+/// Which backends are available depends on the target platform and enabled features.
+/// If one isn't chosen explicitly with [`WindowBuilder::backend`](crate::window::WindowBuilder::backend),
+/// it's picked at runtime by [`Backend::detect`].
+#[derive(Copy, Clone)]
+#[non_exhaustive]
+pub enum Backend {
+    /// No window system at all, see the [`headless`] module.
+    Headless,
+
+    /// The Windows API.
+    #[cfg(windows)]
+    Win32,
+
+    /// The X Window System, through Xlib.
+    #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+    X11,
+
+    /// Wayland, with the xdg-shell protocol.
+    #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+    Wayland,
+
+    /// A platform implemented outside of the crate, see [`BackendImpl`].
+    ///
+    /// These are never picked by [`Backend::detect`], and compare equal only to themselves (by address).
+    Custom(&'static dyn BackendImpl),
+}
+
+impl Backend {
+    /// Every backend available in this build, in order of preference for auto-detection.
+    pub const ALL: &'static [Self] = &[
+        #[cfg(windows)]
+        Self::Win32,
+        #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+        Self::Wayland,
+        #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+        Self::X11,
+        Self::Headless,
+    ];
+
+    /// Gets the lowercase name of the backend, as accepted by the `RAMEN_BACKEND` environment variable.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Headless => "headless",
+            #[cfg(windows)]
+            Self::Win32 => "win32",
+            #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+            Self::X11 => "x11",
+            #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+            Self::Wayland => "wayland",
+            Self::Custom(backend) => backend.name(),
+        }
+    }
+
+    /// Looks up an available backend by its [`name`](Self::name), ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|backend| backend.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Picks the backend to use when one wasn't chosen explicitly.
+    ///
+    /// 1. If the `RAMEN_BACKEND` environment variable is set and not empty, the backend of that
+    ///    [`name`](Self::name). An unknown name or one that's unavailable in this build is an error.
+    /// 2. With the `headless` feature, [`Headless`](Self::Headless).
+    /// 3. On Win32, [`Win32`](Self::Win32).
+    /// 4. On Linux and the BSDs, [`Wayland`](Self::Wayland) if `WAYLAND_DISPLAY` is set
+    ///    and `libwayland-client` can be loaded, otherwise [`X11`](Self::X11) if `DISPLAY` is set.
+    ///    If neither is, there's no display server to connect to, which is an error.
+    pub fn detect() -> Result<Self, Error> {
+        Self::detect_with(|name| env::var_os(name))
+    }
+
+    /// [`detect`](Self::detect), reading environment variables through `var`.
+    fn detect_with(var: impl Fn(&str) -> Option<OsString>) -> Result<Self, Error> {
+        match var(BACKEND_ENV_VAR) {
+            Some(name) if !name.is_empty() => {
+                let name = name.to_string_lossy();
+                Self::from_name(&name)
+                    .ok_or_else(|| Error::from_internal(imp::InternalError::UnknownBackend(name.into_owned())))
+            },
+            _ => Self::native(var),
+        }
+    }
+
+    /// Gets every monitor connected to the system, as seen by this backend.
+    ///
+    /// The headless backend has no monitors, and neither do custom ones.
+    pub fn monitors(self) -> Result<Vec<Monitor>, Error> {
+        imp::monitors(self)
+    }
+
+    #[allow(unreachable_code, unused_variables)]
+    fn native(var: impl Fn(&str) -> Option<OsString>) -> Result<Self, Error> {
+        #[cfg(feature = "headless")]
+        {
+            return Ok(Self::Headless)
+        }
+
+        #[cfg(windows)]
+        {
+            return Ok(Self::Win32)
+        }
+
+        #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+        {
+            let is_set = |name| matches!(var(name), Some(x) if !x.is_empty());
+            if is_set("WAYLAND_DISPLAY") && wayland::api::WAYLAND_CLIENT.link_report().is_complete() {
+                return Ok(Self::Wayland)
+            }
+            if is_set("DISPLAY") {
+                return Ok(Self::X11)
+            }
+            return Err(Error::from_internal(imp::InternalError::NoDisplay))
+        }
+
+        Ok(Self::Headless)
+    }
+}
+
+impl PartialEq for Backend {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            // Comparing fat pointers would also compare vtables, which aren't guaranteed to be unique
+            (Self::Custom(a), Self::Custom(b)) => ptr::eq(*a as *const _ as *const u8, *b as *const _ as *const u8),
+            _ => mem::discriminant(self) == mem::discriminant(other),
+        }
+    }
+}
+
+impl Eq for Backend {}
+
+impl hash::Hash for Backend {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        mem::discriminant(self).hash(state);
+        if let Self::Custom(backend) = self {
+            (*backend as *const _ as *const u8).hash(state);
+        }
+    }
+}
+
+impl fmt::Debug for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Headless => f.write_str("Headless"),
+            #[cfg(windows)]
+            Self::Win32 => f.write_str("Win32"),
+            #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+            Self::X11 => f.write_str("X11"),
+            #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+            Self::Wayland => f.write_str("Wayland"),
+            Self::Custom(backend) => f.debug_tuple("Custom").field(&backend.name()).finish(),
+        }
+    }
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A platform implementation provided from outside the crate, used through [`Backend::Custom`].
+///
+/// Implementing this along with [`WindowImpl`] is all it takes for [`Window`](crate::window::Window),
+/// [`WindowBuilder`] and [`Event`](crate::event::Event) to work on a new platform, such as a console or an
+/// embedded compositor. The builder's settings can be read with its `get_*` functions.
+///
+/// Only the core functions of [`WindowImpl`] have to be written out, the rest have defaults that do nothing.
+///
+/// # Example
+///
+/// ```
+/// use ramen::{
+///     error::Error,
+///     event::Event,
+///     monitor::{Scale, Size},
+///     platform::{Backend, BackendImpl},
+///     window::{Window, WindowBuilder, WindowControls, WindowImpl},
+/// };
+///
+/// struct Console;
+/// struct ConsoleWindow(Size);
+///
+/// impl BackendImpl for Console {
+///     fn name(&self) -> &'static str {
+///         "console"
+///     }
+///
+///     fn make_window(&self, builder: &WindowBuilder) -> Result<Box<dyn WindowImpl>, Error> {
+///         Ok(Box::new(ConsoleWindow(builder.get_inner_size())))
+///     }
+/// }
+///
+/// impl WindowImpl for ConsoleWindow {
+///     fn events(&self) -> &[Event] { &[] }
+///     fn execute(&self, f: &mut dyn FnMut()) { f() }
+///     fn inner_size(&self) -> (Size, Scale) { (self.0, 1.0) }
+///     fn set_controls(&self, _: Option<WindowControls>) {}
+///     fn set_controls_async(&self, _: Option<WindowControls>) {}
+///     # #[cfg(feature = "cursor-lock")]
+///     # fn set_cursor_lock(&self, _: Option<ramen::window::CursorLock>) {}
+///     # #[cfg(feature = "cursor-lock")]
+///     # fn set_cursor_lock_async(&self, _: Option<ramen::window::CursorLock>) {}
+///     fn set_resizable(&self, _: bool) {}
+///     fn set_resizable_async(&self, _: bool) {}
+///     fn set_title(&self, _: &str) {}
+///     fn set_title_async(&self, _: &str) {}
+///     fn set_visible(&self, _: bool) {}
+///     fn set_visible_async(&self, _: bool) {}
+///     fn swap_events(&mut self) {}
+/// }
+///
+/// static CONSOLE: Console = Console;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let window = Window::builder()
+///     .backend(Some(Backend::Custom(&CONSOLE)))
+///     .build()?;
+/// assert_eq!(window.backend().name(), "console");
+/// # Ok(())
+/// # }
+/// ```
+pub trait BackendImpl: Send + Sync {
+    /// Gets the lowercase name of the backend, for display purposes.
+    fn name(&self) -> &'static str;
+
+    /// Creates a window as specified by the builder.
+    ///
+    /// Errors are reported with [`Error::custom`].
+    fn make_window(&self, builder: &WindowBuilder) -> Result<Box<dyn WindowImpl>, Error>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Backend, BACKEND_ENV_VAR};
+    use crate::error::Error;
+    use std::ffi::OsString;
+
+    /// An environment with just the given variables set.
+    fn env<'a>(vars: &'a [(&str, &str)]) -> impl Fn(&str) -> Option<OsString> + 'a {
+        move |name| vars.iter().find(|(key, _)| *key == name).map(|(_, value)| OsString::from(value))
+    }
+
+    fn error_message(result: Result<Backend, Error>) -> String {
+        match result {
+            Ok(backend) => panic!("expected an error, got {:?}", backend),
+            Err(Error::Internal(err)) => err.to_string(),
+        }
+    }
+
+    #[test]
+    fn from_name() {
+        for &backend in Backend::ALL {
+            assert_eq!(Backend::from_name(backend.name()), Some(backend));
+        }
+        assert_eq!(Backend::from_name("HeadLess"), Some(Backend::Headless));
+        assert_eq!(Backend::from_name("nope"), None);
+        assert_eq!(Backend::from_name(""), None);
+    }
+
+    #[test]
+    fn detect_env_override() {
+        let backend = Backend::detect_with(env(&[(BACKEND_ENV_VAR, "Headless")]));
+        assert_eq!(backend.ok(), Some(Backend::Headless));
+
+        let message = error_message(Backend::detect_with(env(&[(BACKEND_ENV_VAR, "nope"), ("DISPLAY", ":0")])));
+        assert!(message.contains("\"nope\""), "{}", message);
+    }
+
+    #[test]
+    fn detect_empty_override_is_ignored() {
+        let backend = Backend::detect_with(env(&[(BACKEND_ENV_VAR, ""), ("DISPLAY", ":0")]));
+        assert_eq!(backend.ok(), Backend::native(env(&[("DISPLAY", ":0")])).ok());
+    }
+
+    #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+    #[cfg(not(feature = "headless"))]
+    #[test]
+    fn detect_needs_a_display() {
+        assert_eq!(Backend::detect_with(env(&[("DISPLAY", ":0")])).ok(), Some(Backend::X11));
+        assert_eq!(Backend::detect_with(env(&[("DISPLAY", ":0"), ("WAYLAND_DISPLAY", "")])).ok(), Some(Backend::X11));
+
+        let message = error_message(Backend::detect_with(env(&[("DISPLAY", ""), ("WAYLAND_DISPLAY", "")])));
+        assert!(message.contains("$DISPLAY"), "{}", message);
+    }
+}
@@ -0,0 +1,587 @@
+//! Headless implementation and API extensions, for running without any display server.
+//!
+//! Windows created with this backend only exist in memory: setters update the stored state, queueing the
+//! [`Resize`], [`Move`], [`Maximize`] and [`Minimize`] events a native backend would get for them.
+//! Anything else has to be pushed with an [`EventInjector`].
+//! This is mostly useful for testing code that drives a [`Window`] without a desktop to show it on.
+//!
+//! The backend is always available: request it with [`WindowBuilder::backend`],
+//! the `RAMEN_BACKEND=headless` environment variable, or make it the default with the `headless` feature.
+//!
+//! [`Window`]: crate::window::Window
+//! [`Resize`]: crate::event::Event::Resize
+//! [`Move`]: crate::event::Event::Move
+//! [`Maximize`]: crate::event::Event::Maximize
+//! [`Minimize`]: crate::event::Event::Minimize
+//! [`WindowBuilder::backend`]: crate::window::WindowBuilder::backend
+//!
+//! # Example
+//!
+//! ```
+//! use ramen::event::{CloseReason, Event};
+//! use ramen::platform::{headless::WindowExt as _, Backend};
+//! use ramen::window::Window;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut window = Window::builder().backend(Some(Backend::Headless)).build()?;
+//! let injector = window.event_injector().unwrap();
+//!
+//! injector.push(Event::CloseRequest(CloseReason::Unknown));
+//! window.swap_events();
+//! assert!(matches!(window.events(), [Event::CloseRequest(_)]));
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{
+    error::Error,
+    event::Event,
+    helpers::sync::{mutex_lock, Mutex},
+    monitor::{Monitor, Point, Scale, Size},
+    platform::imp,
+    window::{self, CursorIcon, Fullscreen, SizeLimits, WindowBuilder, WindowControls, WindowImpl, WindowStyle},
+};
+use std::{fmt, mem, sync::Arc};
+
+#[cfg(feature = "cursor-lock")]
+use crate::window::CursorLock;
+
+/// The initial capacity of the `Vec<Event>` structures
+const EVENT_BUF_INITIAL_SIZE: usize = 512;
+
+/// There's no monitor to be scaled by, so everything is at 100%.
+const SCALE: Scale = 1.0;
+
+/// Nothing can go wrong without an operating system to talk to, so this can't be constructed.
+#[derive(Debug)]
+pub enum InternalError {}
+
+impl std::error::Error for InternalError {}
+impl fmt::Display for InternalError {
+    fn fmt(&self, _f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {}
+    }
+}
+
+pub(crate) struct Window {
+    state: Mutex<WindowState>,
+    event_queue: Arc<Mutex<Vec<Event>>>,
+    event_buffer: Vec<Event>,
+    focused: bool,
+}
+
+pub(crate) type WindowRepr = Window;
+
+/// Everything a native backend would have asked the OS to keep track of.
+struct WindowState {
+    cursor_icon: CursorIcon,
+    #[cfg(feature = "cursor-lock")]
+    cursor_lock: Option<CursorLock>,
+    fullscreen: Option<Box<Fullscreen>>, // boxed to keep headless windows small
+    ime_allowed: bool,
+    ime_position: Point,
+    inner_size: Size,
+    maximized: bool,
+    minimized: bool,
+    outer_position: Point,
+    size_limits: SizeLimits,
+    style: WindowStyle,
+    title: String,
+}
+
+/// A handle for pushing events into a headless [`Window`](crate::window::Window).
+///
+/// Pushed events are buffered exactly like the ones a native backend receives from the OS,
+/// so they show up in [`events`](crate::window::Window::events) after the next
+/// [`swap_events`](crate::window::Window::swap_events).
+///
+/// Injectors can be cloned and sent to other threads, and stay valid after the window is dropped.
+#[derive(Clone)]
+pub struct EventInjector {
+    event_queue: Arc<Mutex<Vec<Event>>>,
+}
+
+impl EventInjector {
+    /// Pushes a single event.
+    pub fn push(&self, event: Event) {
+        let mut lock = mutex_lock(&self.event_queue);
+        lock.push(event);
+        mem::drop(lock);
+    }
+
+    /// Pushes several events, in order.
+    pub fn extend<I>(&self, events: I)
+    where
+        I: IntoIterator<Item = Event>,
+    {
+        let mut lock = mutex_lock(&self.event_queue);
+        lock.extend(events);
+        mem::drop(lock);
+    }
+}
+
+/// Headless specific extensions to the [`Window`](crate::window::Window) API.
+pub trait WindowExt {
+    /// Gets an [`EventInjector`] for pushing events into this window.
+    ///
+    /// Returns `None` if the window wasn't created with [`Backend::Headless`](crate::platform::Backend::Headless).
+    fn event_injector(&self) -> Option<EventInjector>;
+}
+
+impl WindowExt for window::Window {
+    fn event_injector(&self) -> Option<EventInjector> {
+        match &self.inner {
+            imp::WindowRepr::Headless(window) => Some(EventInjector {
+                event_queue: Arc::clone(&window.event_queue),
+            }),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+}
+
+pub(crate) fn make_window(builder: &WindowBuilder) -> Result<WindowRepr, Error> {
+    let mut state = WindowState {
+        cursor_icon: builder.cursor_icon,
+        #[cfg(feature = "cursor-lock")]
+        cursor_lock: builder.cursor_lock,
+        fullscreen: builder.fullscreen.clone().map(Box::new),
+        ime_allowed: false,
+        ime_position: Point::Physical(0, 0),
+        inner_size: builder.inner_size,
+        maximized: false,
+        minimized: false,
+        outer_position: builder.position.unwrap_or(Point::Physical(0, 0)),
+        size_limits: builder.size_limits,
+        style: builder.style.clone(),
+        title: String::from(builder.title.as_ref()),
+    };
+    state.clamp_inner_size();
+    Ok(Window {
+        state: Mutex::new(state),
+        event_queue: Arc::new(Mutex::new(Vec::with_capacity(EVENT_BUF_INITIAL_SIZE))),
+        event_buffer: Vec::with_capacity(EVENT_BUF_INITIAL_SIZE),
+        focused: false,
+    })
+}
+
+impl WindowState {
+    /// Fits the inner size within the size limits, which a native backend would have the OS do.
+    fn clamp_inner_size(&mut self) {
+        let client = self.inner_size.physical(SCALE);
+        let clamped = self.size_limits.clamp(client, SCALE);
+        if clamped != client {
+            self.inner_size = Size::Physical(clamped.0, clamped.1);
+        }
+    }
+}
+
+impl Window {
+    #[inline]
+    fn update<T>(&self, f: impl FnOnce(&mut WindowState) -> T) -> T {
+        let mut lock = mutex_lock(&self.state);
+        let result = f(&mut lock);
+        mem::drop(lock);
+        result
+    }
+
+    #[inline]
+    fn push_event(&self, event: Event) {
+        let mut lock = mutex_lock(&self.event_queue);
+        lock.push(event);
+        mem::drop(lock);
+    }
+
+    /// Runs `f`, then fits the inner size back within the size limits and sends a `Resize` if it changed.
+    fn resize(&self, f: impl FnOnce(&mut WindowState)) {
+        let resized = self.update(|state| {
+            let old = state.inner_size.physical(SCALE);
+            f(state);
+            state.clamp_inner_size();
+            Some(state.inner_size).filter(|size| size.physical(SCALE) != old)
+        });
+        if let Some(inner_size) = resized {
+            self.push_event(Event::Resize((inner_size, SCALE)));
+        }
+    }
+}
+
+impl WindowImpl for Window {
+    #[inline]
+    fn events(&self) -> &[Event] {
+        self.event_buffer.as_slice()
+    }
+
+    #[inline]
+    fn execute(&self, f: &mut dyn FnMut()) {
+        // There's no window thread, so the calling thread is as good as any
+        f()
+    }
+
+    #[inline]
+    fn controls(&self) -> Option<WindowControls> {
+        mutex_lock(&self.state).style.controls.clone()
+    }
+
+    #[inline]
+    fn current_monitor(&self) -> Option<Monitor> {
+        None
+    }
+
+    #[inline]
+    fn inner_size(&self) -> (Size, Scale) {
+        (mutex_lock(&self.state).inner_size, SCALE)
+    }
+
+    #[inline]
+    fn is_borderless(&self) -> bool {
+        mutex_lock(&self.state).style.borderless
+    }
+
+    #[inline]
+    fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    #[inline]
+    fn is_maximized(&self) -> bool {
+        mutex_lock(&self.state).maximized
+    }
+
+    #[inline]
+    fn is_minimized(&self) -> bool {
+        mutex_lock(&self.state).minimized
+    }
+
+    #[inline]
+    fn is_resizable(&self) -> bool {
+        mutex_lock(&self.state).style.resizable
+    }
+
+    #[inline]
+    fn is_visible(&self) -> bool {
+        mutex_lock(&self.state).style.visible
+    }
+
+    #[inline]
+    fn outer_position(&self) -> Point {
+        mutex_lock(&self.state).outer_position
+    }
+
+    #[inline]
+    fn title(&self) -> String {
+        mutex_lock(&self.state).title.clone()
+    }
+
+    #[inline]
+    fn set_aspect_ratio(&self, aspect_ratio: Option<(u32, u32)>) {
+        self.resize(|state| state.size_limits.aspect_ratio = aspect_ratio);
+    }
+
+    #[inline]
+    fn set_aspect_ratio_async(&self, aspect_ratio: Option<(u32, u32)>) {
+        self.set_aspect_ratio(aspect_ratio);
+    }
+
+    #[inline]
+    fn set_borderless(&self, borderless: bool) {
+        self.update(|state| state.style.borderless = borderless);
+    }
+
+    #[inline]
+    fn set_borderless_async(&self, borderless: bool) {
+        self.set_borderless(borderless);
+    }
+
+    #[inline]
+    fn set_controls(&self, controls: Option<WindowControls>) {
+        self.update(|state| state.style.controls = controls);
+    }
+
+    #[inline]
+    fn set_controls_async(&self, controls: Option<WindowControls>) {
+        self.set_controls(controls);
+    }
+
+    #[inline]
+    fn set_cursor_icon(&self, icon: CursorIcon) {
+        self.update(|state| state.cursor_icon = icon);
+    }
+
+    #[inline]
+    fn set_cursor_icon_async(&self, icon: CursorIcon) {
+        self.set_cursor_icon(icon);
+    }
+
+    #[cfg(feature = "cursor-lock")]
+    #[inline]
+    fn set_cursor_lock(&self, mode: Option<CursorLock>) {
+        self.update(|state| state.cursor_lock = mode);
+    }
+
+    #[cfg(feature = "cursor-lock")]
+    #[inline]
+    fn set_cursor_lock_async(&self, mode: Option<CursorLock>) {
+        self.set_cursor_lock(mode);
+    }
+
+    #[inline]
+    fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) {
+        self.update(|state| state.fullscreen = fullscreen.map(Box::new));
+    }
+
+    #[inline]
+    fn set_fullscreen_async(&self, fullscreen: Option<Fullscreen>) {
+        self.set_fullscreen(fullscreen);
+    }
+
+    #[inline]
+    fn set_ime_allowed(&self, allowed: bool) {
+        self.update(|state| state.ime_allowed = allowed);
+    }
+
+    #[inline]
+    fn set_ime_allowed_async(&self, allowed: bool) {
+        self.set_ime_allowed(allowed);
+    }
+
+    #[inline]
+    fn set_ime_position(&self, position: Point) {
+        self.update(|state| state.ime_position = position);
+    }
+
+    #[inline]
+    fn set_ime_position_async(&self, position: Point) {
+        self.set_ime_position(position);
+    }
+
+    #[inline]
+    fn set_inner_size(&self, inner_size: Size) {
+        self.resize(|state| state.inner_size = inner_size);
+    }
+
+    #[inline]
+    fn set_inner_size_async(&self, inner_size: Size) {
+        self.set_inner_size(inner_size);
+    }
+
+    #[inline]
+    fn set_max_inner_size(&self, max_inner_size: Option<Size>) {
+        self.resize(|state| state.size_limits.max = max_inner_size);
+    }
+
+    #[inline]
+    fn set_max_inner_size_async(&self, max_inner_size: Option<Size>) {
+        self.set_max_inner_size(max_inner_size);
+    }
+
+    #[inline]
+    fn set_maximized(&self, maximized: bool) {
+        if self.update(|state| mem::replace(&mut state.maximized, maximized) != maximized) {
+            self.push_event(Event::Maximize(maximized));
+        }
+    }
+
+    #[inline]
+    fn set_maximized_async(&self, maximized: bool) {
+        self.set_maximized(maximized);
+    }
+
+    #[inline]
+    fn set_min_inner_size(&self, min_inner_size: Option<Size>) {
+        self.resize(|state| state.size_limits.min = min_inner_size);
+    }
+
+    #[inline]
+    fn set_min_inner_size_async(&self, min_inner_size: Option<Size>) {
+        self.set_min_inner_size(min_inner_size);
+    }
+
+    #[inline]
+    fn set_minimized(&self, minimized: bool) {
+        if self.update(|state| mem::replace(&mut state.minimized, minimized) != minimized) {
+            self.push_event(Event::Minimize(minimized));
+        }
+    }
+
+    #[inline]
+    fn set_minimized_async(&self, minimized: bool) {
+        self.set_minimized(minimized);
+    }
+
+    #[inline]
+    fn set_outer_position(&self, position: Point) {
+        let old = self.update(|state| mem::replace(&mut state.outer_position, position));
+        if old.physical(SCALE) != position.physical(SCALE) {
+            self.push_event(Event::Move(position));
+        }
+    }
+
+    #[inline]
+    fn set_outer_position_async(&self, position: Point) {
+        self.set_outer_position(position);
+    }
+
+    #[inline]
+    fn set_resizable(&self, resizable: bool) {
+        self.update(|state| state.style.resizable = resizable);
+    }
+
+    #[inline]
+    fn set_resizable_async(&self, resizable: bool) {
+        self.set_resizable(resizable);
+    }
+
+    #[inline]
+    fn set_title(&self, title: &str) {
+        self.update(|state| {
+            state.title.clear();
+            state.title.push_str(title);
+        });
+    }
+
+    #[inline]
+    fn set_title_async(&self, title: &str) {
+        self.set_title(title);
+    }
+
+    #[inline]
+    fn set_visible(&self, visible: bool) {
+        self.update(|state| state.style.visible = visible);
+    }
+
+    #[inline]
+    fn set_visible_async(&self, visible: bool) {
+        self.set_visible(visible);
+    }
+
+    fn swap_events(&mut self) {
+        let mut vec_lock = mutex_lock(&self.event_queue);
+        mem::swap(&mut self.event_buffer, vec_lock.as_mut());
+        vec_lock.clear();
+        mem::drop(vec_lock);
+
+        // Injected events are all there is to go by
+        for event in &self.event_buffer {
+            if let Event::Focus(focused) = *event {
+                self.focused = focused;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WindowExt as _;
+    use crate::{
+        event::{CloseReason, Event},
+        monitor::{Point, Size},
+        platform::Backend,
+        window::{Window, WindowControls},
+    };
+
+    fn window() -> Window {
+        Window::builder()
+            .backend(Some(Backend::Headless))
+            .inner_size(Size::Physical(800, 600))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn injected_events() {
+        let mut window = window();
+        let injector = window.event_injector().unwrap();
+        injector.push(Event::Focus(true));
+        injector.extend(vec![Event::CloseRequest(CloseReason::Unknown), Event::Focus(false)]);
+        assert!(window.events().is_empty());
+
+        window.swap_events();
+        assert!(matches!(
+            window.events(),
+            [Event::Focus(true), Event::CloseRequest(CloseReason::Unknown), Event::Focus(false)],
+        ));
+        assert!(!window.is_focused());
+
+        injector.push(Event::Focus(true));
+        window.swap_events();
+        assert!(matches!(window.events(), [Event::Focus(true)]));
+        assert!(window.is_focused());
+
+        window.swap_events();
+        assert!(window.events().is_empty());
+    }
+
+    #[test]
+    fn setters_and_getters() {
+        let window = window();
+        window.set_title("headless");
+        window.set_resizable(false);
+        window.set_visible(false);
+        window.set_borderless(true);
+        window.set_controls(Some(WindowControls::no_maximize()));
+        window.set_outer_position(Point::Physical(10, 20));
+        window.set_maximized(true);
+        window.set_minimized(true);
+        window.set_inner_size(Size::Physical(640, 480));
+
+        assert_eq!(window.title(), "headless");
+        assert!(!window.is_resizable());
+        assert!(!window.is_visible());
+        assert!(window.is_borderless());
+        assert_eq!(window.controls(), Some(WindowControls::no_maximize()));
+        assert_eq!(window.outer_position().physical(1.0), (10, 20));
+        assert!(window.is_maximized());
+        assert!(window.is_minimized());
+        assert_eq!(window.inner_size().0.physical(1.0), (640, 480));
+    }
+
+    #[test]
+    fn setters_send_events() {
+        let mut window = window();
+        window.set_inner_size(Size::Physical(640, 480));
+        window.set_outer_position(Point::Physical(10, 20));
+        window.set_maximized(true);
+        window.set_minimized(true);
+        window.set_minimized(false);
+        window.swap_events();
+        match window.events() {
+            [
+                Event::Resize((size, _)),
+                Event::Move(position),
+                Event::Maximize(true),
+                Event::Minimize(true),
+                Event::Minimize(false),
+            ] => {
+                assert_eq!(size.physical(1.0), (640, 480));
+                assert_eq!(position.physical(1.0), (10, 20));
+            },
+            events => panic!("unexpected events: {:?}", events),
+        }
+
+        // Nothing changes, so nothing is sent
+        window.set_inner_size(Size::Logical(640.0, 480.0));
+        window.set_outer_position(Point::Physical(10, 20));
+        window.set_maximized(true);
+        window.set_minimized(false);
+        window.swap_events();
+        assert!(window.events().is_empty());
+    }
+
+    #[test]
+    fn size_limits_clamp_inner_size() {
+        let mut window = window();
+        window.set_max_inner_size(Some(Size::Physical(400, 300)));
+        window.swap_events();
+        assert!(matches!(window.events(), [Event::Resize(_)]));
+        assert_eq!(window.inner_size().0.physical(1.0), (400, 300));
+
+        window.set_inner_size(Size::Physical(1000, 100));
+        window.set_min_inner_size(Some(Size::Physical(200, 200)));
+        assert_eq!(window.inner_size().0.physical(1.0), (400, 200));
+
+        window.set_max_inner_size(None);
+        window.set_aspect_ratio(Some((1, 1)));
+        assert_eq!(window.inner_size().0.physical(1.0), (400, 400));
+    }
+}
@@ -0,0 +1,422 @@
+//! Dispatch to whichever [`Backend`] a window was created with.
+
+use super::{headless, Backend, BackendImpl};
+use crate::{
+    error::Error,
+    event::Event,
+    monitor::{Monitor, Point, Scale, Size, VideoMode},
+    window::{CursorIcon, Fullscreen, WindowBuilder, WindowControls, WindowImpl},
+};
+use std::fmt;
+
+#[cfg(feature = "cursor-lock")]
+use crate::window::CursorLock;
+
+#[cfg(windows)]
+use super::win32;
+#[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+use super::x11;
+#[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+use super::wayland;
+
+#[derive(Debug)]
+pub enum InternalError {
+    /// The backend requested with `RAMEN_BACKEND` doesn't exist in this build.
+    UnknownBackend(String),
+
+    /// Auto-detection found neither a Wayland compositor nor an X server to connect to.
+    #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+    NoDisplay,
+
+    /// Anything reported by a `Backend::Custom`.
+    Custom(Box<dyn std::error::Error + Send + Sync>),
+
+    Headless(headless::InternalError),
+    #[cfg(windows)]
+    Win32(win32::InternalError),
+    #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+    X11(x11::InternalError),
+    #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+    Wayland(wayland::InternalError),
+}
+
+impl std::error::Error for InternalError {}
+impl fmt::Display for InternalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownBackend(name) => write!(
+                f,
+                "Unknown or unavailable backend \"{}\" (${})",
+                name,
+                super::BACKEND_ENV_VAR,
+            ),
+            #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+            Self::NoDisplay => write!(
+                f,
+                "No display server found ($WAYLAND_DISPLAY and $DISPLAY are unset, see ${})",
+                super::BACKEND_ENV_VAR,
+            ),
+            Self::Custom(err) => err.fmt(f),
+            Self::Headless(err) => err.fmt(f),
+            #[cfg(windows)]
+            Self::Win32(err) => err.fmt(f),
+            #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+            Self::X11(err) => err.fmt(f),
+            #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+            Self::Wayland(err) => err.fmt(f),
+        }
+    }
+}
+
+macro_rules! internal_error_from {
+    ($($(#[$attr:meta])* $variant:ident($ty:ty)),* $(,)?) => {
+        $(
+            $(#[$attr])*
+            impl From<$ty> for InternalError {
+                #[inline]
+                fn from(err: $ty) -> Self {
+                    Self::$variant(err)
+                }
+            }
+        )*
+    };
+}
+
+internal_error_from! {
+    Headless(headless::InternalError),
+    #[cfg(windows)]
+    Win32(win32::InternalError),
+    #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+    X11(x11::InternalError),
+    #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+    Wayland(wayland::InternalError),
+}
+
+pub(crate) enum WindowRepr {
+    Headless(headless::WindowRepr),
+    #[cfg(windows)]
+    Win32(win32::WindowRepr),
+    #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+    X11(x11::WindowRepr),
+    #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+    Wayland(wayland::WindowRepr),
+    Custom(&'static dyn BackendImpl, Box<dyn WindowImpl>),
+}
+
+impl WindowRepr {
+    pub(crate) fn backend(&self) -> Backend {
+        match self {
+            Self::Headless(_) => Backend::Headless,
+            #[cfg(windows)]
+            Self::Win32(_) => Backend::Win32,
+            #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+            Self::X11(_) => Backend::X11,
+            #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+            Self::Wayland(_) => Backend::Wayland,
+            Self::Custom(backend, _) => Backend::Custom(*backend),
+        }
+    }
+}
+
+pub(crate) fn make_window(builder: &WindowBuilder) -> Result<WindowRepr, Error> {
+    let backend = match builder.backend {
+        Some(backend) => backend,
+        None => Backend::detect()?,
+    };
+    match backend {
+        Backend::Headless => headless::make_window(builder).map(WindowRepr::Headless),
+        #[cfg(windows)]
+        Backend::Win32 => win32::make_window(builder).map(WindowRepr::Win32),
+        #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+        Backend::X11 => x11::make_window(builder).map(WindowRepr::X11),
+        #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+        Backend::Wayland => wayland::make_window(builder).map(WindowRepr::Wayland),
+        Backend::Custom(backend) => backend.make_window(builder).map(|window| WindowRepr::Custom(backend, window)),
+    }
+}
+
+pub(crate) fn monitors(backend: Backend) -> Result<Vec<Monitor>, Error> {
+    match backend {
+        Backend::Headless => Ok(Vec::new()),
+        #[cfg(windows)]
+        Backend::Win32 => win32::monitors(),
+        #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+        Backend::X11 => x11::monitors(),
+        #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+        Backend::Wayland => wayland::monitors(),
+        Backend::Custom(_) => Ok(Vec::new()),
+    }
+}
+
+pub(crate) fn video_modes(monitor: &Monitor) -> Vec<VideoMode> {
+    match monitor.backend {
+        Backend::Headless => Vec::new(),
+        #[cfg(windows)]
+        Backend::Win32 => win32::video_modes(monitor),
+        #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+        Backend::X11 => x11::video_modes(monitor),
+        #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+        Backend::Wayland => wayland::video_modes(monitor),
+        Backend::Custom(_) => Vec::new(),
+    }
+}
+
+/// Calls the same method on whichever window is inside.
+macro_rules! dispatch {
+    ($self:expr, $window:ident => $expr:expr) => {
+        match $self {
+            WindowRepr::Headless($window) => $expr,
+            #[cfg(windows)]
+            WindowRepr::Win32($window) => $expr,
+            #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+            WindowRepr::X11($window) => $expr,
+            #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+            WindowRepr::Wayland($window) => $expr,
+            WindowRepr::Custom(_, $window) => $expr,
+        }
+    };
+}
+
+impl WindowImpl for WindowRepr {
+    #[inline]
+    fn events(&self) -> &[Event] {
+        dispatch!(self, w => w.events())
+    }
+
+    #[inline]
+    fn execute(&self, f: &mut dyn FnMut()) {
+        dispatch!(self, w => w.execute(f))
+    }
+
+    #[inline]
+    fn controls(&self) -> Option<WindowControls> {
+        dispatch!(self, w => w.controls())
+    }
+
+    #[inline]
+    fn current_monitor(&self) -> Option<Monitor> {
+        dispatch!(self, w => w.current_monitor())
+    }
+
+    #[inline]
+    fn inner_size(&self) -> (Size, Scale) {
+        dispatch!(self, w => w.inner_size())
+    }
+
+    #[inline]
+    fn is_borderless(&self) -> bool {
+        dispatch!(self, w => w.is_borderless())
+    }
+
+    #[inline]
+    fn is_focused(&self) -> bool {
+        dispatch!(self, w => w.is_focused())
+    }
+
+    #[inline]
+    fn is_maximized(&self) -> bool {
+        dispatch!(self, w => w.is_maximized())
+    }
+
+    #[inline]
+    fn is_minimized(&self) -> bool {
+        dispatch!(self, w => w.is_minimized())
+    }
+
+    #[inline]
+    fn is_resizable(&self) -> bool {
+        dispatch!(self, w => w.is_resizable())
+    }
+
+    #[inline]
+    fn is_visible(&self) -> bool {
+        dispatch!(self, w => w.is_visible())
+    }
+
+    #[inline]
+    fn outer_position(&self) -> Point {
+        dispatch!(self, w => w.outer_position())
+    }
+
+    #[inline]
+    fn title(&self) -> String {
+        dispatch!(self, w => w.title())
+    }
+
+    #[inline]
+    fn set_aspect_ratio(&self, aspect_ratio: Option<(u32, u32)>) {
+        dispatch!(self, w => w.set_aspect_ratio(aspect_ratio))
+    }
+
+    #[inline]
+    fn set_aspect_ratio_async(&self, aspect_ratio: Option<(u32, u32)>) {
+        dispatch!(self, w => w.set_aspect_ratio_async(aspect_ratio))
+    }
+
+    #[inline]
+    fn set_borderless(&self, borderless: bool) {
+        dispatch!(self, w => w.set_borderless(borderless))
+    }
+
+    #[inline]
+    fn set_borderless_async(&self, borderless: bool) {
+        dispatch!(self, w => w.set_borderless_async(borderless))
+    }
+
+    #[inline]
+    fn set_controls(&self, controls: Option<WindowControls>) {
+        dispatch!(self, w => w.set_controls(controls))
+    }
+
+    #[inline]
+    fn set_controls_async(&self, controls: Option<WindowControls>) {
+        dispatch!(self, w => w.set_controls_async(controls))
+    }
+
+    #[inline]
+    fn set_cursor_icon(&self, icon: CursorIcon) {
+        dispatch!(self, w => w.set_cursor_icon(icon))
+    }
+
+    #[inline]
+    fn set_cursor_icon_async(&self, icon: CursorIcon) {
+        dispatch!(self, w => w.set_cursor_icon_async(icon))
+    }
+
+    #[cfg(feature = "cursor-lock")]
+    #[inline]
+    fn set_cursor_lock(&self, mode: Option<CursorLock>) {
+        dispatch!(self, w => w.set_cursor_lock(mode))
+    }
+
+    #[cfg(feature = "cursor-lock")]
+    #[inline]
+    fn set_cursor_lock_async(&self, mode: Option<CursorLock>) {
+        dispatch!(self, w => w.set_cursor_lock_async(mode))
+    }
+
+    #[inline]
+    fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) {
+        dispatch!(self, w => w.set_fullscreen(fullscreen))
+    }
+
+    #[inline]
+    fn set_fullscreen_async(&self, fullscreen: Option<Fullscreen>) {
+        dispatch!(self, w => w.set_fullscreen_async(fullscreen))
+    }
+
+    #[inline]
+    fn set_ime_allowed(&self, allowed: bool) {
+        dispatch!(self, w => w.set_ime_allowed(allowed))
+    }
+
+    #[inline]
+    fn set_ime_allowed_async(&self, allowed: bool) {
+        dispatch!(self, w => w.set_ime_allowed_async(allowed))
+    }
+
+    #[inline]
+    fn set_ime_position(&self, position: Point) {
+        dispatch!(self, w => w.set_ime_position(position))
+    }
+
+    #[inline]
+    fn set_ime_position_async(&self, position: Point) {
+        dispatch!(self, w => w.set_ime_position_async(position))
+    }
+
+    #[inline]
+    fn set_inner_size(&self, inner_size: Size) {
+        dispatch!(self, w => w.set_inner_size(inner_size))
+    }
+
+    #[inline]
+    fn set_inner_size_async(&self, inner_size: Size) {
+        dispatch!(self, w => w.set_inner_size_async(inner_size))
+    }
+
+    #[inline]
+    fn set_max_inner_size(&self, max_inner_size: Option<Size>) {
+        dispatch!(self, w => w.set_max_inner_size(max_inner_size))
+    }
+
+    #[inline]
+    fn set_max_inner_size_async(&self, max_inner_size: Option<Size>) {
+        dispatch!(self, w => w.set_max_inner_size_async(max_inner_size))
+    }
+
+    #[inline]
+    fn set_maximized(&self, maximized: bool) {
+        dispatch!(self, w => w.set_maximized(maximized))
+    }
+
+    #[inline]
+    fn set_maximized_async(&self, maximized: bool) {
+        dispatch!(self, w => w.set_maximized_async(maximized))
+    }
+
+    #[inline]
+    fn set_min_inner_size(&self, min_inner_size: Option<Size>) {
+        dispatch!(self, w => w.set_min_inner_size(min_inner_size))
+    }
+
+    #[inline]
+    fn set_min_inner_size_async(&self, min_inner_size: Option<Size>) {
+        dispatch!(self, w => w.set_min_inner_size_async(min_inner_size))
+    }
+
+    #[inline]
+    fn set_minimized(&self, minimized: bool) {
+        dispatch!(self, w => w.set_minimized(minimized))
+    }
+
+    #[inline]
+    fn set_minimized_async(&self, minimized: bool) {
+        dispatch!(self, w => w.set_minimized_async(minimized))
+    }
+
+    #[inline]
+    fn set_outer_position(&self, position: Point) {
+        dispatch!(self, w => w.set_outer_position(position))
+    }
+
+    #[inline]
+    fn set_outer_position_async(&self, position: Point) {
+        dispatch!(self, w => w.set_outer_position_async(position))
+    }
+
+    #[inline]
+    fn set_resizable(&self, resizable: bool) {
+        dispatch!(self, w => w.set_resizable(resizable))
+    }
+
+    #[inline]
+    fn set_resizable_async(&self, resizable: bool) {
+        dispatch!(self, w => w.set_resizable_async(resizable))
+    }
+
+    #[inline]
+    fn set_title(&self, title: &str) {
+        dispatch!(self, w => w.set_title(title))
+    }
+
+    #[inline]
+    fn set_title_async(&self, title: &str) {
+        dispatch!(self, w => w.set_title_async(title))
+    }
+
+    #[inline]
+    fn set_visible(&self, visible: bool) {
+        dispatch!(self, w => w.set_visible(visible))
+    }
+
+    #[inline]
+    fn set_visible_async(&self, visible: bool) {
+        dispatch!(self, w => w.set_visible_async(visible))
+    }
+
+    #[inline]
+    fn swap_events(&mut self) {
+        dispatch!(self, w => w.swap_events())
+    }
+}
@@ -0,0 +1,214 @@
+//! Plumbing shared between the POSIX window system backends.
+
+pub(crate) mod xkb;
+
+use crate::helpers::sync::{condvar_notify_all, condvar_wait, mutex_lock, Condvar, Mutex};
+use std::{io, mem, os::raw::{c_char, c_int, c_short, c_ulong, c_void}, thread};
+
+#[repr(C)]
+pub struct pollfd {
+    pub fd: c_int,
+    pub events: c_short,
+    pub revents: c_short,
+}
+pub const POLLIN: c_short = 0x001;
+
+pub const MAP_FAILED: *mut c_void = !0 as *mut c_void;
+pub const MAP_PRIVATE: c_int = 0x02;
+pub const PROT_READ: c_int = 0x1;
+
+pub const RTLD_LAZY: c_int = 0x0001;
+pub const RTLD_LOCAL: c_int = 0;
+
+// The C standard library is always linked by `std`, these are just the bits it doesn't expose.
+extern "C" {
+    pub fn close(fd: c_int) -> c_int;
+    pub fn dlopen(filename: *const c_char, flags: c_int) -> *mut c_void;
+    pub fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+    pub fn mmap(addr: *mut c_void, len: usize, prot: c_int, flags: c_int, fd: c_int, offset: i64) -> *mut c_void;
+    pub fn munmap(addr: *mut c_void, len: usize) -> c_int;
+    pub fn pipe(fds: *mut c_int) -> c_int;
+    pub fn poll(fds: *mut pollfd, nfds: c_ulong, timeout: c_int) -> c_int;
+    pub fn read(fd: c_int, buf: *mut c_void, count: usize) -> isize;
+    pub fn write(fd: c_int, buf: *const c_void, count: usize) -> isize;
+}
+
+/// Opens a shared library for `dyn_link!`, which is never closed.
+#[inline]
+pub unsafe fn dlopen_lazy(name: *const c_char) -> *mut c_void {
+    dlopen(name, RTLD_LAZY | RTLD_LOCAL)
+}
+
+/// Copies `src` into `buffer` with a null terminator, replacing inner nulls like Win32 does.
+pub fn str_to_c_null(src: &str, buffer: &mut Vec<u8>) -> *const c_char {
+    buffer.clear();
+    buffer.reserve(src.len() + 1);
+    buffer.extend(src.bytes().map(|b| if b == 0x00 { b' ' } else { b }));
+    buffer.push(0x00);
+    buffer.as_ptr().cast()
+}
+
+/// Cross-thread message passing to a window thread, in the spirit of `SendMessageW` & `PostMessageW`.
+///
+/// The window thread is expected to sleep in `poll` on both its display connection and
+/// [`wake_fd`](Self::wake_fd), which is readable for as long as there are messages waiting.
+pub(crate) struct MessageQueue<M> {
+    state: Mutex<MessageQueueState<M>>,
+    signal: Condvar,
+    thread_id: thread::ThreadId,
+    wake_pipe: [c_int; 2],
+}
+
+struct MessageQueueState<M> {
+    messages: Vec<M>,
+
+    // Running counts of messages sent & processed, used to wait on synchronous messages
+    sent: u64,
+    processed: u64,
+}
+
+impl<M> MessageQueue<M> {
+    /// Creates a message queue owned by the calling thread, which should be the window thread.
+    pub fn new() -> io::Result<Self> {
+        let mut wake_pipe = [0 as c_int; 2];
+        if unsafe { pipe(wake_pipe.as_mut_ptr()) } != 0 {
+            return Err(io::Error::last_os_error())
+        }
+        Ok(Self {
+            state: Mutex::new(MessageQueueState {
+                messages: Vec::new(),
+                sent: 0,
+                processed: 0,
+            }),
+            signal: Condvar::new(),
+            thread_id: thread::current().id(),
+            wake_pipe,
+        })
+    }
+
+    /// Whether the calling thread is the window thread, in which case waiting on messages would deadlock.
+    #[inline]
+    pub fn is_owner_thread(&self) -> bool {
+        thread::current().id() == self.thread_id
+    }
+
+    /// The file descriptor to poll for incoming messages.
+    #[inline]
+    pub fn wake_fd(&self) -> c_int {
+        self.wake_pipe[0]
+    }
+
+    /// Queues a message without waiting for it to be processed, returning its serial number.
+    pub fn post(&self, message: M) -> u64 {
+        let mut state = mutex_lock(&self.state);
+
+        // Invariant: There's a byte in the pipe if and only if there are messages queued
+        if state.messages.is_empty() {
+            let byte = 0u8;
+            let _ = unsafe { write(self.wake_pipe[1], (&byte) as *const u8 as *const c_void, 1) };
+        }
+        state.messages.push(message);
+        state.sent += 1;
+        state.sent
+    }
+
+    /// Blocks until the message with the given serial number has been processed.
+    pub fn wait(&self, serial: u64) {
+        let mut state = mutex_lock(&self.state);
+        while state.processed < serial {
+            condvar_wait(&self.signal, &mut state);
+        }
+    }
+
+    /// Takes every queued message, to be processed in order and marked with [`mark_processed`](Self::mark_processed).
+    pub fn take(&self) -> Vec<M> {
+        let mut state = mutex_lock(&self.state);
+        if !state.messages.is_empty() {
+            let mut byte = 0u8;
+            let _ = unsafe { read(self.wake_pipe[0], (&mut byte) as *mut u8 as *mut c_void, 1) };
+        }
+        mem::take(&mut state.messages)
+    }
+
+    /// Marks the oldest unprocessed message as processed, waking up anyone waiting on it.
+    pub fn mark_processed(&self) {
+        let mut state = mutex_lock(&self.state);
+        state.processed += 1;
+        condvar_notify_all(&self.signal);
+        mem::drop(state);
+    }
+}
+
+impl<M> Drop for MessageQueue<M> {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = close(self.wake_pipe[0]);
+            let _ = close(self.wake_pipe[1]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{poll, pollfd, MessageQueue, POLLIN};
+    use std::{sync::{mpsc, Arc}, thread};
+
+    /// Whether the wake fd would wake up a window thread right now.
+    fn is_readable(queue: &MessageQueue<u32>) -> bool {
+        let mut fd = pollfd { fd: queue.wake_fd(), events: POLLIN, revents: 0 };
+        unsafe { poll(&mut fd, 1, 0) == 1 && fd.revents & POLLIN != 0 }
+    }
+
+    #[test]
+    fn wake_fd_tracks_queued_messages() {
+        let queue = MessageQueue::new().unwrap();
+        assert!(!is_readable(&queue));
+
+        // Posting doesn't wait on anything, even without a window thread to process messages
+        let _ = queue.post(1);
+        let _ = queue.post(2);
+        assert!(is_readable(&queue));
+
+        assert_eq!(queue.take(), [1, 2]);
+        assert!(!is_readable(&queue));
+        assert!(queue.take().is_empty());
+    }
+
+    #[test]
+    fn sync_post_waits_until_processed() {
+        // The queue belongs to the test thread, which plays the window thread
+        let queue = Arc::new(MessageQueue::new().unwrap());
+        assert!(queue.is_owner_thread());
+
+        let (done_send, done_recv) = mpsc::channel();
+        let sender = {
+            let queue = Arc::clone(&queue);
+            thread::spawn(move || {
+                assert!(!queue.is_owner_thread());
+                let first = queue.post(1); // async, doesn't block the sync one after it
+                let second = queue.post(2);
+                queue.wait(first);
+                done_send.send(first).unwrap();
+                queue.wait(second);
+                done_send.send(second).unwrap();
+            })
+        };
+
+        let mut messages = Vec::new();
+        while messages.len() < 2 {
+            let mut fd = pollfd { fd: queue.wake_fd(), events: POLLIN, revents: 0 };
+            assert_eq!(unsafe { poll(&mut fd, 1, 5000) }, 1, "timed out waiting for messages");
+            messages.extend(queue.take());
+        }
+        assert_eq!(messages, [1, 2]);
+
+        // Only the first message is processed, so the sender has to keep waiting on the second
+        queue.mark_processed();
+        let first = done_recv.recv().unwrap();
+        assert_eq!(done_recv.try_recv(), Err(mpsc::TryRecvError::Empty));
+
+        queue.mark_processed();
+        assert!(done_recv.recv().unwrap() > first);
+        sender.join().unwrap();
+    }
+}
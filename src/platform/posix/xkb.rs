@@ -0,0 +1,321 @@
+//! Bindings to `libxkbcommon`, for keymaps & compose sequences on the backends that don't do it themselves.
+
+#![allow(bad_style, dead_code)]
+
+use super::{dlopen_lazy, dlsym, str_to_c_null};
+use std::{char, env, os::raw::{c_char, c_int, c_void}, ptr};
+
+// Opaque types
+#[doc(hidden)]
+pub enum xkb_compose_state {}
+#[doc(hidden)]
+pub enum xkb_compose_table {}
+#[doc(hidden)]
+pub enum xkb_context {}
+#[doc(hidden)]
+pub enum xkb_keymap {}
+#[doc(hidden)]
+pub enum xkb_state {}
+
+// Typedefs
+pub type xkb_keycode_t = u32;
+pub type xkb_keysym_t = u32;
+pub type xkb_layout_index_t = u32;
+pub type xkb_mod_mask_t = u32;
+
+// Constants
+pub const XKB_CONTEXT_NO_FLAGS: c_int = 0;
+pub const XKB_KEYMAP_FORMAT_TEXT_V1: c_int = 1;
+pub const XKB_KEYMAP_COMPILE_NO_FLAGS: c_int = 0;
+pub const XKB_COMPOSE_COMPILE_NO_FLAGS: c_int = 0;
+pub const XKB_COMPOSE_STATE_NO_FLAGS: c_int = 0;
+pub const XKB_COMPOSE_NOTHING: c_int = 0;
+pub const XKB_COMPOSE_COMPOSING: c_int = 1;
+pub const XKB_COMPOSE_COMPOSED: c_int = 2;
+pub const XKB_COMPOSE_CANCELLED: c_int = 3;
+pub const XKB_COMPOSE_FEED_IGNORED: c_int = 0;
+pub const XKB_COMPOSE_FEED_ACCEPTED: c_int = 1;
+
+// Dynamically loaded imports, see `dyn_link!`
+dyn_link! {
+    pub(crate) struct Xkbcommon(dlopen_lazy => *mut c_void | dlsym) extern "C" static XKBCOMMON {
+        "libxkbcommon.so.0" | "libxkbcommon.so" {
+            // Contexts
+            fn xkb_context_new(flags: c_int) -> *mut xkb_context;
+            fn xkb_context_unref(context: *mut xkb_context) -> ();
+
+            // Keymaps
+            fn xkb_keymap_new_from_string(
+                context: *mut xkb_context,
+                string: *const c_char,
+                format: c_int,
+                flags: c_int,
+            ) -> *mut xkb_keymap;
+            fn xkb_keymap_new_from_buffer(
+                context: *mut xkb_context,
+                buffer: *const c_char,
+                length: usize,
+                format: c_int,
+                flags: c_int,
+            ) -> *mut xkb_keymap;
+            fn xkb_keymap_unref(keymap: *mut xkb_keymap) -> ();
+            fn xkb_keymap_key_get_syms_by_level(
+                keymap: *mut xkb_keymap,
+                key: xkb_keycode_t,
+                layout: xkb_layout_index_t,
+                level: u32,
+                syms_out: *mut *const xkb_keysym_t,
+            ) -> c_int;
+            fn xkb_keymap_key_repeats(keymap: *mut xkb_keymap, key: xkb_keycode_t) -> c_int;
+
+            // Keyboard state
+            fn xkb_state_new(keymap: *mut xkb_keymap) -> *mut xkb_state;
+            fn xkb_state_unref(state: *mut xkb_state) -> ();
+            fn xkb_state_update_mask(
+                state: *mut xkb_state,
+                depressed_mods: xkb_mod_mask_t,
+                latched_mods: xkb_mod_mask_t,
+                locked_mods: xkb_mod_mask_t,
+                depressed_layout: xkb_layout_index_t,
+                latched_layout: xkb_layout_index_t,
+                locked_layout: xkb_layout_index_t,
+            ) -> c_int;
+            fn xkb_state_key_get_layout(state: *mut xkb_state, key: xkb_keycode_t) -> xkb_layout_index_t;
+            fn xkb_state_key_get_one_sym(state: *mut xkb_state, key: xkb_keycode_t) -> xkb_keysym_t;
+            fn xkb_state_key_get_utf32(state: *mut xkb_state, key: xkb_keycode_t) -> u32;
+            fn xkb_keysym_to_utf32(keysym: xkb_keysym_t) -> u32;
+
+            // Compose sequences (dead keys and the like)
+            fn xkb_compose_table_new_from_locale(
+                context: *mut xkb_context,
+                locale: *const c_char,
+                flags: c_int,
+            ) -> *mut xkb_compose_table;
+            fn xkb_compose_table_unref(table: *mut xkb_compose_table) -> ();
+            fn xkb_compose_state_new(table: *mut xkb_compose_table, flags: c_int) -> *mut xkb_compose_state;
+            fn xkb_compose_state_unref(state: *mut xkb_compose_state) -> ();
+            fn xkb_compose_state_feed(state: *mut xkb_compose_state, keysym: xkb_keysym_t) -> c_int;
+            fn xkb_compose_state_reset(state: *mut xkb_compose_state) -> ();
+            fn xkb_compose_state_get_status(state: *mut xkb_compose_state) -> c_int;
+            fn xkb_compose_state_get_one_sym(state: *mut xkb_compose_state) -> xkb_keysym_t;
+        },
+    }
+}
+
+/// A compiled keymap along with the current modifier state, for backends where keymaps are sent as text.
+pub(crate) struct Keymap {
+    context: *mut xkb_context,
+    keymap: *mut xkb_keymap,
+    state: *mut xkb_state,
+}
+
+impl Keymap {
+    /// Compiles a keymap in the XKB text format.
+    /// Returns `None` if it's invalid, or if `libxkbcommon` can't be loaded in the first place.
+    pub unsafe fn from_text(text: &[u8]) -> Option<Self> {
+        if !XKBCOMMON.link_report().is_complete() {
+            return None
+        }
+
+        // Keymaps are usually sent with the null terminator included, which the parser doesn't like
+        let len = text.iter().rposition(|&b| b != 0x00).map_or(0, |i| i + 1);
+
+        let context = xkb_context_new(XKB_CONTEXT_NO_FLAGS);
+        if context.is_null() {
+            return None
+        }
+        let keymap = xkb_keymap_new_from_buffer(
+            context,
+            text.as_ptr().cast(),
+            len,
+            XKB_KEYMAP_FORMAT_TEXT_V1,
+            XKB_KEYMAP_COMPILE_NO_FLAGS,
+        );
+        let state = if keymap.is_null() { ptr::null_mut() } else { xkb_state_new(keymap) };
+        if state.is_null() {
+            if !keymap.is_null() {
+                xkb_keymap_unref(keymap);
+            }
+            xkb_context_unref(context);
+            return None
+        }
+        Some(Self { context, keymap, state })
+    }
+
+    /// Updates the modifiers & layout, as sent by the server.
+    pub unsafe fn update_mask(&mut self, depressed: u32, latched: u32, locked: u32, group: u32) {
+        let _ = xkb_state_update_mask(self.state, depressed, latched, locked, 0, 0, group);
+    }
+
+    /// Gets the keysym for an XKB keycode in the active layout, ignoring modifiers. Zero means there isn't one.
+    pub unsafe fn keysym(&self, keycode: xkb_keycode_t) -> xkb_keysym_t {
+        let layout = xkb_state_key_get_layout(self.state, keycode);
+        let mut syms: *const xkb_keysym_t = ptr::null();
+        if xkb_keymap_key_get_syms_by_level(self.keymap, keycode, layout, 0, &mut syms) > 0 {
+            *syms
+        } else {
+            0
+        }
+    }
+
+    /// Gets the keysym for an XKB keycode with the current modifiers applied.
+    pub unsafe fn modified_keysym(&self, keycode: xkb_keycode_t) -> xkb_keysym_t {
+        xkb_state_key_get_one_sym(self.state, keycode)
+    }
+
+    /// Gets the character the key types with the current modifiers applied, including control characters.
+    pub unsafe fn key_char(&self, keycode: xkb_keycode_t) -> Option<char> {
+        utf32_to_char(xkb_state_key_get_utf32(self.state, keycode))
+    }
+
+    /// Whether holding down the key should repeat it, which isn't the case for modifiers and the like.
+    pub unsafe fn key_repeats(&self, keycode: xkb_keycode_t) -> bool {
+        xkb_keymap_key_repeats(self.keymap, keycode) != 0
+    }
+}
+
+impl Drop for Keymap {
+    fn drop(&mut self) {
+        unsafe {
+            xkb_state_unref(self.state);
+            xkb_keymap_unref(self.keymap);
+            xkb_context_unref(self.context);
+        }
+    }
+}
+
+/// The result of feeding a keysym to [`Compose`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum ComposeStatus {
+    /// The keysym isn't part of a sequence and should be handled as usual.
+    Ignored,
+
+    /// The keysym continued or cancelled a sequence, so nothing should be input.
+    Composing,
+
+    /// A sequence was completed, inputting this keysym instead.
+    Composed(xkb_keysym_t),
+}
+
+/// Compose sequences (dead keys, the Compose key and so on) for the user's locale.
+pub(crate) struct Compose {
+    context: *mut xkb_context,
+    table: *mut xkb_compose_table,
+    state: *mut xkb_compose_state,
+}
+
+impl Compose {
+    /// Loads the compose table for the current locale, if `libxkbcommon` can be loaded and there is one.
+    pub unsafe fn from_locale() -> Option<Self> {
+        if !XKBCOMMON.link_report().is_complete() {
+            return None
+        }
+
+        // Same lookup order as `setlocale`, which we don't want to call as it's process-wide
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .unwrap_or_else(|| String::from("C"));
+
+        let context = xkb_context_new(XKB_CONTEXT_NO_FLAGS);
+        if context.is_null() {
+            return None
+        }
+        let mut buffer = Vec::new();
+        let table = xkb_compose_table_new_from_locale(
+            context,
+            str_to_c_null(&locale, &mut buffer),
+            XKB_COMPOSE_COMPILE_NO_FLAGS,
+        );
+        let state = if table.is_null() { ptr::null_mut() } else { xkb_compose_state_new(table, XKB_COMPOSE_STATE_NO_FLAGS) };
+        if state.is_null() {
+            if !table.is_null() {
+                xkb_compose_table_unref(table);
+            }
+            xkb_context_unref(context);
+            return None
+        }
+        Some(Self { context, table, state })
+    }
+
+    /// Feeds the keysym of a pressed key (with modifiers applied) to the current sequence.
+    pub unsafe fn feed(&mut self, keysym: xkb_keysym_t) -> ComposeStatus {
+        if xkb_compose_state_feed(self.state, keysym) == XKB_COMPOSE_FEED_IGNORED {
+            return ComposeStatus::Ignored
+        }
+        match xkb_compose_state_get_status(self.state) {
+            XKB_COMPOSE_COMPOSED => {
+                let keysym = xkb_compose_state_get_one_sym(self.state);
+                xkb_compose_state_reset(self.state);
+                ComposeStatus::Composed(keysym)
+            },
+            XKB_COMPOSE_NOTHING => ComposeStatus::Ignored,
+            _ => ComposeStatus::Composing,
+        }
+    }
+
+    /// Abandons the current sequence, such as when focus is lost.
+    pub unsafe fn reset(&mut self) {
+        xkb_compose_state_reset(self.state);
+    }
+}
+
+impl Drop for Compose {
+    fn drop(&mut self) {
+        unsafe {
+            xkb_compose_state_unref(self.state);
+            xkb_compose_table_unref(self.table);
+            xkb_context_unref(self.context);
+        }
+    }
+}
+
+/// Gets the character a keysym types, if it types anything.
+pub(crate) unsafe fn keysym_to_char(keysym: xkb_keysym_t) -> Option<char> {
+    if XKBCOMMON.link_report().is_complete() {
+        utf32_to_char(xkb_keysym_to_utf32(keysym))
+    } else {
+        None
+    }
+}
+
+#[inline]
+fn utf32_to_char(utf32: u32) -> Option<char> {
+    // Zero is what `libxkbcommon` returns for "nothing"
+    if utf32 == 0 { None } else { char::from_u32(utf32) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{keysym_to_char, utf32_to_char, XKBCOMMON};
+
+    #[test]
+    fn utf32_conversion() {
+        assert_eq!(utf32_to_char(0x61), Some('a'));
+        assert_eq!(utf32_to_char(0x1F35C), Some('\u{1F35C}'));
+        assert_eq!(utf32_to_char(0), None);
+        assert_eq!(utf32_to_char(0xD800), None);
+    }
+
+    #[test]
+    fn keysym_conversion() {
+        // Nothing to check against without the library, which `keysym_to_char` reports as `None`
+        if !XKBCOMMON.link_report().is_complete() {
+            assert_eq!(unsafe { keysym_to_char(0x61) }, None);
+            return
+        }
+        unsafe {
+            assert_eq!(keysym_to_char(0x61), Some('a')); // XK_a
+            assert_eq!(keysym_to_char(0xE9), Some('é')); // XK_eacute
+            assert_eq!(keysym_to_char(0x0101_F35C), Some('\u{1F35C}')); // Unicode keysym
+            assert_eq!(keysym_to_char(0xFF08), Some('\u{8}')); // XK_BackSpace
+            assert_eq!(keysym_to_char(0xFF0D), Some('\r')); // XK_Return
+
+            // Keys that don't type anything are filtered out
+            assert_eq!(keysym_to_char(0xFFE1), None); // XK_Shift_L
+            assert_eq!(keysym_to_char(0xFFBE), None); // XK_F1
+            assert_eq!(keysym_to_char(0), None); // NoSymbol
+        }
+    }
+}
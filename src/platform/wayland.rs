@@ -0,0 +1,2122 @@
+//! Wayland specific implementations and API extensions.
+//!
+//! Windows are `xdg_toplevel` surfaces from the
+//! [xdg-shell](https://wayland.app/protocols/xdg-shell) protocol.
+//! Wayland only shows surfaces with a buffer attached, so windows are filled with black
+//! (from a `wl_shm` buffer sized to the client area) until there's a way to draw to them.
+//!
+//! Borders are drawn by the compositor, if it supports
+//! [xdg-decoration](https://wayland.app/protocols/xdg-decoration-unstable-v1).
+//! Without it, windows are left to the compositor, which usually means they have no border.
+//!
+//! Cursor icons need the compositor to support
+//! [cursor-shape-v1](https://wayland.app/protocols/cursor-shape-v1),
+//! without it the cursor is left as whatever the compositor last showed.
+//!
+//! The IME needs the compositor to support
+//! [text-input-unstable-v3](https://wayland.app/protocols/text-input-unstable-v3),
+//! without it there are no `ImePreedit` or `ImeCommit` events.
+
+pub(crate) mod api;
+pub(crate) mod protocol;
+
+use api::*;
+use protocol::*;
+use crate::{
+    error::Error,
+    event::{CloseReason, Event, Key, MouseButton},
+    helpers::{sync::{condvar_notify1, condvar_wait, mutex_lock, Condvar, Mutex}, LinkReport, MaybeStatic},
+    monitor::{Monitor, Point, Scale, Size, VideoMode},
+    platform::{
+        posix::{close, mmap, munmap, poll, pollfd, str_to_c_null, xkb, MessageQueue, MAP_FAILED, MAP_PRIVATE, POLLIN, PROT_READ},
+        Backend,
+    },
+    window::{CursorIcon, Fullscreen, SizeLimits, WindowBuilder, WindowControls, WindowImpl, WindowStyle},
+};
+use std::{
+    cell,
+    env,
+    ffi::CStr,
+    fmt,
+    fs,
+    io,
+    mem,
+    ops,
+    os::unix::{fs::OpenOptionsExt, io::AsRawFd},
+    path::PathBuf,
+    process,
+    ptr,
+    slice,
+    sync::{self, atomic::{self, AtomicBool, AtomicUsize}},
+    thread,
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "cursor-lock")]
+use crate::window::CursorLock;
+
+/// The initial capacity of the `Vec<Event>` structures
+///
+/// TODO: This should be bigger than normal if input is enabled
+const EVENT_BUF_INITIAL_SIZE: usize = 512;
+
+/// Key repeat settings until the compositor sends its own, the same as the X server defaults.
+const DEFAULT_REPEAT_DELAY: Duration = Duration::from_millis(660);
+const DEFAULT_REPEAT_INTERVAL: Duration = Duration::from_millis(40);
+
+/// How far `wl_pointer.axis` scrolls for one wheel notch, which is what most compositors send.
+/// Only used for devices without notches, or compositors too old to send `axis_discrete`.
+const AXIS_UNITS_PER_NOTCH: f64 = 10.0;
+
+// Mouse buttons, from `linux/input-event-codes.h`
+const BTN_LEFT: u32 = 0x110;
+const BTN_RIGHT: u32 = 0x111;
+const BTN_MIDDLE: u32 = 0x112;
+const BTN_SIDE: u32 = 0x113;
+const BTN_EXTRA: u32 = 0x114;
+const BTN_FORWARD: u32 = 0x115;
+const BTN_BACK: u32 = 0x116;
+
+#[derive(Debug)]
+pub struct InternalError {
+    code: c_int,
+    context: &'static str,
+    message: String,
+}
+
+impl std::error::Error for InternalError {}
+impl fmt::Display for InternalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (Code {}; {})", self.message.as_str(), self.code, self.context)
+    }
+}
+
+impl InternalError {
+    pub(crate) fn from_io(context: &'static str, err: io::Error) -> Self {
+        Self {
+            code: err.raw_os_error().unwrap_or(0),
+            context,
+            message: err.to_string(),
+        }
+    }
+
+    pub(crate) fn from_link_report(context: &'static str, report: &LinkReport) -> Self {
+        Self {
+            code: 0,
+            context,
+            message: report.to_string(),
+        }
+    }
+
+    pub(crate) fn no_display(context: &'static str) -> Self {
+        Self {
+            code: 0,
+            context,
+            message: String::from("Can't connect to the compositor (is $WAYLAND_DISPLAY set?)"),
+        }
+    }
+
+    pub(crate) fn missing_global(context: &'static str) -> Self {
+        Self {
+            code: 0,
+            context,
+            message: String::from("The compositor doesn't support a required protocol"),
+        }
+    }
+}
+
+pub(crate) struct Window {
+    // guts
+    thread: Option<thread::JoinHandle<()>>,
+
+    // api
+    user_data: Box<cell::UnsafeCell<WindowUserData>>,
+    event_buffer: Vec<Event>,
+}
+unsafe impl Send for Window {}
+unsafe impl Sync for Window {}
+
+pub(crate) type WindowRepr = Window;
+
+/// Requests for the window thread, the equivalent of the custom `WM_USER` messages on Win32.
+enum Message {
+    /// Run arbitrary functions.
+    Execute(*mut &'static mut dyn FnMut()),
+
+    /// Destroy the window and exit the window thread.
+    Destroy,
+
+    /// Get the monitor the surface is on, written to the pointer.
+    GetCurrentMonitor(*mut Option<Monitor>),
+
+    /// Get the `WindowUserDpiData` struct, written to the pointer.
+    GetDpiData(*mut WindowUserDpiData),
+
+    /// Get whether the surface has keyboard focus, written to the pointer.
+    GetFocusState(*mut bool),
+
+    /// Get the window title, written to the pointer.
+    GetTitle(*mut String),
+
+    /// Get the `WindowStyle` struct, written to the pointer.
+    GetWindowStyle(*mut WindowStyle),
+
+    /// Set the aspect ratio the inner size is kept at.
+    SetAspectRatio(Option<(u32, u32)>),
+
+    /// Set whether the window is without a border.
+    SetBorderless(bool),
+
+    /// Update window controls.
+    SetControls(Option<WindowControls>),
+
+    /// Set the cursor icon.
+    SetCursorIcon(CursorIcon),
+
+    /// Set the cursor lock.
+    #[cfg(feature = "cursor-lock")]
+    SetCursorLock(Option<CursorLock>),
+
+    /// Enter, change or leave fullscreen.
+    SetFullscreen(Option<Fullscreen>),
+
+    /// Set whether the IME can be used.
+    SetImeAllowed(bool),
+
+    /// Set the IME candidate window position.
+    SetImePosition(Point),
+
+    /// Set the inner size.
+    SetInnerSize(Size),
+
+    /// Set the largest inner size.
+    SetMaxInnerSize(Option<Size>),
+
+    /// Maximize or restore the window.
+    SetMaximized(bool),
+
+    /// Set the smallest inner size.
+    SetMinInnerSize(Option<Size>),
+
+    /// Minimize the window, which can't be undone by the client.
+    SetMinimized(bool),
+
+    /// Set whether the window is resizable.
+    SetResizable(bool),
+
+    /// Set the window title.
+    SetTitle(String),
+
+    /// Set whether the window is visible.
+    SetVisible(bool),
+}
+
+struct WindowUserData {
+    cursor_icon: CursorIcon,
+    #[cfg(feature = "cursor-lock")]
+    cursor_lock: Option<CursorLock>,
+    event_queue: Mutex<Vec<Event>>,
+    focus_state: bool,
+    ime_allowed: bool,
+    ime_position: Point,
+    is_maximized: AtomicBool,
+    size_limits: SizeLimits,
+    window_style: WindowStyle,
+
+    dpi_data: WindowUserDpiData,
+
+    // State that needs to be re-sent if the toplevel is re-created
+    class_name: MaybeStatic<str>,
+    fullscreen: Option<Fullscreen>,
+    maximized: bool,
+    title: String,
+
+    // Configuration sent with `xdg_toplevel.configure`, applied on `xdg_surface.configure`
+    configured: bool,
+    pending_focus: bool,
+    pending_maximized: bool,
+    pending_size: (i32, i32),
+
+    // Keyboard state - Wayland leaves key repeat to the client, so it's timed in the message loop
+    compose: Option<xkb::Compose>,
+    keymap: Option<xkb::Keymap>,
+    repeat_delay: Duration,
+    repeat_interval: Option<Duration>,
+    repeat_key: Option<(u32, Instant)>,
+
+    // Serial of the last `wl_pointer.enter`, which is needed to set the cursor, while the pointer is over the surface
+    pointer_serial: Option<u32>,
+
+    // Scrolling is sent per axis and applied on `wl_pointer.frame`
+    pending_scroll: (f64, f64),
+    pending_scroll_discrete: Option<(i32, i32)>,
+
+    // Input method state - text-input sends its events in batches, which are applied on `done`
+    text_input_active: bool,
+    pending_commit: Option<String>,
+    pending_preedit: Option<(String, i32, i32)>,
+    preedit_shown: bool,
+
+    // Every output (monitor), and which of them the surface is on - the scale is the highest of those
+    #[allow(clippy::vec_box)] // listener data pointers must stay put
+    outputs: Vec<Box<Output>>,
+    surface_outputs: Vec<*mut wl_proxy>,
+
+    // Wayland state, only accessed from the window thread
+    display: *mut wl_display,
+    registry: *mut wl_proxy,
+    compositor: *mut wl_proxy,
+    shm: *mut wl_proxy,
+    wm_base: *mut wl_proxy,
+    decoration_manager: *mut wl_proxy,
+    seat: *mut wl_proxy,
+    pointer: *mut wl_proxy,
+    cursor_shape_manager: *mut wl_proxy,
+    cursor_shape_device: *mut wl_proxy,
+    relative_pointer_manager: *mut wl_proxy,
+    relative_pointer: *mut wl_proxy,
+    keyboard: *mut wl_proxy,
+    text_input_manager: *mut wl_proxy,
+    text_input: *mut wl_proxy,
+    surface: *mut wl_proxy,
+    xdg_surface: *mut wl_proxy,
+    xdg_toplevel: *mut wl_proxy,
+    decoration: *mut wl_proxy,
+
+    // What's attached to the surface, and its size in pixels - it's only replaced when the size changes
+    buffer: *mut wl_proxy,
+    buffer_size: (i32, i32),
+
+    message_queue: MessageQueue<Message>,
+}
+
+/// A bound `wl_output` global, which is what Wayland calls a monitor.
+///
+/// These are boxed to be their own listener data, as they're also bound without a window to list monitors.
+struct Output {
+    name: u32,
+    proxy: *mut wl_proxy,
+    window: *mut WindowUserData,
+
+    // Whether the window should hear about changes, which excludes the first batch of outputs known at creation
+    announce: bool,
+
+    // Output properties are sent in batches, which are applied on `done`
+    pending: OutputInfo,
+    info: OutputInfo,
+}
+
+#[derive(Clone)]
+struct OutputInfo {
+    name: String,
+    make_model: String,
+    position: (i32, i32),
+    mode: (i32, i32),
+    refresh: i32, // mHz
+    scale: i32,
+}
+
+impl Output {
+    /// Gets the output as a `Monitor`. The global name is used as the ID, which is the same for every client.
+    fn monitor(&self, is_primary: bool) -> Monitor {
+        let info = &self.info;
+        let (x, y) = info.position;
+        let (width, height) = info.mode;
+        let position = Point::Logical(x.into(), y.into());
+        let size = Size::Physical(width.max(0) as u32, height.max(0) as u32);
+        Monitor {
+            backend: Backend::Wayland,
+            id: self.name.into(),
+            // The name is only sent since version 4, before that the make & model are all there is
+            name: if info.name.is_empty() { info.make_model.clone() } else { info.name.clone() },
+            is_primary,
+            position,
+            size,
+            work_area: (position, size),
+            scale: info.scale.into(),
+            refresh_rate: (info.refresh > 0).then(|| f64::from(info.refresh) / 1000.0),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Default)]
+struct WindowUserDpiData {
+    client: (u32, u32),
+    is_logical: bool,
+    scale_factor: f64,
+}
+
+impl WindowUserDpiData {
+    /// Gets the client size in the `Size` convention the window was created with, along with the scale.
+    fn inner_size(&self) -> (Size, Scale) {
+        let (width, height) = self.client;
+        let inner_size = Size::Physical(width, height);
+        if self.is_logical {
+            (inner_size.to_logical(self.scale_factor), self.scale_factor)
+        } else {
+            (inner_size, self.scale_factor)
+        }
+    }
+
+    /// Converts a surface-local position to the `Point` convention the window was created with.
+    fn point(&self, x: f64, y: f64) -> Point {
+        // Surface-local coordinates are already logical
+        let point = Point::Logical(x, y);
+        if self.is_logical {
+            point
+        } else {
+            point.to_physical(self.scale_factor)
+        }
+    }
+}
+
+static REGISTRY_LISTENER: wl_registry_listener = wl_registry_listener {
+    global: registry_global,
+    global_remove: registry_global_remove,
+};
+static SURFACE_LISTENER: wl_surface_listener = wl_surface_listener {
+    enter: surface_enter,
+    leave: surface_leave,
+};
+static OUTPUT_LISTENER: wl_output_listener = wl_output_listener {
+    geometry: output_geometry,
+    mode: output_mode,
+    done: output_done,
+    scale: output_scale,
+    name: output_name,
+    description: output_description,
+};
+static MONITOR_REGISTRY_LISTENER: wl_registry_listener = wl_registry_listener {
+    global: monitor_registry_global,
+    global_remove: monitor_registry_global_remove,
+};
+static SEAT_LISTENER: wl_seat_listener = wl_seat_listener {
+    capabilities: seat_capabilities,
+    name: seat_name,
+};
+static POINTER_LISTENER: wl_pointer_listener = wl_pointer_listener {
+    enter: pointer_enter,
+    leave: pointer_leave,
+    motion: pointer_motion,
+    button: pointer_button,
+    axis: pointer_axis,
+    frame: pointer_frame,
+    axis_source: pointer_axis_source,
+    axis_stop: pointer_axis_stop,
+    axis_discrete: pointer_axis_discrete,
+};
+static RELATIVE_POINTER_LISTENER: zwp_relative_pointer_v1_listener = zwp_relative_pointer_v1_listener {
+    relative_motion: relative_pointer_motion,
+};
+static KEYBOARD_LISTENER: wl_keyboard_listener = wl_keyboard_listener {
+    keymap: keyboard_keymap,
+    enter: keyboard_enter,
+    leave: keyboard_leave,
+    key: keyboard_key,
+    modifiers: keyboard_modifiers,
+    repeat_info: keyboard_repeat_info,
+};
+static TEXT_INPUT_LISTENER: zwp_text_input_v3_listener = zwp_text_input_v3_listener {
+    enter: text_input_enter,
+    leave: text_input_leave,
+    preedit_string: text_input_preedit_string,
+    commit_string: text_input_commit_string,
+    delete_surrounding_text: text_input_delete_surrounding_text,
+    done: text_input_done,
+};
+static WM_BASE_LISTENER: xdg_wm_base_listener = xdg_wm_base_listener {
+    ping: wm_base_ping,
+};
+static XDG_SURFACE_LISTENER: xdg_surface_listener = xdg_surface_listener {
+    configure: xdg_surface_configure,
+};
+static XDG_TOPLEVEL_LISTENER: xdg_toplevel_listener = xdg_toplevel_listener {
+    configure: xdg_toplevel_configure,
+    close: xdg_toplevel_close,
+};
+
+pub(crate) fn make_window(builder: &WindowBuilder) -> Result<WindowRepr, Error> {
+    // Condvar & mutex pair for receiving the `Result<WindowRepr, Error>` from spawned thread
+    let signal = sync::Arc::new((Mutex::<Option<Result<WindowRepr, Error>>>::new(None), Condvar::new()));
+
+    let builder = builder.clone();
+    let cond_pair = sync::Arc::clone(&signal);
+    let thread_builder = thread::Builder::new()
+        .name(format!("Window Thread (Class \"{}\")", builder.class_name.as_ref()));
+    let window_thread = thread_builder.spawn(move || unsafe {
+        let result = create_window(&builder);
+        let user_data_ptr = result.as_ref().ok().map(|window| window.user_data.get());
+
+        // Yield window struct, signal outer function
+        // NOTE: For future developments, do not insert panics before this,
+        // or the waiting condvar never gets anything and thus the outer fn never returns
+        let (mutex, condvar) = &*cond_pair;
+        let mut lock = mutex_lock(mutex);
+        *lock = Some(result);
+        condvar_notify1(condvar);
+        mem::drop(lock);
+
+        // Release condvar + mutex pair so the `Arc` contents are deallocated once the outer fn returns
+        mem::drop(cond_pair);
+
+        // Run message loop until exit, if the window was created
+        if let Some(user_data_ptr) = user_data_ptr {
+            let user_data = &mut *user_data_ptr;
+            message_loop(user_data);
+            destroy_window(user_data);
+        }
+    }).expect("Failed to spawn window thread");
+
+    // Wait until the thread is done creating the window or notifying us why it couldn't do that
+    let (mutex, condvar) = &*signal;
+    let mut lock = mutex_lock(mutex);
+    loop {
+        if let Some(result) = lock.take() {
+            break result.map(|mut window| {
+                window.thread = Some(window_thread);
+                window
+            })
+        } else {
+            condvar_wait(condvar, &mut lock);
+        }
+    }
+}
+
+/// Creates the window on the calling thread, which becomes the window thread.
+unsafe fn create_window(builder: &WindowBuilder) -> Result<Window, Error> {
+    let report = WAYLAND_CLIENT.link_report();
+    if !report.is_complete() {
+        return Err(Error::from_internal(InternalError::from_link_report("Failed to load libwayland-client.", report)))
+    }
+
+    let display = wl_display_connect(ptr::null());
+    if display.is_null() {
+        return Err(Error::from_internal(InternalError::no_display("wl_display_connect returned NULL.")))
+    }
+
+    let message_queue = match MessageQueue::new() {
+        Ok(queue) => queue,
+        Err(err) => {
+            wl_display_disconnect(display);
+            return Err(Error::from_internal(InternalError::from_io("pipe failed.", err)))
+        },
+    };
+
+    // Surfaces aren't scaled until they're shown on an output, which sends a `ScaleFactor` if needed
+    let scale_factor = 1.0;
+    let (width, height) = builder.size_limits.clamp(builder.inner_size.physical(scale_factor), scale_factor);
+
+    // Listeners need a stable address to point to, so the user data is boxed before binding anything
+    let user_data = Box::new(cell::UnsafeCell::new(WindowUserData {
+        cursor_icon: builder.cursor_icon,
+        #[cfg(feature = "cursor-lock")]
+        cursor_lock: builder.cursor_lock,
+        event_queue: Mutex::new(Vec::with_capacity(EVENT_BUF_INITIAL_SIZE)),
+        focus_state: false,
+        ime_allowed: false,
+        ime_position: Point::Physical(0, 0),
+        is_maximized: AtomicBool::new(false),
+        size_limits: builder.size_limits,
+        window_style: builder.style.clone(),
+
+        dpi_data: WindowUserDpiData {
+            client: (width, height),
+            is_logical: matches!(builder.inner_size, Size::Logical(..)),
+            scale_factor,
+        },
+
+        class_name: builder.class_name.clone(),
+        fullscreen: builder.fullscreen.clone(),
+        maximized: false,
+        title: String::from(builder.title.as_ref()),
+
+        configured: false,
+        pending_focus: false,
+        pending_maximized: false,
+        pending_size: (0, 0),
+
+        compose: xkb::Compose::from_locale(),
+        keymap: None,
+        repeat_delay: DEFAULT_REPEAT_DELAY,
+        repeat_interval: Some(DEFAULT_REPEAT_INTERVAL),
+        repeat_key: None,
+
+        pointer_serial: None,
+
+        pending_scroll: (0.0, 0.0),
+        pending_scroll_discrete: None,
+
+        text_input_active: false,
+        pending_commit: None,
+        pending_preedit: None,
+        preedit_shown: false,
+
+        outputs: Vec::new(),
+        surface_outputs: Vec::new(),
+
+        display,
+        registry: ptr::null_mut(),
+        compositor: ptr::null_mut(),
+        shm: ptr::null_mut(),
+        wm_base: ptr::null_mut(),
+        decoration_manager: ptr::null_mut(),
+        seat: ptr::null_mut(),
+        pointer: ptr::null_mut(),
+        cursor_shape_manager: ptr::null_mut(),
+        cursor_shape_device: ptr::null_mut(),
+        relative_pointer_manager: ptr::null_mut(),
+        relative_pointer: ptr::null_mut(),
+        keyboard: ptr::null_mut(),
+        text_input_manager: ptr::null_mut(),
+        text_input: ptr::null_mut(),
+        surface: ptr::null_mut(),
+        xdg_surface: ptr::null_mut(),
+        xdg_toplevel: ptr::null_mut(),
+        decoration: ptr::null_mut(),
+
+        buffer: ptr::null_mut(),
+        buffer_size: (0, 0),
+
+        message_queue,
+    }));
+    let user_data_ptr = user_data.get();
+    let data = &mut *user_data_ptr;
+
+    // Bind globals (in `registry_global`) and wait for the compositor to tell us about all of them
+    data.registry = wl_display_get_registry(display);
+    let _ = wl_proxy_add_listener(data.registry, (&REGISTRY_LISTENER) as *const _ as *const c_void, user_data_ptr.cast());
+    if wl_display_roundtrip(display) == -1 {
+        let err = InternalError::from_io("wl_display_roundtrip failed.", io::Error::from_raw_os_error(wl_display_get_error(display)));
+        destroy_window(data);
+        return Err(Error::from_internal(err))
+    }
+    if data.compositor.is_null() || data.shm.is_null() || data.wm_base.is_null() {
+        destroy_window(data);
+        return Err(Error::from_internal(InternalError::missing_global("Missing wl_compositor, wl_shm or xdg_wm_base.")))
+    }
+
+    // Both globals can come in either order, so this waits until they're all known
+    if !data.text_input_manager.is_null() && !data.seat.is_null() {
+        data.text_input = zwp_text_input_manager_v3_get_text_input(data.text_input_manager, data.seat);
+        let _ = wl_proxy_add_listener(data.text_input, (&TEXT_INPUT_LISTENER) as *const _ as *const c_void, user_data_ptr.cast());
+    }
+
+    data.surface = wl_compositor_create_surface(data.compositor);
+    let _ = wl_proxy_add_listener(data.surface, (&SURFACE_LISTENER) as *const _ as *const c_void, user_data_ptr.cast());
+    if builder.style.visible {
+        map_toplevel(data);
+    }
+    let _ = wl_display_flush(display);
+
+    Ok(Window {
+        thread: None,
+        user_data,
+        event_buffer: Vec::with_capacity(EVENT_BUF_INITIAL_SIZE),
+    })
+}
+
+/// Destroys every protocol object and disconnects. Also used to clean up after failed creation.
+unsafe fn destroy_window(user_data: &mut WindowUserData) {
+    unmap_toplevel(user_data);
+    if !user_data.surface.is_null() {
+        wl_surface_destroy(user_data.surface);
+    }
+    if !user_data.text_input.is_null() {
+        zwp_text_input_v3_destroy(user_data.text_input);
+    }
+    if !user_data.text_input_manager.is_null() {
+        zwp_text_input_manager_v3_destroy(user_data.text_input_manager);
+    }
+    release_pointer(user_data);
+    if !user_data.cursor_shape_manager.is_null() {
+        wp_cursor_shape_manager_v1_destroy(user_data.cursor_shape_manager);
+    }
+    if !user_data.relative_pointer_manager.is_null() {
+        zwp_relative_pointer_manager_v1_destroy(user_data.relative_pointer_manager);
+    }
+    if !user_data.keyboard.is_null() {
+        wl_keyboard_release(user_data.keyboard);
+    }
+    if !user_data.seat.is_null() {
+        wl_seat_release(user_data.seat);
+    }
+    user_data.keymap = None;
+    user_data.compose = None;
+    if !user_data.decoration_manager.is_null() {
+        zxdg_decoration_manager_v1_destroy(user_data.decoration_manager);
+    }
+    if !user_data.wm_base.is_null() {
+        xdg_wm_base_destroy(user_data.wm_base);
+    }
+    for output in user_data.outputs.drain(..) {
+        wl_output_release(output.proxy);
+    }
+    for proxy in &[user_data.shm, user_data.compositor, user_data.registry] {
+        if !proxy.is_null() {
+            wl_proxy_destroy(*proxy);
+        }
+    }
+    wl_display_disconnect(user_data.display);
+}
+
+/// Gives the surface the `xdg_toplevel` role, which (eventually) makes it appear on screen.
+unsafe fn map_toplevel(user_data: &mut WindowUserData) {
+    let user_data_ptr = (user_data as *mut WindowUserData).cast::<c_void>();
+    user_data.xdg_surface = xdg_wm_base_get_xdg_surface(user_data.wm_base, user_data.surface);
+    let _ = wl_proxy_add_listener(user_data.xdg_surface, (&XDG_SURFACE_LISTENER) as *const _ as *const c_void, user_data_ptr);
+    user_data.xdg_toplevel = xdg_surface_get_toplevel(user_data.xdg_surface);
+    let _ = wl_proxy_add_listener(user_data.xdg_toplevel, (&XDG_TOPLEVEL_LISTENER) as *const _ as *const c_void, user_data_ptr);
+
+    let mut buffer = Vec::new();
+    xdg_toplevel_set_title(user_data.xdg_toplevel, str_to_c_null(&user_data.title, &mut buffer));
+    xdg_toplevel_set_app_id(user_data.xdg_toplevel, str_to_c_null(&user_data.class_name, &mut buffer));
+    if !user_data.decoration_manager.is_null() {
+        user_data.decoration =
+            zxdg_decoration_manager_v1_get_toplevel_decoration(user_data.decoration_manager, user_data.xdg_toplevel);
+        update_decoration(user_data);
+    }
+    update_size_limits(user_data);
+    if user_data.fullscreen.is_some() {
+        update_fullscreen(user_data);
+    }
+    if user_data.maximized {
+        xdg_toplevel_set_maximized(user_data.xdg_toplevel);
+    }
+
+    // The initial commit (without a buffer) asks the compositor for the first configure
+    user_data.configured = false;
+    wl_surface_commit(user_data.surface);
+}
+
+/// Removes the `xdg_toplevel` role from the surface, which is how windows are hidden on Wayland.
+unsafe fn unmap_toplevel(user_data: &mut WindowUserData) {
+    if !user_data.xdg_toplevel.is_null() {
+        wl_surface_attach(user_data.surface, ptr::null_mut(), 0, 0);
+        wl_surface_commit(user_data.surface);
+        if !user_data.decoration.is_null() {
+            // Has to go before the toplevel it decorates
+            zxdg_toplevel_decoration_v1_destroy(user_data.decoration);
+            user_data.decoration = ptr::null_mut();
+        }
+        xdg_toplevel_destroy(user_data.xdg_toplevel);
+        xdg_surface_destroy(user_data.xdg_surface);
+        user_data.xdg_toplevel = ptr::null_mut();
+        user_data.xdg_surface = ptr::null_mut();
+    }
+    if !user_data.buffer.is_null() {
+        wl_buffer_destroy(user_data.buffer);
+        user_data.buffer = ptr::null_mut();
+    }
+}
+
+/// Asks the compositor for a border, or for none at all if the window is borderless.
+/// Client-side decorations are how xdg-decoration says no border, since we don't draw any of our own.
+unsafe fn update_decoration(user_data: &mut WindowUserData) {
+    if user_data.decoration.is_null() {
+        return
+    }
+    let mode = if user_data.window_style.borderless {
+        ZXDG_TOPLEVEL_DECORATION_V1_MODE_CLIENT_SIDE
+    } else {
+        ZXDG_TOPLEVEL_DECORATION_V1_MODE_SERVER_SIDE
+    };
+    zxdg_toplevel_decoration_v1_set_mode(user_data.decoration, mode);
+}
+
+/// Applies pending surface state, attaching a buffer the size of the client area if it changed.
+/// Buffers can only be attached once the toplevel has been configured, until then this only commits state.
+unsafe fn commit_surface(user_data: &mut WindowUserData) {
+    if user_data.xdg_toplevel.is_null() {
+        return
+    }
+    if user_data.configured {
+        // Buffers must be a multiple of the buffer scale, so odd physical sizes are rounded up
+        let scale = user_data.dpi_data.scale_factor as i32;
+        let round = |x: u32| {
+            let x = x.max(1).min(i32::MAX as u32) as i32;
+            x.saturating_add((scale - x % scale) % scale)
+        };
+        let (width, height) = user_data.dpi_data.client;
+        let size = (round(width), round(height));
+        if user_data.buffer.is_null() || size != user_data.buffer_size {
+            // On failure the old buffer stays, which is at worst the wrong size
+            if let Ok(buffer) = create_buffer(user_data.shm, size) {
+                wl_surface_attach(user_data.surface, buffer, 0, 0);
+                wl_surface_damage(user_data.surface, 0, 0, i32::MAX, i32::MAX);
+
+                // The contents are never written to, so it's fine for the compositor to still be reading them
+                if !user_data.buffer.is_null() {
+                    wl_buffer_destroy(user_data.buffer);
+                }
+                user_data.buffer = buffer;
+                user_data.buffer_size = size;
+            }
+        }
+    }
+    wl_surface_commit(user_data.surface);
+}
+
+/// Creates a black buffer of the given size in pixels.
+unsafe fn create_buffer(shm: *mut wl_proxy, (width, height): (i32, i32)) -> io::Result<*mut wl_proxy> {
+    let stride = width.checked_mul(4);
+    let size = stride.and_then(|stride| stride.checked_mul(height));
+    let (stride, size) = match (stride, size) {
+        (Some(stride), Some(size)) => (stride, size),
+        _ => return Err(io::Error::new(io::ErrorKind::InvalidInput, "buffer too large")),
+    };
+
+    // A new file is all zeroes, which is black in XRGB8888, so it never has to be mapped on our side
+    let file = shared_memory_file(size as u64)?;
+    let pool = wl_shm_create_pool(shm, file.as_raw_fd(), size);
+    let buffer = wl_shm_pool_create_buffer(pool, 0, width, height, stride, WL_SHM_FORMAT_XRGB8888);
+
+    // The memory stays around for as long as the buffer does, `libwayland-client` already sent a copy of the fd
+    wl_shm_pool_destroy(pool);
+    Ok(buffer)
+}
+
+/// Creates a file to share memory with the compositor through, which is deleted as soon as it's open.
+/// `memfd_create` would do this better, but isn't on every platform this backend builds for.
+fn shared_memory_file(size: u64) -> io::Result<fs::File> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let dir = env::var_os("XDG_RUNTIME_DIR").map_or_else(env::temp_dir, PathBuf::from);
+    let name = format!("ramen-shm-{}-{}", process::id(), COUNTER.fetch_add(1, atomic::Ordering::Relaxed));
+    let path = dir.join(name);
+    let file = fs::OpenOptions::new().read(true).write(true).create_new(true).mode(0o600).open(&path)?;
+    let _ = fs::remove_file(&path);
+    file.set_len(size)?;
+    Ok(file)
+}
+
+/// Asks the compositor to make the toplevel fullscreen or not, on the requested monitor if it's still around.
+/// Video modes can't be changed on Wayland, so `Fullscreen::Exclusive` only uses the monitor.
+/// Without a monitor, the compositor picks one, which is usually the one the surface is on.
+unsafe fn update_fullscreen(user_data: &mut WindowUserData) {
+    if user_data.xdg_toplevel.is_null() {
+        return
+    }
+    match &user_data.fullscreen {
+        Some(fullscreen) => {
+            let monitor = match fullscreen {
+                Fullscreen::Borderless(monitor) => monitor.as_ref(),
+                Fullscreen::Exclusive(mode) => Some(&mode.monitor),
+            };
+            let output = monitor
+                .and_then(|monitor| user_data.outputs.iter().find(|output| u64::from(output.name) == monitor.id))
+                .map_or(ptr::null_mut(), |output| output.proxy);
+            xdg_toplevel_set_fullscreen(user_data.xdg_toplevel, output);
+        },
+        None => xdg_toplevel_unset_fullscreen(user_data.xdg_toplevel),
+    }
+}
+
+/// There's no "resizable" flag in xdg-shell, but setting the minimum and maximum size does the trick.
+/// Like every other piece of surface state, this is applied with the next `wl_surface.commit`.
+unsafe fn update_size_limits(user_data: &mut WindowUserData) {
+    if user_data.xdg_toplevel.is_null() {
+        return
+    }
+
+    // Sizes in xdg-shell are surface-local, which is logical, and zero means no limit
+    let scale = user_data.dpi_data.scale_factor;
+    let logical = |size: Option<Size>| {
+        size.map_or((0, 0), |size| {
+            let (width, height) = size.logical(scale);
+            (width.round() as i32, height.round() as i32)
+        })
+    };
+    let (min, max) = if user_data.window_style.resizable {
+        (logical(user_data.size_limits.min), logical(user_data.size_limits.max))
+    } else {
+        let (width, height) = user_data.dpi_data.client;
+        let client = logical(Some(Size::Physical(width, height)));
+        (client, client)
+    };
+    xdg_toplevel_set_min_size(user_data.xdg_toplevel, min.0, min.1);
+    xdg_toplevel_set_max_size(user_data.xdg_toplevel, max.0, max.1);
+}
+
+/// Replaces the size limits, fitting the surface into them.
+/// The aspect ratio isn't something xdg-shell knows about, so it's only kept by resizing in `configure`.
+unsafe fn set_size_limits(user_data: &mut WindowUserData, size_limits: SizeLimits) {
+    user_data.size_limits = size_limits;
+    update_size_limits(user_data);
+    if user_data.fullscreen.is_none() {
+        let client = size_limits.clamp(user_data.dpi_data.client, user_data.dpi_data.scale_factor);
+        if client != user_data.dpi_data.client {
+            user_data.dpi_data.client = client;
+            push_event(user_data, Event::Resize(user_data.dpi_data.inner_size()));
+        }
+    }
+    commit_surface(user_data);
+}
+
+#[inline]
+fn push_event(user_data: &mut WindowUserData, event: Event) {
+    let mut lock = mutex_lock(&user_data.event_queue);
+    lock.push(event);
+    mem::drop(lock);
+}
+
+unsafe extern "C" fn registry_global(
+    data: *mut c_void,
+    registry: *mut wl_proxy,
+    name: u32,
+    interface: *const c_char,
+    version: u32,
+) {
+    let user_data = &mut *(data as *mut WindowUserData);
+    match CStr::from_ptr(interface).to_bytes() {
+        b"wl_compositor" if user_data.compositor.is_null() => {
+            let version = version.min(wl_compositor_interface.version as u32);
+            user_data.compositor = wl_registry_bind(registry, name, &wl_compositor_interface, version);
+        },
+        b"wl_shm" if user_data.shm.is_null() => {
+            let version = version.min(wl_shm_interface.version as u32);
+            user_data.shm = wl_registry_bind(registry, name, &wl_shm_interface, version);
+        },
+        b"xdg_wm_base" if user_data.wm_base.is_null() => {
+            let version = version.min(xdg_wm_base_interface.version as u32);
+            user_data.wm_base = wl_registry_bind(registry, name, &xdg_wm_base_interface, version);
+            let _ = wl_proxy_add_listener(user_data.wm_base, (&WM_BASE_LISTENER) as *const _ as *const c_void, data);
+        },
+        b"zxdg_decoration_manager_v1" if user_data.decoration_manager.is_null() => {
+            let version = version.min(zxdg_decoration_manager_v1_interface.version as u32);
+            user_data.decoration_manager =
+                wl_registry_bind(registry, name, &zxdg_decoration_manager_v1_interface, version);
+        },
+        b"wl_seat" if user_data.seat.is_null() => {
+            // Only the first seat is used, which is the only one on pretty much every desktop
+            let version = version.min(wl_seat_interface.version as u32);
+            user_data.seat = wl_registry_bind(registry, name, &wl_seat_interface, version);
+            let _ = wl_proxy_add_listener(user_data.seat, (&SEAT_LISTENER) as *const _ as *const c_void, data);
+        },
+        b"wl_output" => {
+            let mut output = bind_output(registry, name, version, user_data);
+            // The surface is created after the initial round-trip, so this is a newly plugged in monitor
+            output.announce = !user_data.surface.is_null();
+            user_data.outputs.push(output);
+        },
+        b"wp_cursor_shape_manager_v1" if user_data.cursor_shape_manager.is_null() => {
+            let version = version.min(wp_cursor_shape_manager_v1_interface.version as u32);
+            user_data.cursor_shape_manager = wl_registry_bind(registry, name, &wp_cursor_shape_manager_v1_interface, version);
+        },
+        b"zwp_relative_pointer_manager_v1" if user_data.relative_pointer_manager.is_null() => {
+            let version = version.min(zwp_relative_pointer_manager_v1_interface.version as u32);
+            user_data.relative_pointer_manager =
+                wl_registry_bind(registry, name, &zwp_relative_pointer_manager_v1_interface, version);
+        },
+        b"zwp_text_input_manager_v3" if user_data.text_input_manager.is_null() => {
+            let version = version.min(zwp_text_input_manager_v3_interface.version as u32);
+            user_data.text_input_manager = wl_registry_bind(registry, name, &zwp_text_input_manager_v3_interface, version);
+        },
+        _ => (),
+    }
+}
+
+unsafe extern "C" fn registry_global_remove(data: *mut c_void, _registry: *mut wl_proxy, name: u32) {
+    // Outputs come and go with monitors being plugged in, the other globals we use are never removed
+    let user_data = &mut *(data as *mut WindowUserData);
+    if let Some(index) = user_data.outputs.iter().position(|output| output.name == name) {
+        let output = user_data.outputs.swap_remove(index);
+        user_data.surface_outputs.retain(|&proxy| proxy != output.proxy);
+        wl_output_release(output.proxy);
+        update_scale_factor(user_data);
+        push_event(user_data, Event::MonitorsChanged);
+    }
+}
+
+/// Binds an output, for a window if `window` isn't null.
+unsafe fn bind_output(registry: *mut wl_proxy, name: u32, version: u32, window: *mut WindowUserData) -> Box<Output> {
+    let version = version.min(wl_output_interface.version as u32);
+    let info = OutputInfo {
+        name: String::new(),
+        make_model: String::new(),
+        position: (0, 0),
+        mode: (0, 0),
+        refresh: 0,
+        scale: 1,
+    };
+    let mut output = Box::new(Output {
+        name,
+        proxy: wl_registry_bind(registry, name, &wl_output_interface, version),
+        window,
+        announce: false,
+        pending: info.clone(),
+        info,
+    });
+    let data = (&mut *output) as *mut Output as *mut c_void;
+    let _ = wl_proxy_add_listener(output.proxy, (&OUTPUT_LISTENER) as *const _ as *const c_void, data);
+    output
+}
+
+pub(crate) fn monitors() -> Result<Vec<Monitor>, Error> {
+    with_outputs(|outputs| outputs.iter().enumerate().map(|(i, output)| output.monitor(i == 0)).collect())
+}
+
+/// Gets the current video mode of a monitor, as it's the only one there is for clients on Wayland.
+pub(crate) fn video_modes(monitor: &Monitor) -> Vec<VideoMode> {
+    let video_mode = |output: &Output| VideoMode {
+        monitor: monitor.clone(),
+        id: 0,
+        size: (output.info.mode.0.max(0) as u32, output.info.mode.1.max(0) as u32),
+        bit_depth: 32, // what every compositor takes, it doesn't say what the output itself uses
+        refresh_rate: f64::from(output.info.refresh) / 1000.0,
+    };
+    with_outputs(|outputs| {
+        outputs.iter().find(|output| u64::from(output.name) == monitor.id).map(|output| video_mode(output))
+    })
+    .ok()
+    .flatten()
+    .into_iter()
+    .collect()
+}
+
+/// Connects just to get the outputs, which is what monitor queries have to do on Wayland.
+fn with_outputs<T>(f: impl FnOnce(&[Box<Output>]) -> T) -> Result<T, Error> {
+    let report = WAYLAND_CLIENT.link_report();
+    if !report.is_complete() {
+        return Err(Error::from_internal(InternalError::from_link_report("Failed to load libwayland-client.", report)))
+    }
+    unsafe {
+        let display = wl_display_connect(ptr::null());
+        if display.is_null() {
+            return Err(Error::from_internal(InternalError::no_display("wl_display_connect returned NULL.")))
+        }
+
+        // One round-trip binds the outputs, the second gets everything they send once bound
+        let mut outputs: Vec<Box<Output>> = Vec::new();
+        let registry = wl_display_get_registry(display);
+        let data = (&mut outputs) as *mut Vec<Box<Output>> as *mut c_void;
+        let _ = wl_proxy_add_listener(registry, (&MONITOR_REGISTRY_LISTENER) as *const _ as *const c_void, data);
+        let result = if wl_display_roundtrip(display) == -1 || wl_display_roundtrip(display) == -1 {
+            let err = io::Error::from_raw_os_error(wl_display_get_error(display));
+            Err(Error::from_internal(InternalError::from_io("wl_display_roundtrip failed.", err)))
+        } else {
+            Ok(f(&outputs))
+        };
+
+        for output in outputs {
+            wl_output_release(output.proxy);
+        }
+        wl_proxy_destroy(registry);
+        wl_display_disconnect(display);
+        result
+    }
+}
+
+unsafe extern "C" fn monitor_registry_global(
+    data: *mut c_void,
+    registry: *mut wl_proxy,
+    name: u32,
+    interface: *const c_char,
+    version: u32,
+) {
+    let outputs = &mut *(data as *mut Vec<Box<Output>>);
+    if CStr::from_ptr(interface).to_bytes() == b"wl_output" {
+        outputs.push(bind_output(registry, name, version, ptr::null_mut()));
+    }
+}
+
+unsafe extern "C" fn monitor_registry_global_remove(_data: *mut c_void, _registry: *mut wl_proxy, _name: u32) {}
+
+unsafe extern "C" fn output_geometry(
+    data: *mut c_void,
+    proxy: *mut wl_proxy,
+    x: i32,
+    y: i32,
+    _physical_width: i32,
+    _physical_height: i32,
+    _subpixel: i32,
+    make: *const c_char,
+    model: *const c_char,
+    _transform: i32,
+) {
+    let output = &mut *(data as *mut Output);
+    let make = CStr::from_ptr(make).to_string_lossy();
+    let model = CStr::from_ptr(model).to_string_lossy();
+    output.pending.make_model = format!("{} {}", make, model);
+    output.pending.position = (x, y);
+    output_changed(output, proxy);
+}
+
+unsafe extern "C" fn output_mode(
+    data: *mut c_void,
+    proxy: *mut wl_proxy,
+    flags: u32,
+    width: i32,
+    height: i32,
+    refresh: i32,
+) {
+    // Every mode is listed, but only the current one matters
+    if flags & WL_OUTPUT_MODE_CURRENT != 0 {
+        let output = &mut *(data as *mut Output);
+        output.pending.mode = (width, height);
+        output.pending.refresh = refresh;
+        output_changed(output, proxy);
+    }
+}
+
+unsafe extern "C" fn output_done(data: *mut c_void, _proxy: *mut wl_proxy) {
+    let output = &mut *(data as *mut Output);
+    output.info = output.pending.clone();
+    // Anything sent after the first batch is a reconfiguration
+    let announce = mem::replace(&mut output.announce, true);
+    if !output.window.is_null() {
+        let user_data = &mut *output.window;
+        update_scale_factor(user_data);
+        if announce {
+            push_event(user_data, Event::MonitorsChanged);
+        }
+    }
+}
+
+unsafe extern "C" fn output_scale(data: *mut c_void, _proxy: *mut wl_proxy, factor: i32) {
+    let output = &mut *(data as *mut Output);
+    output.pending.scale = factor.max(1);
+}
+
+unsafe extern "C" fn output_name(data: *mut c_void, _proxy: *mut wl_proxy, name: *const c_char) {
+    let output = &mut *(data as *mut Output);
+    output.pending.name = CStr::from_ptr(name).to_string_lossy().into_owned();
+}
+
+unsafe extern "C" fn output_description(_data: *mut c_void, _proxy: *mut wl_proxy, _description: *const c_char) {}
+
+/// Version 1 outputs don't have `done`, so changes apply immediately.
+unsafe fn output_changed(output: &mut Output, proxy: *mut wl_proxy) {
+    if wl_proxy_get_version(proxy) < 2 {
+        output.info = output.pending.clone();
+    }
+}
+
+unsafe extern "C" fn surface_enter(data: *mut c_void, _surface: *mut wl_proxy, output: *mut wl_proxy) {
+    let user_data = &mut *(data as *mut WindowUserData);
+    if !user_data.surface_outputs.contains(&output) {
+        user_data.surface_outputs.push(output);
+    }
+    update_scale_factor(user_data);
+}
+
+unsafe extern "C" fn surface_leave(data: *mut c_void, _surface: *mut wl_proxy, output: *mut wl_proxy) {
+    let user_data = &mut *(data as *mut WindowUserData);
+    user_data.surface_outputs.retain(|&proxy| proxy != output);
+    update_scale_factor(user_data);
+}
+
+/// Scales the surface for the highest scale output it's on, so it looks sharp on all of them.
+/// Windows with a logical size keep it, which changes the physical size.
+unsafe fn update_scale_factor(user_data: &mut WindowUserData) {
+    let scale = user_data.outputs
+        .iter()
+        .filter(|output| user_data.surface_outputs.contains(&output.proxy))
+        .map(|output| output.info.scale)
+        .max();
+
+    // Off every output (such as while hidden), there's nothing to be scaled for
+    let scale = match scale {
+        Some(scale) => scale,
+        None => return,
+    };
+    let scale_factor = f64::from(scale);
+    if scale_factor == user_data.dpi_data.scale_factor {
+        return
+    }
+    let (size, _) = user_data.dpi_data.inner_size();
+    user_data.dpi_data.scale_factor = scale_factor;
+    if user_data.dpi_data.is_logical {
+        user_data.dpi_data.client = size.physical(scale_factor);
+    }
+    wl_surface_set_buffer_scale(user_data.surface, scale);
+    update_size_limits(user_data);
+    commit_surface(user_data);
+
+    push_event(user_data, Event::ScaleFactor(scale_factor));
+    if user_data.dpi_data.is_logical {
+        push_event(user_data, Event::Resize(user_data.dpi_data.inner_size()));
+    }
+}
+
+unsafe extern "C" fn seat_capabilities(data: *mut c_void, seat: *mut wl_proxy, capabilities: u32) {
+    let user_data = &mut *(data as *mut WindowUserData);
+    let has_pointer = capabilities & WL_SEAT_CAPABILITY_POINTER != 0;
+    if has_pointer && user_data.pointer.is_null() {
+        user_data.pointer = wl_seat_get_pointer(seat);
+        let _ = wl_proxy_add_listener(user_data.pointer, (&POINTER_LISTENER) as *const _ as *const c_void, data);
+
+        // Globals are all bound before the first `capabilities`, so the managers are there if they're supported
+        if !user_data.cursor_shape_manager.is_null() {
+            user_data.cursor_shape_device =
+                wp_cursor_shape_manager_v1_get_pointer(user_data.cursor_shape_manager, user_data.pointer);
+        }
+        if !user_data.relative_pointer_manager.is_null() {
+            user_data.relative_pointer = zwp_relative_pointer_manager_v1_get_relative_pointer(
+                user_data.relative_pointer_manager,
+                user_data.pointer,
+            );
+            let listener = (&RELATIVE_POINTER_LISTENER) as *const _ as *const c_void;
+            let _ = wl_proxy_add_listener(user_data.relative_pointer, listener, data);
+        }
+    } else if !has_pointer {
+        release_pointer(user_data);
+    }
+
+    let has_keyboard = capabilities & WL_SEAT_CAPABILITY_KEYBOARD != 0;
+    if has_keyboard && user_data.keyboard.is_null() {
+        user_data.keyboard = wl_seat_get_keyboard(seat);
+        let _ = wl_proxy_add_listener(user_data.keyboard, (&KEYBOARD_LISTENER) as *const _ as *const c_void, data);
+    } else if !has_keyboard && !user_data.keyboard.is_null() {
+        wl_keyboard_release(user_data.keyboard);
+        user_data.keyboard = ptr::null_mut();
+        user_data.keymap = None;
+        user_data.repeat_key = None;
+    }
+}
+
+unsafe extern "C" fn seat_name(_data: *mut c_void, _seat: *mut wl_proxy, _name: *const c_char) {}
+
+/// Releases the pointer along with everything created from it.
+unsafe fn release_pointer(user_data: &mut WindowUserData) {
+    user_data.pointer_serial = None;
+    if !user_data.cursor_shape_device.is_null() {
+        wp_cursor_shape_device_v1_destroy(user_data.cursor_shape_device);
+        user_data.cursor_shape_device = ptr::null_mut();
+    }
+    if !user_data.relative_pointer.is_null() {
+        zwp_relative_pointer_v1_destroy(user_data.relative_pointer);
+        user_data.relative_pointer = ptr::null_mut();
+    }
+    if !user_data.pointer.is_null() {
+        wl_pointer_release(user_data.pointer);
+        user_data.pointer = ptr::null_mut();
+    }
+}
+
+unsafe extern "C" fn pointer_enter(
+    data: *mut c_void,
+    _pointer: *mut wl_proxy,
+    serial: u32,
+    _surface: *mut wl_proxy,
+    x: wl_fixed_t,
+    y: wl_fixed_t,
+) {
+    let user_data = &mut *(data as *mut WindowUserData);
+
+    // The cursor image is undefined on entering until it's set, so it has to be set every time
+    user_data.pointer_serial = Some(serial);
+    update_cursor(user_data);
+
+    push_event(user_data, Event::MouseEnter);
+    let point = user_data.dpi_data.point(wl_fixed_to_f64(x), wl_fixed_to_f64(y));
+    push_event(user_data, Event::MouseMove(point));
+}
+
+unsafe extern "C" fn pointer_leave(data: *mut c_void, _pointer: *mut wl_proxy, _serial: u32, _surface: *mut wl_proxy) {
+    let user_data = &mut *(data as *mut WindowUserData);
+    user_data.pointer_serial = None;
+    push_event(user_data, Event::MouseLeave);
+}
+
+/// Shows the cursor icon if the pointer is over the surface, as the cursor can only be set while it's ours.
+unsafe fn update_cursor(user_data: &mut WindowUserData) {
+    let serial = match user_data.pointer_serial {
+        Some(serial) if !user_data.cursor_shape_device.is_null() => serial,
+        _ => return,
+    };
+    let shape = match user_data.cursor_icon {
+        CursorIcon::Arrow => WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_DEFAULT,
+        CursorIcon::Text => WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_TEXT,
+        CursorIcon::Hand => WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_POINTER,
+        CursorIcon::Crosshair => WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_CROSSHAIR,
+        CursorIcon::Wait => WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_WAIT,
+        CursorIcon::Progress => WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_PROGRESS,
+        CursorIcon::NotAllowed => WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_NOT_ALLOWED,
+        CursorIcon::Help => WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_HELP,
+        CursorIcon::Move => WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_MOVE,
+        CursorIcon::ResizeHorizontal => WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_EW_RESIZE,
+        CursorIcon::ResizeVertical => WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_NS_RESIZE,
+        CursorIcon::ResizeNeSw => WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_NESW_RESIZE,
+        CursorIcon::ResizeNwSe => WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_NWSE_RESIZE,
+    };
+    wp_cursor_shape_device_v1_set_shape(user_data.cursor_shape_device, serial, shape);
+}
+
+unsafe extern "C" fn pointer_motion(data: *mut c_void, _pointer: *mut wl_proxy, _time: u32, x: wl_fixed_t, y: wl_fixed_t) {
+    let user_data = &mut *(data as *mut WindowUserData);
+    let point = user_data.dpi_data.point(wl_fixed_to_f64(x), wl_fixed_to_f64(y));
+    push_event(user_data, Event::MouseMove(point));
+}
+
+unsafe extern "C" fn pointer_button(
+    data: *mut c_void,
+    _pointer: *mut wl_proxy,
+    _serial: u32,
+    _time: u32,
+    button: u32,
+    state: u32,
+) {
+    let user_data = &mut *(data as *mut WindowUserData);
+    let button = match button {
+        BTN_LEFT => MouseButton::Left,
+        BTN_RIGHT => MouseButton::Right,
+        BTN_MIDDLE => MouseButton::Middle,
+        BTN_SIDE | BTN_BACK => MouseButton::X1,
+        BTN_EXTRA | BTN_FORWARD => MouseButton::X2,
+        _ => return,
+    };
+    let event = if state == WL_POINTER_BUTTON_STATE_PRESSED {
+        Event::MouseDown(button)
+    } else {
+        Event::MouseUp(button)
+    };
+    push_event(user_data, event);
+}
+
+unsafe extern "C" fn pointer_axis(data: *mut c_void, pointer: *mut wl_proxy, _time: u32, axis: u32, value: wl_fixed_t) {
+    let user_data = &mut *(data as *mut WindowUserData);
+
+    // Wayland scrolls down and right for positive values, so vertical is flipped to match the other platforms
+    let value = wl_fixed_to_f64(value) / AXIS_UNITS_PER_NOTCH;
+    match axis {
+        WL_POINTER_AXIS_VERTICAL_SCROLL => user_data.pending_scroll.1 -= value,
+        WL_POINTER_AXIS_HORIZONTAL_SCROLL => user_data.pending_scroll.0 += value,
+        _ => return,
+    }
+
+    // Before version 5 there's no `frame`, so every event stands on its own
+    if wl_proxy_get_version(pointer) < 5 {
+        pointer_frame(data, pointer);
+    }
+}
+
+unsafe extern "C" fn pointer_frame(data: *mut c_void, _pointer: *mut wl_proxy) {
+    let user_data = &mut *(data as *mut WindowUserData);
+    let continuous = mem::take(&mut user_data.pending_scroll);
+    let (delta, precise) = match user_data.pending_scroll_discrete.take() {
+        Some((x, y)) => ((f64::from(x), -f64::from(y)), false),
+        None => (continuous, true),
+    };
+    if delta != (0.0, 0.0) {
+        push_event(user_data, Event::MouseWheel { delta, precise });
+    }
+}
+
+unsafe extern "C" fn pointer_axis_source(_data: *mut c_void, _pointer: *mut wl_proxy, _source: u32) {}
+
+unsafe extern "C" fn pointer_axis_stop(_data: *mut c_void, _pointer: *mut wl_proxy, _time: u32, _axis: u32) {}
+
+unsafe extern "C" fn pointer_axis_discrete(data: *mut c_void, _pointer: *mut wl_proxy, axis: u32, discrete: i32) {
+    // Sent before the matching `axis` for wheels, which is then only used if there are no notches
+    let user_data = &mut *(data as *mut WindowUserData);
+    let (x, y) = user_data.pending_scroll_discrete.get_or_insert((0, 0));
+    match axis {
+        WL_POINTER_AXIS_VERTICAL_SCROLL => *y += discrete,
+        WL_POINTER_AXIS_HORIZONTAL_SCROLL => *x += discrete,
+        _ => (),
+    }
+}
+
+unsafe extern "C" fn relative_pointer_motion(
+    data: *mut c_void,
+    _relative_pointer: *mut wl_proxy,
+    _utime_hi: u32,
+    _utime_lo: u32,
+    _dx: wl_fixed_t,
+    _dy: wl_fixed_t,
+    dx_unaccel: wl_fixed_t,
+    dy_unaccel: wl_fixed_t,
+) {
+    // This follows pointer focus, which can be on an unfocused window
+    let user_data = &mut *(data as *mut WindowUserData);
+    if user_data.focus_state {
+        let (dx, dy) = (wl_fixed_to_f64(dx_unaccel), wl_fixed_to_f64(dy_unaccel));
+        push_event(user_data, Event::RawMouseMotion { dx, dy });
+    }
+}
+
+unsafe extern "C" fn keyboard_keymap(data: *mut c_void, _keyboard: *mut wl_proxy, format: u32, fd: i32, size: u32) {
+    let user_data = &mut *(data as *mut WindowUserData);
+    user_data.keymap = None;
+    if format == WL_KEYBOARD_KEYMAP_FORMAT_XKB_V1 {
+        let size = size as usize;
+        let text = mmap(ptr::null_mut(), size, PROT_READ, MAP_PRIVATE, fd, 0);
+        if text != MAP_FAILED {
+            user_data.keymap = xkb::Keymap::from_text(slice::from_raw_parts(text as *const u8, size));
+            let _ = munmap(text, size);
+        }
+    }
+    let _ = close(fd);
+}
+
+unsafe extern "C" fn keyboard_enter(
+    _data: *mut c_void,
+    _keyboard: *mut wl_proxy,
+    _serial: u32,
+    _surface: *mut wl_proxy,
+    _keys: *mut wl_array,
+) {
+    // Keys that were already held down aren't reported, same as on the other platforms
+}
+
+unsafe extern "C" fn keyboard_leave(data: *mut c_void, _keyboard: *mut wl_proxy, _serial: u32, _surface: *mut wl_proxy) {
+    let user_data = &mut *(data as *mut WindowUserData);
+    user_data.repeat_key = None;
+    if let Some(compose) = user_data.compose.as_mut() {
+        compose.reset();
+    }
+}
+
+unsafe extern "C" fn keyboard_key(
+    data: *mut c_void,
+    _keyboard: *mut wl_proxy,
+    _serial: u32,
+    _time: u32,
+    code: u32,
+    state: u32,
+) {
+    let user_data = &mut *(data as *mut WindowUserData);
+    let key = Key::from_evdev(code);
+    let virtual_key = key_sym(user_data, code);
+    if state == WL_KEYBOARD_KEY_STATE_PRESSED {
+        push_event(user_data, Event::KeyboardDown { key, virtual_key });
+        if let Some(chr) = key_input(user_data, code) {
+            push_event(user_data, Event::Input(chr));
+        }
+        let repeats = match user_data.keymap.as_ref() {
+            Some(keymap) => keymap.key_repeats(code + 8),
+            None => true,
+        };
+        if repeats && user_data.repeat_interval.is_some() {
+            user_data.repeat_key = Some((code, Instant::now() + user_data.repeat_delay));
+        }
+    } else {
+        push_event(user_data, Event::KeyboardUp { key, virtual_key });
+        if matches!(user_data.repeat_key, Some((repeat_code, _)) if repeat_code == code) {
+            user_data.repeat_key = None;
+        }
+    }
+}
+
+unsafe extern "C" fn keyboard_modifiers(
+    data: *mut c_void,
+    _keyboard: *mut wl_proxy,
+    _serial: u32,
+    depressed: u32,
+    latched: u32,
+    locked: u32,
+    group: u32,
+) {
+    let user_data = &mut *(data as *mut WindowUserData);
+    if let Some(keymap) = user_data.keymap.as_mut() {
+        keymap.update_mask(depressed, latched, locked, group);
+    }
+}
+
+unsafe extern "C" fn keyboard_repeat_info(data: *mut c_void, _keyboard: *mut wl_proxy, rate: i32, delay: i32) {
+    let user_data = &mut *(data as *mut WindowUserData);
+    user_data.repeat_delay = Duration::from_millis(delay.max(0) as u64);
+    user_data.repeat_interval = if rate > 0 {
+        Some(Duration::from_secs(1) / rate as u32)
+    } else {
+        user_data.repeat_key = None;
+        None // a rate of zero disables repeat
+    };
+}
+
+/// Gets the keysym for an evdev key code, or zero if there's no keymap to look it up in.
+#[inline]
+unsafe fn key_sym(user_data: &WindowUserData, code: u32) -> u32 {
+    // XKB keycodes are evdev codes offset by 8, just like on X11
+    user_data.keymap.as_ref().map_or(0, |keymap| keymap.keysym(code + 8))
+}
+
+/// Gets the character typed by a key press, going through compose sequences if they're available.
+unsafe fn key_input(user_data: &mut WindowUserData, code: u32) -> Option<char> {
+    let keymap = user_data.keymap.as_ref()?;
+    let keycode = code + 8;
+    match user_data.compose.as_mut().map(|compose| compose.feed(keymap.modified_keysym(keycode))) {
+        Some(xkb::ComposeStatus::Composing) => None,
+        Some(xkb::ComposeStatus::Composed(keysym)) => xkb::keysym_to_char(keysym),
+        Some(xkb::ComposeStatus::Ignored) | None => keymap.key_char(keycode),
+    }
+}
+
+/// Sends a repeat for the held key if it's due, scheduling the next one.
+unsafe fn repeat_key(user_data: &mut WindowUserData) {
+    if let (Some((code, at)), Some(interval)) = (user_data.repeat_key, user_data.repeat_interval) {
+        let now = Instant::now();
+        if now >= at {
+            let key = Key::from_evdev(code);
+            let virtual_key = key_sym(user_data, code);
+            push_event(user_data, Event::KeyboardRepeat { key, virtual_key });
+            if let Some(chr) = key_input(user_data, code) {
+                push_event(user_data, Event::Input(chr));
+            }
+
+            // If we've fallen behind, skip the missed repeats instead of sending them all at once
+            user_data.repeat_key = Some((code, (at + interval).max(now)));
+        }
+    }
+}
+
+/// Enables or disables the text input for the window, which is what shows or hides the IME.
+unsafe fn update_text_input(user_data: &mut WindowUserData) {
+    if !user_data.text_input_active {
+        return
+    }
+    if user_data.ime_allowed {
+        zwp_text_input_v3_enable(user_data.text_input);
+        update_text_input_position(user_data);
+    } else {
+        zwp_text_input_v3_disable(user_data.text_input);
+        end_preedit(user_data);
+    }
+    zwp_text_input_v3_commit(user_data.text_input);
+}
+
+/// Sets the cursor rectangle, which the candidate window is placed next to. Needs a commit to apply.
+unsafe fn update_text_input_position(user_data: &mut WindowUserData) {
+    // Surface-local coordinates are logical pixels
+    let (x, y) = user_data.ime_position.logical(user_data.dpi_data.scale_factor);
+    zwp_text_input_v3_set_cursor_rectangle(user_data.text_input, x as i32, y as i32, 0, 0);
+}
+
+/// Sends the empty preedit that ends a composition, if one is going on.
+fn end_preedit(user_data: &mut WindowUserData) {
+    if user_data.preedit_shown {
+        user_data.preedit_shown = false;
+        push_event(user_data, Event::ImePreedit { text: String::new(), cursor_range: None });
+    }
+}
+
+unsafe extern "C" fn text_input_enter(data: *mut c_void, _text_input: *mut wl_proxy, _surface: *mut wl_proxy) {
+    let user_data = &mut *(data as *mut WindowUserData);
+    user_data.text_input_active = true;
+    if user_data.ime_allowed {
+        update_text_input(user_data);
+    }
+}
+
+unsafe extern "C" fn text_input_leave(data: *mut c_void, _text_input: *mut wl_proxy, _surface: *mut wl_proxy) {
+    let user_data = &mut *(data as *mut WindowUserData);
+    if user_data.ime_allowed {
+        zwp_text_input_v3_disable(user_data.text_input);
+        zwp_text_input_v3_commit(user_data.text_input);
+    }
+    user_data.text_input_active = false;
+    user_data.pending_commit = None;
+    user_data.pending_preedit = None;
+    end_preedit(user_data);
+}
+
+unsafe extern "C" fn text_input_preedit_string(
+    data: *mut c_void,
+    _text_input: *mut wl_proxy,
+    text: *const c_char,
+    cursor_begin: i32,
+    cursor_end: i32,
+) {
+    let user_data = &mut *(data as *mut WindowUserData);
+    let text = if text.is_null() { String::new() } else { CStr::from_ptr(text).to_string_lossy().into_owned() };
+    user_data.pending_preedit = Some((text, cursor_begin, cursor_end));
+}
+
+unsafe extern "C" fn text_input_commit_string(data: *mut c_void, _text_input: *mut wl_proxy, text: *const c_char) {
+    let user_data = &mut *(data as *mut WindowUserData);
+    if !text.is_null() {
+        user_data.pending_commit = Some(CStr::from_ptr(text).to_string_lossy().into_owned());
+    }
+}
+
+unsafe extern "C" fn text_input_delete_surrounding_text(
+    _data: *mut c_void,
+    _text_input: *mut wl_proxy,
+    _before_length: u32,
+    _after_length: u32,
+) {
+    // We never send any surrounding text, so there's nothing to delete
+}
+
+unsafe extern "C" fn text_input_done(data: *mut c_void, _text_input: *mut wl_proxy, _serial: u32) {
+    let user_data = &mut *(data as *mut WindowUserData);
+
+    // The protocol says to replace the preedit with the commit string, then show the new preedit.
+    // A `done` without a `preedit_string` clears it.
+    let preedit = user_data.pending_preedit.take();
+    if let Some(text) = user_data.pending_commit.take() {
+        end_preedit(user_data);
+        push_event(user_data, Event::ImeCommit(text));
+    }
+    match preedit {
+        Some((text, begin, end)) if !text.is_empty() => {
+            // A cursor of -1 means it's hidden
+            let cursor_range = if begin >= 0 && end >= 0 {
+                Some((begin as usize, end as usize))
+            } else {
+                None
+            };
+            user_data.preedit_shown = true;
+            push_event(user_data, Event::ImePreedit { text, cursor_range });
+        },
+        _ => end_preedit(user_data),
+    }
+}
+
+unsafe extern "C" fn wm_base_ping(_data: *mut c_void, wm_base: *mut wl_proxy, serial: u32) {
+    // Unanswered pings make the compositor think we're not responding
+    xdg_wm_base_pong(wm_base, serial);
+}
+
+unsafe extern "C" fn xdg_surface_configure(data: *mut c_void, xdg_surface: *mut wl_proxy, serial: u32) {
+    let user_data = &mut *(data as *mut WindowUserData);
+    xdg_surface_ack_configure(xdg_surface, serial);
+
+    // Width and height of 0 means we get to decide, so we keep what we had.
+    // Fullscreen sizes have to be followed exactly, but otherwise they're only suggestions.
+    let (width, height) = user_data.pending_size;
+    let scale = user_data.dpi_data.scale_factor;
+    let mut client = Size::Logical(width.into(), height.into()).physical(scale);
+    if user_data.fullscreen.is_none() {
+        client = user_data.size_limits.clamp(client, scale);
+    }
+    if width > 0 && height > 0 && client != user_data.dpi_data.client {
+        user_data.dpi_data.client = client;
+        push_event(user_data, Event::Resize(user_data.dpi_data.inner_size()));
+    }
+
+    let focus = user_data.pending_focus;
+    if focus != user_data.focus_state {
+        user_data.focus_state = focus;
+        push_event(user_data, Event::Focus(focus));
+    }
+
+    let maximized = user_data.pending_maximized;
+    user_data.maximized = maximized;
+    if user_data.is_maximized.swap(maximized, atomic::Ordering::AcqRel) != maximized {
+        push_event(user_data, Event::Maximize(maximized));
+    }
+
+    // The acknowledgement applies with this commit, which also attaches the first buffer and shows the window
+    user_data.configured = true;
+    commit_surface(user_data);
+}
+
+unsafe extern "C" fn xdg_toplevel_configure(
+    data: *mut c_void,
+    _toplevel: *mut wl_proxy,
+    width: i32,
+    height: i32,
+    states: *mut wl_array,
+) {
+    let user_data = &mut *(data as *mut WindowUserData);
+    let states = &*states;
+    let states = if states.data.is_null() {
+        &[]
+    } else {
+        slice::from_raw_parts(states.data as *const u32, states.size / mem::size_of::<u32>())
+    };
+    user_data.pending_focus = states.contains(&XDG_TOPLEVEL_STATE_ACTIVATED);
+    user_data.pending_maximized = states.contains(&XDG_TOPLEVEL_STATE_MAXIMIZED);
+    user_data.pending_size = (width, height);
+}
+
+unsafe extern "C" fn xdg_toplevel_close(data: *mut c_void, _toplevel: *mut wl_proxy) {
+    // The compositor doesn't tell us why, it could be a button on its own decorations or a shortcut
+    push_event(&mut *(data as *mut WindowUserData), Event::CloseRequest(CloseReason::Unknown));
+}
+
+unsafe fn message_loop(user_data: &mut WindowUserData) {
+    let display = user_data.display;
+    let mut poll_fds = [
+        pollfd { fd: wl_display_get_fd(display), events: POLLIN, revents: 0 },
+        pollfd { fd: user_data.message_queue.wake_fd(), events: POLLIN, revents: 0 },
+    ];
+
+    'message_loop: loop {
+        // Handle everything the `Window` sent
+        for message in user_data.message_queue.take() {
+            let destroy = matches!(message, Message::Destroy);
+            process_message(user_data, message);
+            user_data.message_queue.mark_processed();
+            if destroy {
+                break 'message_loop
+            }
+        }
+
+        // Handle everything the compositor sent, which calls into the listeners.
+        // This is the standard dance for sleeping on the display while also polling something else.
+        while wl_display_prepare_read(display) != 0 {
+            let _ = wl_display_dispatch_pending(display);
+        }
+        let _ = wl_display_flush(display);
+        let timeout = user_data.repeat_key.map_or(-1, |(_, at)| {
+            let wait = at.saturating_duration_since(Instant::now());
+            // Rounded up, so the key isn't polled for just before it's due
+            let millis = wait.as_millis() + u128::from(wait.subsec_nanos() % 1_000_000 != 0);
+            millis.min(c_int::MAX as u128) as c_int
+        });
+        let _ = poll(poll_fds.as_mut_ptr(), poll_fds.len() as _, timeout);
+        if poll_fds[0].revents != 0 {
+            let _ = wl_display_read_events(display);
+        } else {
+            wl_display_cancel_read(display);
+        }
+        let _ = wl_display_dispatch_pending(display);
+        repeat_key(user_data);
+
+        // If the connection is lost, there's no getting it back - the window is as good as closed
+        if poll_fds[0].fd != -1 && wl_display_get_error(display) != 0 {
+            poll_fds[0].fd = -1; // ignored by `poll`
+            push_event(user_data, Event::CloseRequest(CloseReason::Unknown));
+        }
+    }
+}
+
+unsafe fn process_message(user_data: &mut WindowUserData, message: Message) {
+    match message {
+        Message::Execute(f) => (*f)(),
+
+        // The actual teardown is done once the message loop exits
+        Message::Destroy => (),
+
+        Message::GetCurrentMonitor(out) => {
+            // Surfaces can span several outputs, the first one entered wins
+            *out = user_data.surface_outputs.first().and_then(|&proxy| {
+                let index = user_data.outputs.iter().position(|output| output.proxy == proxy)?;
+                Some(user_data.outputs[index].monitor(index == 0))
+            });
+        },
+        Message::GetDpiData(out) => *out = user_data.dpi_data,
+
+        Message::GetFocusState(out) => *out = user_data.focus_state,
+
+        Message::GetTitle(out) => *out = user_data.title.clone(),
+
+        Message::GetWindowStyle(out) => *out = user_data.window_style.clone(),
+
+        Message::SetAspectRatio(aspect_ratio) => {
+            set_size_limits(user_data, SizeLimits { aspect_ratio, ..user_data.size_limits });
+        },
+
+        // The compositor answers with a configure, which is committed along with everything else
+        Message::SetBorderless(borderless) => {
+            if user_data.window_style.borderless != borderless {
+                user_data.window_style.borderless = borderless;
+                update_decoration(user_data);
+            }
+        },
+
+        Message::SetControls(controls) => user_data.window_style.controls = controls,
+
+        Message::SetCursorIcon(icon) => {
+            if user_data.cursor_icon != icon {
+                user_data.cursor_icon = icon;
+                update_cursor(user_data);
+            }
+        },
+
+        // TODO: Cursor locking needs the pointer-constraints extension
+        #[cfg(feature = "cursor-lock")]
+        Message::SetCursorLock(mode) => user_data.cursor_lock = mode,
+
+        Message::SetFullscreen(fullscreen) => {
+            if user_data.fullscreen != fullscreen {
+                user_data.fullscreen = fullscreen;
+                update_fullscreen(user_data);
+            }
+        },
+        Message::SetImeAllowed(allowed) => {
+            if user_data.ime_allowed != allowed {
+                user_data.ime_allowed = allowed;
+                update_text_input(user_data);
+            }
+        },
+
+        Message::SetImePosition(position) => {
+            user_data.ime_position = position;
+            if user_data.text_input_active && user_data.ime_allowed {
+                update_text_input_position(user_data);
+                zwp_text_input_v3_commit(user_data.text_input);
+            }
+        },
+
+        // Surfaces are sized by the client, the compositor only suggests sizes in `configure`
+        Message::SetInnerSize(inner_size) => {
+            let scale = user_data.dpi_data.scale_factor;
+            let client = user_data.size_limits.clamp(inner_size.physical(scale), scale);
+            if client != user_data.dpi_data.client {
+                user_data.dpi_data.client = client;
+                update_size_limits(user_data);
+                commit_surface(user_data);
+                push_event(user_data, Event::Resize(user_data.dpi_data.inner_size()));
+            }
+        },
+
+        // Hidden windows get it once they're shown
+        Message::SetMaximized(maximized) => {
+            user_data.maximized = maximized;
+            if !user_data.xdg_toplevel.is_null() {
+                if maximized {
+                    xdg_toplevel_set_maximized(user_data.xdg_toplevel);
+                } else {
+                    xdg_toplevel_unset_maximized(user_data.xdg_toplevel);
+                }
+            }
+        },
+
+        Message::SetMaxInnerSize(max) => set_size_limits(user_data, SizeLimits { max, ..user_data.size_limits }),
+        Message::SetMinInnerSize(min) => set_size_limits(user_data, SizeLimits { min, ..user_data.size_limits }),
+
+        // There's no way back from this for clients, nor any way to know when the user brings it back
+        Message::SetMinimized(minimized) => {
+            if minimized && !user_data.xdg_toplevel.is_null() {
+                xdg_toplevel_set_minimized(user_data.xdg_toplevel);
+            }
+        },
+
+        Message::SetResizable(resizable) => {
+            if user_data.window_style.resizable != resizable {
+                user_data.window_style.resizable = resizable;
+                update_size_limits(user_data);
+                commit_surface(user_data);
+            }
+        },
+
+        Message::SetTitle(title) => {
+            user_data.title = title;
+            if !user_data.xdg_toplevel.is_null() {
+                let mut buffer = Vec::new();
+                xdg_toplevel_set_title(user_data.xdg_toplevel, str_to_c_null(&user_data.title, &mut buffer));
+            }
+        },
+
+        Message::SetVisible(visible) => {
+            if user_data.window_style.visible != visible {
+                user_data.window_style.visible = visible;
+                if visible {
+                    map_toplevel(user_data);
+                } else {
+                    unmap_toplevel(user_data);
+                }
+            }
+        },
+    }
+}
+
+impl Window {
+    /// Sends a message to the window thread, blocking until it's been processed.
+    fn send_message(&self, message: Message) {
+        unsafe {
+            let user_data = self.user_data.get();
+
+            // Same as `SendMessageW`, we're already on the window thread so we just process it
+            if (*user_data).message_queue.is_owner_thread() {
+                process_message(&mut *user_data, message);
+            } else {
+                let queue = &(*user_data).message_queue;
+                queue.wait(queue.post(message));
+            }
+        }
+    }
+
+    /// Posts a message to the window thread without waiting for it to be processed.
+    #[inline]
+    fn post_message(&self, message: Message) {
+        let _ = unsafe { &*self.user_data.get() }.message_queue.post(message);
+    }
+
+    /// Gets a copy of the window style as last set, from the window thread.
+    fn window_style(&self) -> WindowStyle {
+        let mut style = WindowStyle::default();
+        self.send_message(Message::GetWindowStyle(&mut style));
+        style
+    }
+}
+
+impl WindowImpl for Window {
+    #[inline]
+    fn events(&self) -> &[Event] {
+        self.event_buffer.as_slice()
+    }
+
+    fn execute(&self, mut f: &mut dyn FnMut()) {
+        // The reference is only sent to the window thread while we block, so it's fine to erase the lifetime
+        let wrap: *mut &mut dyn FnMut() = &mut f;
+        self.send_message(Message::Execute(wrap.cast()));
+    }
+
+    #[inline]
+    fn controls(&self) -> Option<WindowControls> {
+        self.window_style().controls
+    }
+
+    fn current_monitor(&self) -> Option<Monitor> {
+        let mut monitor = None;
+        self.send_message(Message::GetCurrentMonitor(&mut monitor));
+        monitor
+    }
+
+    fn inner_size(&self) -> (Size, Scale) {
+        let mut info = WindowUserDpiData::default();
+        self.send_message(Message::GetDpiData(&mut info));
+        info.inner_size()
+    }
+
+    #[inline]
+    fn is_borderless(&self) -> bool {
+        self.window_style().borderless
+    }
+
+    fn is_focused(&self) -> bool {
+        let mut focused = false;
+        self.send_message(Message::GetFocusState(&mut focused));
+        focused
+    }
+
+    #[inline]
+    fn is_maximized(&self) -> bool {
+        let user_data = unsafe { &*self.user_data.get() };
+        user_data.is_maximized.load(atomic::Ordering::Acquire)
+    }
+
+    /// There's no minimized state in xdg-shell.
+    #[inline]
+    fn is_minimized(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn is_resizable(&self) -> bool {
+        self.window_style().resizable
+    }
+
+    #[inline]
+    fn is_visible(&self) -> bool {
+        self.window_style().visible
+    }
+
+    #[inline]
+    fn outer_position(&self) -> Point {
+        // Surfaces have no idea where they are on Wayland
+        Point::Physical(0, 0)
+    }
+
+    fn title(&self) -> String {
+        let mut title = String::new();
+        self.send_message(Message::GetTitle(&mut title));
+        title
+    }
+
+    #[inline]
+    fn set_aspect_ratio(&self, aspect_ratio: Option<(u32, u32)>) {
+        self.send_message(Message::SetAspectRatio(aspect_ratio));
+    }
+
+    #[inline]
+    fn set_aspect_ratio_async(&self, aspect_ratio: Option<(u32, u32)>) {
+        self.post_message(Message::SetAspectRatio(aspect_ratio));
+    }
+
+    #[inline]
+    fn set_borderless(&self, borderless: bool) {
+        self.send_message(Message::SetBorderless(borderless));
+    }
+
+    #[inline]
+    fn set_borderless_async(&self, borderless: bool) {
+        self.post_message(Message::SetBorderless(borderless));
+    }
+
+    #[inline]
+    fn set_controls(&self, controls: Option<WindowControls>) {
+        self.send_message(Message::SetControls(controls));
+    }
+
+    #[inline]
+    fn set_controls_async(&self, controls: Option<WindowControls>) {
+        self.post_message(Message::SetControls(controls));
+    }
+
+    #[inline]
+    fn set_cursor_icon(&self, icon: CursorIcon) {
+        self.send_message(Message::SetCursorIcon(icon));
+    }
+
+    #[inline]
+    fn set_cursor_icon_async(&self, icon: CursorIcon) {
+        self.post_message(Message::SetCursorIcon(icon));
+    }
+
+    #[cfg(feature = "cursor-lock")]
+    #[inline]
+    fn set_cursor_lock(&self, mode: Option<CursorLock>) {
+        self.send_message(Message::SetCursorLock(mode));
+    }
+
+    #[cfg(feature = "cursor-lock")]
+    #[inline]
+    fn set_cursor_lock_async(&self, mode: Option<CursorLock>) {
+        self.post_message(Message::SetCursorLock(mode));
+    }
+
+    #[inline]
+    fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) {
+        self.send_message(Message::SetFullscreen(fullscreen));
+    }
+
+    #[inline]
+    fn set_fullscreen_async(&self, fullscreen: Option<Fullscreen>) {
+        self.post_message(Message::SetFullscreen(fullscreen));
+    }
+
+    #[inline]
+    fn set_ime_allowed(&self, allowed: bool) {
+        self.send_message(Message::SetImeAllowed(allowed));
+    }
+
+    #[inline]
+    fn set_ime_allowed_async(&self, allowed: bool) {
+        self.post_message(Message::SetImeAllowed(allowed));
+    }
+
+    #[inline]
+    fn set_ime_position(&self, position: Point) {
+        self.send_message(Message::SetImePosition(position));
+    }
+
+    #[inline]
+    fn set_ime_position_async(&self, position: Point) {
+        self.post_message(Message::SetImePosition(position));
+    }
+
+    #[inline]
+    fn set_inner_size(&self, inner_size: Size) {
+        self.send_message(Message::SetInnerSize(inner_size));
+    }
+
+    #[inline]
+    fn set_inner_size_async(&self, inner_size: Size) {
+        self.post_message(Message::SetInnerSize(inner_size));
+    }
+
+    #[inline]
+    fn set_max_inner_size(&self, max_inner_size: Option<Size>) {
+        self.send_message(Message::SetMaxInnerSize(max_inner_size));
+    }
+
+    #[inline]
+    fn set_max_inner_size_async(&self, max_inner_size: Option<Size>) {
+        self.post_message(Message::SetMaxInnerSize(max_inner_size));
+    }
+
+    #[inline]
+    fn set_maximized(&self, maximized: bool) {
+        self.send_message(Message::SetMaximized(maximized));
+    }
+
+    #[inline]
+    fn set_maximized_async(&self, maximized: bool) {
+        self.post_message(Message::SetMaximized(maximized));
+    }
+
+    #[inline]
+    fn set_min_inner_size(&self, min_inner_size: Option<Size>) {
+        self.send_message(Message::SetMinInnerSize(min_inner_size));
+    }
+
+    #[inline]
+    fn set_min_inner_size_async(&self, min_inner_size: Option<Size>) {
+        self.post_message(Message::SetMinInnerSize(min_inner_size));
+    }
+
+    #[inline]
+    fn set_minimized(&self, minimized: bool) {
+        self.send_message(Message::SetMinimized(minimized));
+    }
+
+    #[inline]
+    fn set_minimized_async(&self, minimized: bool) {
+        self.post_message(Message::SetMinimized(minimized));
+    }
+
+    /// Clients can't position their own surfaces.
+    #[inline]
+    fn set_outer_position(&self, _position: Point) {}
+
+    #[inline]
+    fn set_outer_position_async(&self, _position: Point) {}
+
+    #[inline]
+    fn set_resizable(&self, resizable: bool) {
+        self.send_message(Message::SetResizable(resizable));
+    }
+
+    #[inline]
+    fn set_resizable_async(&self, resizable: bool) {
+        self.post_message(Message::SetResizable(resizable));
+    }
+
+    #[inline]
+    fn set_title(&self, title: &str) {
+        self.send_message(Message::SetTitle(title.into()));
+    }
+
+    #[inline]
+    fn set_title_async(&self, title: &str) {
+        self.post_message(Message::SetTitle(title.into()));
+    }
+
+    #[inline]
+    fn set_visible(&self, visible: bool) {
+        self.send_message(Message::SetVisible(visible));
+    }
+
+    #[inline]
+    fn set_visible_async(&self, visible: bool) {
+        self.post_message(Message::SetVisible(visible));
+    }
+
+    fn swap_events(&mut self) {
+        let user_data = unsafe { &mut *self.user_data.get() };
+        let mut vec_lock = mutex_lock(&user_data.event_queue);
+        mem::swap(&mut self.event_buffer, vec_lock.as_mut());
+        vec_lock.clear();
+        mem::drop(vec_lock);
+    }
+}
+
+impl ops::Drop for Window {
+    fn drop(&mut self) {
+        self.post_message(Message::Destroy);
+        let _ = self.thread.take().map(thread::JoinHandle::join);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{platform::Backend, window::Window};
+    use std::{
+        env,
+        thread,
+        time::{Duration, Instant},
+    };
+
+    /// Needs a compositor to connect to, such as `weston --backend=headless-backend.so`, and passes without one.
+    #[test]
+    fn window_is_shown() {
+        match env::var_os("WAYLAND_DISPLAY") {
+            Some(display) if !display.is_empty() => (),
+            _ => return,
+        }
+        let window = Window::builder().backend(Some(Backend::Wayland)).build().expect("failed to create window");
+
+        // Surfaces only enter an output once they're mapped, which takes a configure and a buffer
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while window.current_monitor().is_none() {
+            assert!(Instant::now() < deadline, "window was never shown");
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert!(window.is_visible());
+    }
+}
@@ -0,0 +1,98 @@
+#![allow(bad_style, dead_code)]
+
+// Opaque types
+#[doc(hidden)]
+pub enum wl_display {}
+#[doc(hidden)]
+pub enum wl_proxy {}
+
+use crate::platform::posix::{dlopen_lazy, dlsym};
+
+// Typedefs
+pub use std::os::raw::{c_char, c_int, c_void};
+pub type wl_fixed_t = i32;
+
+/// Converts a 24.8 fixed point number to a float, like `wl_fixed_to_double` in `wayland-util.h`.
+#[inline]
+pub fn wl_fixed_to_f64(f: wl_fixed_t) -> f64 {
+    f64::from(f) / 256.0
+}
+
+// Structs
+#[repr(C)]
+pub struct wl_array {
+    pub size: usize,
+    pub alloc: usize,
+    pub data: *mut c_void,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union wl_argument {
+    pub i: i32,
+    pub u: u32,
+    pub f: wl_fixed_t,
+    pub s: *const c_char,
+    pub o: *mut wl_proxy,
+    pub n: u32,
+    pub a: *mut wl_array,
+    pub h: i32,
+}
+#[repr(C)]
+pub struct wl_message {
+    pub name: *const c_char,
+    pub signature: *const c_char,
+    pub types: *const *const wl_interface,
+}
+#[repr(C)]
+pub struct wl_interface {
+    pub name: *const c_char,
+    pub version: c_int,
+    pub method_count: c_int,
+    pub methods: *const wl_message,
+    pub event_count: c_int,
+    pub events: *const wl_message,
+}
+
+// Interface descriptions are immutable, they only contain raw pointers to be C compatible
+unsafe impl Sync for wl_interface {}
+
+// Dynamically loaded imports, see `dyn_link!`
+dyn_link! {
+    pub(crate) struct WaylandClient(dlopen_lazy => *mut c_void | dlsym) extern "C" static WAYLAND_CLIENT {
+        "libwayland-client.so.0" | "libwayland-client.so" {
+            // Display management
+            fn wl_display_connect(name: *const c_char) -> *mut wl_display;
+            fn wl_display_disconnect(display: *mut wl_display) -> ();
+            fn wl_display_get_error(display: *mut wl_display) -> c_int;
+            fn wl_display_get_fd(display: *mut wl_display) -> c_int;
+
+            // Event loop
+            fn wl_display_cancel_read(display: *mut wl_display) -> ();
+            fn wl_display_dispatch_pending(display: *mut wl_display) -> c_int;
+            fn wl_display_flush(display: *mut wl_display) -> c_int;
+            fn wl_display_prepare_read(display: *mut wl_display) -> c_int;
+            fn wl_display_read_events(display: *mut wl_display) -> c_int;
+            fn wl_display_roundtrip(display: *mut wl_display) -> c_int;
+
+            // Proxies (protocol objects)
+            // The variadic `wl_proxy_marshal*` functions can't be forwarded, so the array versions are used
+            fn wl_proxy_add_listener(proxy: *mut wl_proxy, implementation: *const c_void, data: *mut c_void) -> c_int;
+            fn wl_proxy_destroy(proxy: *mut wl_proxy) -> ();
+            fn wl_proxy_get_version(proxy: *mut wl_proxy) -> u32;
+            fn wl_proxy_marshal_array(proxy: *mut wl_proxy, opcode: u32, args: *mut wl_argument) -> ();
+            fn wl_proxy_marshal_array_constructor(
+                proxy: *mut wl_proxy,
+                opcode: u32,
+                args: *mut wl_argument,
+                interface: *const wl_interface,
+            ) -> *mut wl_proxy;
+            fn wl_proxy_marshal_array_constructor_versioned(
+                proxy: *mut wl_proxy,
+                opcode: u32,
+                args: *mut wl_argument,
+                interface: *const wl_interface,
+                version: u32,
+            ) -> *mut wl_proxy;
+        },
+    }
+}
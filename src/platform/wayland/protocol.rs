@@ -0,0 +1,736 @@
+//! Hand-written equivalents of what `wayland-scanner` would generate for the protocols we use.
+//!
+//! Interfaces are declared at the highest version we understand, which is what's bound at most.
+//! Message signatures and argument types must match the protocol XML exactly (in order),
+//! as `libwayland-client` uses them to (de)serialize everything on the wire.
+
+#![allow(bad_style, dead_code)]
+
+use super::api::*;
+use std::ptr;
+
+/// Placeholder for arguments that aren't objects.
+const N: *const wl_interface = ptr::null();
+
+macro_rules! wl_message {
+    ($name:literal, $signature:literal, [$($ty:expr),* $(,)?]) => {
+        wl_message {
+            name: c_string!($name).as_ptr() as *const c_char,
+            signature: c_string!($signature).as_ptr() as *const c_char,
+            types: &[$($ty as *const wl_interface),*] as *const [*const wl_interface] as *const *const wl_interface,
+        }
+    };
+}
+
+macro_rules! wl_interfaces {
+    ($(
+        $ident:ident($name:literal, $version:literal) {
+            requests: [$($req:expr),* $(,)?],
+            events: [$($ev:expr),* $(,)?] $(,)?
+        }
+    )*) => {
+        $(
+            pub static $ident: wl_interface = wl_interface {
+                name: c_string!($name).as_ptr() as *const c_char,
+                version: $version,
+                method_count: <[&str]>::len(&[$(stringify!($req)),*]) as c_int,
+                methods: &[$($req),*] as *const [wl_message] as *const wl_message,
+                event_count: <[&str]>::len(&[$(stringify!($ev)),*]) as c_int,
+                events: &[$($ev),*] as *const [wl_message] as *const wl_message,
+            };
+        )*
+    };
+}
+
+wl_interfaces! {
+    // wayland.xml
+    wl_registry_interface("wl_registry", 1) {
+        requests: [wl_message!("bind", "usun", [N, N, N, N])],
+        events: [
+            wl_message!("global", "usu", [N, N, N]),
+            wl_message!("global_remove", "u", [N]),
+        ],
+    }
+    wl_callback_interface("wl_callback", 1) {
+        requests: [],
+        events: [wl_message!("done", "u", [N])],
+    }
+    wl_compositor_interface("wl_compositor", 4) {
+        requests: [
+            wl_message!("create_surface", "n", [&wl_surface_interface]),
+            wl_message!("create_region", "n", [&wl_region_interface]),
+        ],
+        events: [],
+    }
+    wl_shm_pool_interface("wl_shm_pool", 1) {
+        requests: [
+            wl_message!("create_buffer", "niiiiu", [&wl_buffer_interface, N, N, N, N, N]),
+            wl_message!("destroy", "", []),
+            wl_message!("resize", "i", [N]),
+        ],
+        events: [],
+    }
+    wl_shm_interface("wl_shm", 1) {
+        requests: [wl_message!("create_pool", "nhi", [&wl_shm_pool_interface, N, N])],
+        events: [wl_message!("format", "u", [N])],
+    }
+    wl_buffer_interface("wl_buffer", 1) {
+        requests: [wl_message!("destroy", "", [])],
+        events: [wl_message!("release", "", [])],
+    }
+    wl_surface_interface("wl_surface", 4) {
+        requests: [
+            wl_message!("destroy", "", []),
+            wl_message!("attach", "?oii", [&wl_buffer_interface, N, N]),
+            wl_message!("damage", "iiii", [N, N, N, N]),
+            wl_message!("frame", "n", [&wl_callback_interface]),
+            wl_message!("set_opaque_region", "?o", [&wl_region_interface]),
+            wl_message!("set_input_region", "?o", [&wl_region_interface]),
+            wl_message!("commit", "", []),
+            wl_message!("set_buffer_transform", "2i", [N]),
+            wl_message!("set_buffer_scale", "3i", [N]),
+            wl_message!("damage_buffer", "4iiii", [N, N, N, N]),
+        ],
+        events: [
+            wl_message!("enter", "o", [&wl_output_interface]),
+            wl_message!("leave", "o", [&wl_output_interface]),
+        ],
+    }
+    wl_seat_interface("wl_seat", 5) {
+        requests: [
+            wl_message!("get_pointer", "n", [&wl_pointer_interface]),
+            wl_message!("get_keyboard", "n", [&wl_keyboard_interface]),
+            wl_message!("get_touch", "n", [&wl_touch_interface]),
+            wl_message!("release", "5", []),
+        ],
+        events: [
+            wl_message!("capabilities", "u", [N]),
+            wl_message!("name", "2s", [N]),
+        ],
+    }
+    wl_pointer_interface("wl_pointer", 5) {
+        requests: [
+            wl_message!("set_cursor", "u?oii", [N, &wl_surface_interface, N, N]),
+            wl_message!("release", "3", []),
+        ],
+        events: [
+            wl_message!("enter", "uoff", [N, &wl_surface_interface, N, N]),
+            wl_message!("leave", "uo", [N, &wl_surface_interface]),
+            wl_message!("motion", "uff", [N, N, N]),
+            wl_message!("button", "uuuu", [N, N, N, N]),
+            wl_message!("axis", "uuf", [N, N, N]),
+            wl_message!("frame", "5", []),
+            wl_message!("axis_source", "5u", [N]),
+            wl_message!("axis_stop", "5uu", [N, N]),
+            wl_message!("axis_discrete", "5ui", [N, N]),
+        ],
+    }
+    wl_keyboard_interface("wl_keyboard", 5) {
+        requests: [wl_message!("release", "3", [])],
+        events: [
+            wl_message!("keymap", "uhu", [N, N, N]),
+            wl_message!("enter", "uoa", [N, &wl_surface_interface, N]),
+            wl_message!("leave", "uo", [N, &wl_surface_interface]),
+            wl_message!("key", "uuuu", [N, N, N, N]),
+            wl_message!("modifiers", "uuuuu", [N, N, N, N, N]),
+            wl_message!("repeat_info", "4ii", [N, N]),
+        ],
+    }
+    wl_touch_interface("wl_touch", 5) {
+        requests: [wl_message!("release", "3", [])],
+        events: [
+            wl_message!("down", "uuoiff", [N, N, &wl_surface_interface, N, N, N]),
+            wl_message!("up", "uui", [N, N, N]),
+            wl_message!("motion", "uiff", [N, N, N, N]),
+            wl_message!("frame", "", []),
+            wl_message!("cancel", "", []),
+        ],
+    }
+    wl_output_interface("wl_output", 4) {
+        requests: [wl_message!("release", "3", [])],
+        events: [
+            wl_message!("geometry", "iiiiissi", [N, N, N, N, N, N, N, N]),
+            wl_message!("mode", "uiii", [N, N, N, N]),
+            wl_message!("done", "2", []),
+            wl_message!("scale", "2i", [N]),
+            wl_message!("name", "4s", [N]),
+            wl_message!("description", "4s", [N]),
+        ],
+    }
+    wl_region_interface("wl_region", 1) {
+        requests: [
+            wl_message!("destroy", "", []),
+            wl_message!("add", "iiii", [N, N, N, N]),
+            wl_message!("subtract", "iiii", [N, N, N, N]),
+        ],
+        events: [],
+    }
+
+    // xdg-shell.xml
+    xdg_wm_base_interface("xdg_wm_base", 1) {
+        requests: [
+            wl_message!("destroy", "", []),
+            wl_message!("create_positioner", "n", [&xdg_positioner_interface]),
+            wl_message!("get_xdg_surface", "no", [&xdg_surface_interface, &wl_surface_interface]),
+            wl_message!("pong", "u", [N]),
+        ],
+        events: [wl_message!("ping", "u", [N])],
+    }
+    xdg_positioner_interface("xdg_positioner", 1) {
+        requests: [
+            wl_message!("destroy", "", []),
+            wl_message!("set_size", "ii", [N, N]),
+            wl_message!("set_anchor_rect", "iiii", [N, N, N, N]),
+            wl_message!("set_anchor", "u", [N]),
+            wl_message!("set_gravity", "u", [N]),
+            wl_message!("set_constraint_adjustment", "u", [N]),
+            wl_message!("set_offset", "ii", [N, N]),
+        ],
+        events: [],
+    }
+    xdg_surface_interface("xdg_surface", 1) {
+        requests: [
+            wl_message!("destroy", "", []),
+            wl_message!("get_toplevel", "n", [&xdg_toplevel_interface]),
+            wl_message!("get_popup", "n?oo", [&xdg_popup_interface, &xdg_surface_interface, &xdg_positioner_interface]),
+            wl_message!("set_window_geometry", "iiii", [N, N, N, N]),
+            wl_message!("ack_configure", "u", [N]),
+        ],
+        events: [wl_message!("configure", "u", [N])],
+    }
+    xdg_toplevel_interface("xdg_toplevel", 1) {
+        requests: [
+            wl_message!("destroy", "", []),
+            wl_message!("set_parent", "?o", [&xdg_toplevel_interface]),
+            wl_message!("set_title", "s", [N]),
+            wl_message!("set_app_id", "s", [N]),
+            wl_message!("show_window_menu", "ouii", [&wl_seat_interface, N, N, N]),
+            wl_message!("move", "ou", [&wl_seat_interface, N]),
+            wl_message!("resize", "ouu", [&wl_seat_interface, N, N]),
+            wl_message!("set_max_size", "ii", [N, N]),
+            wl_message!("set_min_size", "ii", [N, N]),
+            wl_message!("set_maximized", "", []),
+            wl_message!("unset_maximized", "", []),
+            wl_message!("set_fullscreen", "?o", [&wl_output_interface]),
+            wl_message!("unset_fullscreen", "", []),
+            wl_message!("set_minimized", "", []),
+        ],
+        events: [
+            wl_message!("configure", "iia", [N, N, N]),
+            wl_message!("close", "", []),
+        ],
+    }
+    xdg_popup_interface("xdg_popup", 1) {
+        requests: [
+            wl_message!("destroy", "", []),
+            wl_message!("grab", "ou", [&wl_seat_interface, N]),
+        ],
+        events: [
+            wl_message!("configure", "iiii", [N, N, N, N]),
+            wl_message!("popup_done", "", []),
+        ],
+    }
+
+    // relative-pointer-unstable-v1.xml
+    zwp_relative_pointer_manager_v1_interface("zwp_relative_pointer_manager_v1", 1) {
+        requests: [
+            wl_message!("destroy", "", []),
+            wl_message!("get_relative_pointer", "no", [&zwp_relative_pointer_v1_interface, &wl_pointer_interface]),
+        ],
+        events: [],
+    }
+    zwp_relative_pointer_v1_interface("zwp_relative_pointer_v1", 1) {
+        requests: [wl_message!("destroy", "", [])],
+        events: [wl_message!("relative_motion", "uuffff", [N, N, N, N, N, N])],
+    }
+
+    // text-input-unstable-v3.xml
+    zwp_text_input_manager_v3_interface("zwp_text_input_manager_v3", 1) {
+        requests: [
+            wl_message!("destroy", "", []),
+            wl_message!("get_text_input", "no", [&zwp_text_input_v3_interface, &wl_seat_interface]),
+        ],
+        events: [],
+    }
+    zwp_text_input_v3_interface("zwp_text_input_v3", 1) {
+        requests: [
+            wl_message!("destroy", "", []),
+            wl_message!("enable", "", []),
+            wl_message!("disable", "", []),
+            wl_message!("set_surrounding_text", "sii", [N, N, N]),
+            wl_message!("set_text_change_cause", "u", [N]),
+            wl_message!("set_content_type", "uu", [N, N]),
+            wl_message!("set_cursor_rectangle", "iiii", [N, N, N, N]),
+            wl_message!("commit", "", []),
+        ],
+        events: [
+            wl_message!("enter", "o", [&wl_surface_interface]),
+            wl_message!("leave", "o", [&wl_surface_interface]),
+            wl_message!("preedit_string", "?sii", [N, N, N]),
+            wl_message!("commit_string", "?s", [N]),
+            wl_message!("delete_surrounding_text", "uu", [N, N]),
+            wl_message!("done", "u", [N]),
+        ],
+    }
+
+    // xdg-decoration-unstable-v1.xml
+    zxdg_decoration_manager_v1_interface("zxdg_decoration_manager_v1", 1) {
+        requests: [
+            wl_message!("destroy", "", []),
+            wl_message!("get_toplevel_decoration", "no", [&zxdg_toplevel_decoration_v1_interface, &xdg_toplevel_interface]),
+        ],
+        events: [],
+    }
+    zxdg_toplevel_decoration_v1_interface("zxdg_toplevel_decoration_v1", 1) {
+        requests: [
+            wl_message!("destroy", "", []),
+            wl_message!("set_mode", "u", [N]),
+            wl_message!("unset_mode", "", []),
+        ],
+        events: [wl_message!("configure", "u", [N])],
+    }
+
+    // cursor-shape-v1.xml
+    wp_cursor_shape_manager_v1_interface("wp_cursor_shape_manager_v1", 1) {
+        requests: [
+            wl_message!("destroy", "", []),
+            wl_message!("get_pointer", "no", [&wp_cursor_shape_device_v1_interface, &wl_pointer_interface]),
+            // Never sent, so the tablet interfaces aren't declared
+            wl_message!("get_tablet_tool_v2", "no", [&wp_cursor_shape_device_v1_interface, N]),
+        ],
+        events: [],
+    }
+    wp_cursor_shape_device_v1_interface("wp_cursor_shape_device_v1", 1) {
+        requests: [
+            wl_message!("destroy", "", []),
+            wl_message!("set_shape", "uu", [N, N]),
+        ],
+        events: [],
+    }
+}
+
+// Enums
+pub const WL_SEAT_CAPABILITY_POINTER: u32 = 1;
+pub const WL_SEAT_CAPABILITY_KEYBOARD: u32 = 2;
+pub const WL_SEAT_CAPABILITY_TOUCH: u32 = 4;
+pub const WL_SHM_FORMAT_ARGB8888: u32 = 0;
+pub const WL_SHM_FORMAT_XRGB8888: u32 = 1;
+pub const WL_OUTPUT_MODE_CURRENT: u32 = 1;
+pub const WL_POINTER_BUTTON_STATE_RELEASED: u32 = 0;
+pub const WL_POINTER_BUTTON_STATE_PRESSED: u32 = 1;
+pub const WL_POINTER_AXIS_VERTICAL_SCROLL: u32 = 0;
+pub const WL_POINTER_AXIS_HORIZONTAL_SCROLL: u32 = 1;
+pub const WL_KEYBOARD_KEYMAP_FORMAT_NO_KEYMAP: u32 = 0;
+pub const WL_KEYBOARD_KEYMAP_FORMAT_XKB_V1: u32 = 1;
+pub const WL_KEYBOARD_KEY_STATE_RELEASED: u32 = 0;
+pub const WL_KEYBOARD_KEY_STATE_PRESSED: u32 = 1;
+pub const XDG_TOPLEVEL_STATE_MAXIMIZED: u32 = 1;
+pub const XDG_TOPLEVEL_STATE_FULLSCREEN: u32 = 2;
+pub const XDG_TOPLEVEL_STATE_RESIZING: u32 = 3;
+pub const XDG_TOPLEVEL_STATE_ACTIVATED: u32 = 4;
+pub const ZXDG_TOPLEVEL_DECORATION_V1_MODE_CLIENT_SIDE: u32 = 1;
+pub const ZXDG_TOPLEVEL_DECORATION_V1_MODE_SERVER_SIDE: u32 = 2;
+pub const WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_DEFAULT: u32 = 1;
+pub const WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_HELP: u32 = 3;
+pub const WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_POINTER: u32 = 4;
+pub const WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_PROGRESS: u32 = 5;
+pub const WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_WAIT: u32 = 6;
+pub const WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_CROSSHAIR: u32 = 8;
+pub const WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_TEXT: u32 = 9;
+pub const WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_MOVE: u32 = 13;
+pub const WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_NOT_ALLOWED: u32 = 15;
+pub const WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_EW_RESIZE: u32 = 26;
+pub const WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_NS_RESIZE: u32 = 27;
+pub const WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_NESW_RESIZE: u32 = 28;
+pub const WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_NWSE_RESIZE: u32 = 29;
+
+// Listeners
+#[repr(C)]
+pub struct wl_registry_listener {
+    pub global: unsafe extern "C" fn(*mut c_void, *mut wl_proxy, u32, *const c_char, u32),
+    pub global_remove: unsafe extern "C" fn(*mut c_void, *mut wl_proxy, u32),
+}
+#[repr(C)]
+pub struct wl_surface_listener {
+    pub enter: unsafe extern "C" fn(*mut c_void, *mut wl_proxy, *mut wl_proxy),
+    pub leave: unsafe extern "C" fn(*mut c_void, *mut wl_proxy, *mut wl_proxy),
+}
+#[repr(C)]
+pub struct wl_seat_listener {
+    pub capabilities: unsafe extern "C" fn(*mut c_void, *mut wl_proxy, u32),
+    pub name: unsafe extern "C" fn(*mut c_void, *mut wl_proxy, *const c_char),
+}
+#[repr(C)]
+pub struct wl_pointer_listener {
+    pub enter: unsafe extern "C" fn(*mut c_void, *mut wl_proxy, u32, *mut wl_proxy, wl_fixed_t, wl_fixed_t),
+    pub leave: unsafe extern "C" fn(*mut c_void, *mut wl_proxy, u32, *mut wl_proxy),
+    pub motion: unsafe extern "C" fn(*mut c_void, *mut wl_proxy, u32, wl_fixed_t, wl_fixed_t),
+    pub button: unsafe extern "C" fn(*mut c_void, *mut wl_proxy, u32, u32, u32, u32),
+    pub axis: unsafe extern "C" fn(*mut c_void, *mut wl_proxy, u32, u32, wl_fixed_t),
+    pub frame: unsafe extern "C" fn(*mut c_void, *mut wl_proxy),
+    pub axis_source: unsafe extern "C" fn(*mut c_void, *mut wl_proxy, u32),
+    pub axis_stop: unsafe extern "C" fn(*mut c_void, *mut wl_proxy, u32, u32),
+    pub axis_discrete: unsafe extern "C" fn(*mut c_void, *mut wl_proxy, u32, i32),
+}
+#[repr(C)]
+pub struct wl_keyboard_listener {
+    pub keymap: unsafe extern "C" fn(*mut c_void, *mut wl_proxy, u32, i32, u32),
+    pub enter: unsafe extern "C" fn(*mut c_void, *mut wl_proxy, u32, *mut wl_proxy, *mut wl_array),
+    pub leave: unsafe extern "C" fn(*mut c_void, *mut wl_proxy, u32, *mut wl_proxy),
+    pub key: unsafe extern "C" fn(*mut c_void, *mut wl_proxy, u32, u32, u32, u32),
+    pub modifiers: unsafe extern "C" fn(*mut c_void, *mut wl_proxy, u32, u32, u32, u32, u32),
+    pub repeat_info: unsafe extern "C" fn(*mut c_void, *mut wl_proxy, i32, i32),
+}
+#[repr(C)]
+pub struct wl_output_listener {
+    pub geometry: unsafe extern "C" fn(
+        *mut c_void,
+        *mut wl_proxy,
+        i32,
+        i32,
+        i32,
+        i32,
+        i32,
+        *const c_char,
+        *const c_char,
+        i32,
+    ),
+    pub mode: unsafe extern "C" fn(*mut c_void, *mut wl_proxy, u32, i32, i32, i32),
+    pub done: unsafe extern "C" fn(*mut c_void, *mut wl_proxy),
+    pub scale: unsafe extern "C" fn(*mut c_void, *mut wl_proxy, i32),
+    pub name: unsafe extern "C" fn(*mut c_void, *mut wl_proxy, *const c_char),
+    pub description: unsafe extern "C" fn(*mut c_void, *mut wl_proxy, *const c_char),
+}
+#[repr(C)]
+pub struct zwp_relative_pointer_v1_listener {
+    pub relative_motion: unsafe extern "C" fn(
+        *mut c_void,
+        *mut wl_proxy,
+        u32,
+        u32,
+        wl_fixed_t,
+        wl_fixed_t,
+        wl_fixed_t,
+        wl_fixed_t,
+    ),
+}
+#[repr(C)]
+pub struct zwp_text_input_v3_listener {
+    pub enter: unsafe extern "C" fn(*mut c_void, *mut wl_proxy, *mut wl_proxy),
+    pub leave: unsafe extern "C" fn(*mut c_void, *mut wl_proxy, *mut wl_proxy),
+    pub preedit_string: unsafe extern "C" fn(*mut c_void, *mut wl_proxy, *const c_char, i32, i32),
+    pub commit_string: unsafe extern "C" fn(*mut c_void, *mut wl_proxy, *const c_char),
+    pub delete_surrounding_text: unsafe extern "C" fn(*mut c_void, *mut wl_proxy, u32, u32),
+    pub done: unsafe extern "C" fn(*mut c_void, *mut wl_proxy, u32),
+}
+#[repr(C)]
+pub struct xdg_wm_base_listener {
+    pub ping: unsafe extern "C" fn(*mut c_void, *mut wl_proxy, u32),
+}
+#[repr(C)]
+pub struct xdg_surface_listener {
+    pub configure: unsafe extern "C" fn(*mut c_void, *mut wl_proxy, u32),
+}
+#[repr(C)]
+pub struct xdg_toplevel_listener {
+    pub configure: unsafe extern "C" fn(*mut c_void, *mut wl_proxy, i32, i32, *mut wl_array),
+    pub close: unsafe extern "C" fn(*mut c_void, *mut wl_proxy),
+}
+
+// Requests (what `wayland-scanner` would generate as `static inline` functions)
+
+/// Placeholder for the `new_id` argument of constructors, which `libwayland-client` fills in.
+const NEW_ID: wl_argument = wl_argument { o: ptr::null_mut() };
+
+#[inline]
+unsafe fn marshal(proxy: *mut wl_proxy, opcode: u32, args: &mut [wl_argument]) {
+    wl_proxy_marshal_array(proxy, opcode, args.as_mut_ptr());
+}
+
+#[inline]
+unsafe fn marshal_constructor(
+    proxy: *mut wl_proxy,
+    opcode: u32,
+    interface: &'static wl_interface,
+    args: &mut [wl_argument],
+) -> *mut wl_proxy {
+    wl_proxy_marshal_array_constructor(proxy, opcode, args.as_mut_ptr(), interface)
+}
+
+pub unsafe fn wl_display_get_registry(display: *mut wl_display) -> *mut wl_proxy {
+    marshal_constructor(display.cast(), 1, &wl_registry_interface, &mut [NEW_ID])
+}
+
+pub unsafe fn wl_registry_bind(
+    registry: *mut wl_proxy,
+    name: u32,
+    interface: &'static wl_interface,
+    version: u32,
+) -> *mut wl_proxy {
+    let mut args = [
+        wl_argument { u: name },
+        wl_argument { s: interface.name },
+        wl_argument { u: version },
+        NEW_ID,
+    ];
+    wl_proxy_marshal_array_constructor_versioned(registry, 0, args.as_mut_ptr(), interface, version)
+}
+
+pub unsafe fn wl_compositor_create_surface(compositor: *mut wl_proxy) -> *mut wl_proxy {
+    marshal_constructor(compositor, 0, &wl_surface_interface, &mut [NEW_ID])
+}
+
+pub unsafe fn wl_shm_create_pool(shm: *mut wl_proxy, fd: i32, size: i32) -> *mut wl_proxy {
+    marshal_constructor(shm, 0, &wl_shm_pool_interface, &mut [NEW_ID, wl_argument { h: fd }, wl_argument { i: size }])
+}
+
+pub unsafe fn wl_shm_pool_create_buffer(
+    pool: *mut wl_proxy,
+    offset: i32,
+    width: i32,
+    height: i32,
+    stride: i32,
+    format: u32,
+) -> *mut wl_proxy {
+    let mut args = [
+        NEW_ID,
+        wl_argument { i: offset },
+        wl_argument { i: width },
+        wl_argument { i: height },
+        wl_argument { i: stride },
+        wl_argument { u: format },
+    ];
+    marshal_constructor(pool, 0, &wl_buffer_interface, &mut args)
+}
+
+pub unsafe fn wl_shm_pool_destroy(pool: *mut wl_proxy) {
+    marshal(pool, 1, &mut []);
+    wl_proxy_destroy(pool);
+}
+
+pub unsafe fn wl_buffer_destroy(buffer: *mut wl_proxy) {
+    marshal(buffer, 0, &mut []);
+    wl_proxy_destroy(buffer);
+}
+
+pub unsafe fn wl_surface_destroy(surface: *mut wl_proxy) {
+    marshal(surface, 0, &mut []);
+    wl_proxy_destroy(surface);
+}
+
+pub unsafe fn wl_surface_attach(surface: *mut wl_proxy, buffer: *mut wl_proxy, x: i32, y: i32) {
+    marshal(surface, 1, &mut [wl_argument { o: buffer }, wl_argument { i: x }, wl_argument { i: y }]);
+}
+
+pub unsafe fn wl_surface_damage(surface: *mut wl_proxy, x: i32, y: i32, width: i32, height: i32) {
+    let mut args = [wl_argument { i: x }, wl_argument { i: y }, wl_argument { i: width }, wl_argument { i: height }];
+    marshal(surface, 2, &mut args);
+}
+
+pub unsafe fn wl_surface_commit(surface: *mut wl_proxy) {
+    marshal(surface, 6, &mut []);
+}
+
+pub unsafe fn wl_surface_set_buffer_scale(surface: *mut wl_proxy, scale: i32) {
+    // Since version 3, older surfaces can only be unscaled
+    if wl_proxy_get_version(surface) >= 3 {
+        marshal(surface, 8, &mut [wl_argument { i: scale }]);
+    }
+}
+
+pub unsafe fn wl_seat_get_pointer(seat: *mut wl_proxy) -> *mut wl_proxy {
+    marshal_constructor(seat, 0, &wl_pointer_interface, &mut [NEW_ID])
+}
+
+pub unsafe fn wl_seat_get_keyboard(seat: *mut wl_proxy) -> *mut wl_proxy {
+    marshal_constructor(seat, 1, &wl_keyboard_interface, &mut [NEW_ID])
+}
+
+pub unsafe fn wl_seat_release(seat: *mut wl_proxy) {
+    // Destructor since version 5, older seats can only be forgotten about
+    if wl_proxy_get_version(seat) >= 5 {
+        marshal(seat, 3, &mut []);
+    }
+    wl_proxy_destroy(seat);
+}
+
+pub unsafe fn wl_pointer_release(pointer: *mut wl_proxy) {
+    // Destructor since version 3
+    if wl_proxy_get_version(pointer) >= 3 {
+        marshal(pointer, 1, &mut []);
+    }
+    wl_proxy_destroy(pointer);
+}
+
+pub unsafe fn wl_keyboard_release(keyboard: *mut wl_proxy) {
+    // Destructor since version 3
+    if wl_proxy_get_version(keyboard) >= 3 {
+        marshal(keyboard, 0, &mut []);
+    }
+    wl_proxy_destroy(keyboard);
+}
+
+pub unsafe fn wl_output_release(output: *mut wl_proxy) {
+    // Destructor since version 3
+    if wl_proxy_get_version(output) >= 3 {
+        marshal(output, 0, &mut []);
+    }
+    wl_proxy_destroy(output);
+}
+
+pub unsafe fn xdg_wm_base_destroy(wm_base: *mut wl_proxy) {
+    marshal(wm_base, 0, &mut []);
+    wl_proxy_destroy(wm_base);
+}
+
+pub unsafe fn xdg_wm_base_get_xdg_surface(wm_base: *mut wl_proxy, surface: *mut wl_proxy) -> *mut wl_proxy {
+    marshal_constructor(wm_base, 2, &xdg_surface_interface, &mut [NEW_ID, wl_argument { o: surface }])
+}
+
+pub unsafe fn xdg_wm_base_pong(wm_base: *mut wl_proxy, serial: u32) {
+    marshal(wm_base, 3, &mut [wl_argument { u: serial }]);
+}
+
+pub unsafe fn xdg_surface_destroy(xdg_surface: *mut wl_proxy) {
+    marshal(xdg_surface, 0, &mut []);
+    wl_proxy_destroy(xdg_surface);
+}
+
+pub unsafe fn xdg_surface_get_toplevel(xdg_surface: *mut wl_proxy) -> *mut wl_proxy {
+    marshal_constructor(xdg_surface, 1, &xdg_toplevel_interface, &mut [NEW_ID])
+}
+
+pub unsafe fn xdg_surface_ack_configure(xdg_surface: *mut wl_proxy, serial: u32) {
+    marshal(xdg_surface, 4, &mut [wl_argument { u: serial }]);
+}
+
+pub unsafe fn xdg_toplevel_destroy(toplevel: *mut wl_proxy) {
+    marshal(toplevel, 0, &mut []);
+    wl_proxy_destroy(toplevel);
+}
+
+pub unsafe fn xdg_toplevel_set_title(toplevel: *mut wl_proxy, title: *const c_char) {
+    marshal(toplevel, 2, &mut [wl_argument { s: title }]);
+}
+
+pub unsafe fn xdg_toplevel_set_app_id(toplevel: *mut wl_proxy, app_id: *const c_char) {
+    marshal(toplevel, 3, &mut [wl_argument { s: app_id }]);
+}
+
+pub unsafe fn xdg_toplevel_set_max_size(toplevel: *mut wl_proxy, width: i32, height: i32) {
+    marshal(toplevel, 7, &mut [wl_argument { i: width }, wl_argument { i: height }]);
+}
+
+pub unsafe fn xdg_toplevel_set_min_size(toplevel: *mut wl_proxy, width: i32, height: i32) {
+    marshal(toplevel, 8, &mut [wl_argument { i: width }, wl_argument { i: height }]);
+}
+
+pub unsafe fn xdg_toplevel_set_maximized(toplevel: *mut wl_proxy) {
+    marshal(toplevel, 9, &mut []);
+}
+
+pub unsafe fn xdg_toplevel_unset_maximized(toplevel: *mut wl_proxy) {
+    marshal(toplevel, 10, &mut []);
+}
+
+pub unsafe fn xdg_toplevel_set_fullscreen(toplevel: *mut wl_proxy, output: *mut wl_proxy) {
+    marshal(toplevel, 11, &mut [wl_argument { o: output }]);
+}
+
+pub unsafe fn xdg_toplevel_unset_fullscreen(toplevel: *mut wl_proxy) {
+    marshal(toplevel, 12, &mut []);
+}
+
+pub unsafe fn xdg_toplevel_set_minimized(toplevel: *mut wl_proxy) {
+    marshal(toplevel, 13, &mut []);
+}
+
+pub unsafe fn zwp_relative_pointer_manager_v1_destroy(manager: *mut wl_proxy) {
+    marshal(manager, 0, &mut []);
+    wl_proxy_destroy(manager);
+}
+
+pub unsafe fn zwp_relative_pointer_manager_v1_get_relative_pointer(
+    manager: *mut wl_proxy,
+    pointer: *mut wl_proxy,
+) -> *mut wl_proxy {
+    marshal_constructor(manager, 1, &zwp_relative_pointer_v1_interface, &mut [NEW_ID, wl_argument { o: pointer }])
+}
+
+pub unsafe fn zwp_relative_pointer_v1_destroy(relative_pointer: *mut wl_proxy) {
+    marshal(relative_pointer, 0, &mut []);
+    wl_proxy_destroy(relative_pointer);
+}
+
+pub unsafe fn zwp_text_input_manager_v3_destroy(manager: *mut wl_proxy) {
+    marshal(manager, 0, &mut []);
+    wl_proxy_destroy(manager);
+}
+
+pub unsafe fn zwp_text_input_manager_v3_get_text_input(manager: *mut wl_proxy, seat: *mut wl_proxy) -> *mut wl_proxy {
+    marshal_constructor(manager, 1, &zwp_text_input_v3_interface, &mut [NEW_ID, wl_argument { o: seat }])
+}
+
+pub unsafe fn zwp_text_input_v3_destroy(text_input: *mut wl_proxy) {
+    marshal(text_input, 0, &mut []);
+    wl_proxy_destroy(text_input);
+}
+
+pub unsafe fn zwp_text_input_v3_enable(text_input: *mut wl_proxy) {
+    marshal(text_input, 1, &mut []);
+}
+
+pub unsafe fn zwp_text_input_v3_disable(text_input: *mut wl_proxy) {
+    marshal(text_input, 2, &mut []);
+}
+
+pub unsafe fn zwp_text_input_v3_set_cursor_rectangle(text_input: *mut wl_proxy, x: i32, y: i32, width: i32, height: i32) {
+    let mut args = [wl_argument { i: x }, wl_argument { i: y }, wl_argument { i: width }, wl_argument { i: height }];
+    marshal(text_input, 6, &mut args);
+}
+
+pub unsafe fn zwp_text_input_v3_commit(text_input: *mut wl_proxy) {
+    marshal(text_input, 7, &mut []);
+}
+
+pub unsafe fn zxdg_decoration_manager_v1_destroy(manager: *mut wl_proxy) {
+    marshal(manager, 0, &mut []);
+    wl_proxy_destroy(manager);
+}
+
+pub unsafe fn zxdg_decoration_manager_v1_get_toplevel_decoration(
+    manager: *mut wl_proxy,
+    toplevel: *mut wl_proxy,
+) -> *mut wl_proxy {
+    marshal_constructor(manager, 1, &zxdg_toplevel_decoration_v1_interface, &mut [NEW_ID, wl_argument { o: toplevel }])
+}
+
+pub unsafe fn zxdg_toplevel_decoration_v1_destroy(decoration: *mut wl_proxy) {
+    marshal(decoration, 0, &mut []);
+    wl_proxy_destroy(decoration);
+}
+
+pub unsafe fn zxdg_toplevel_decoration_v1_set_mode(decoration: *mut wl_proxy, mode: u32) {
+    marshal(decoration, 1, &mut [wl_argument { u: mode }]);
+}
+
+pub unsafe fn wp_cursor_shape_manager_v1_destroy(manager: *mut wl_proxy) {
+    marshal(manager, 0, &mut []);
+    wl_proxy_destroy(manager);
+}
+
+pub unsafe fn wp_cursor_shape_manager_v1_get_pointer(
+    manager: *mut wl_proxy,
+    pointer: *mut wl_proxy,
+) -> *mut wl_proxy {
+    marshal_constructor(manager, 1, &wp_cursor_shape_device_v1_interface, &mut [NEW_ID, wl_argument { o: pointer }])
+}
+
+pub unsafe fn wp_cursor_shape_device_v1_destroy(device: *mut wl_proxy) {
+    marshal(device, 0, &mut []);
+    wl_proxy_destroy(device);
+}
+
+pub unsafe fn wp_cursor_shape_device_v1_set_shape(device: *mut wl_proxy, serial: u32, shape: u32) {
+    marshal(device, 1, &mut [wl_argument { u: serial }, wl_argument { u: shape }]);
+}
@@ -6,10 +6,11 @@ pub(crate) mod util;
 use api::*;
 use crate::{
     error::Error,
-    event::{CloseReason, Event},
+    event::{CloseReason, Event, Key, MouseButton},
     helpers::{LazyCell, sync::{condvar_notify1, condvar_wait, mutex_lock, Condvar, Mutex}},
-    monitor::{Scale, Size},
-    window::{WindowBuilder, WindowControls, WindowImpl, WindowStyle},
+    monitor::{Monitor, Point, Scale, Size, VideoMode},
+    platform::Backend,
+    window::{CursorIcon, Fullscreen, SizeLimits, WindowBuilder, WindowControls, WindowImpl, WindowStyle},
 };
 use std::{cell, fmt, mem, ops, ptr, sync::{self, atomic::{self, AtomicBool}}, thread};
 
@@ -39,6 +40,20 @@ const RAMEN_WM_SETTHICKFRAME: UINT = WM_USER + 4;
 const RAMEN_WM_GETDPIDATA: UINT = WM_USER + 5;
 #[cfg(feature = "cursor-lock")]
 const RAMEN_WM_SETCURSORLOCK: UINT = WM_USER + 6;
+const RAMEN_WM_SETIMEALLOWED: UINT = WM_USER + 7;
+const RAMEN_WM_SETIMEPOSITION: UINT = WM_USER + 8;
+const RAMEN_WM_SETFULLSCREEN: UINT = WM_USER + 9;
+const RAMEN_WM_SETINNERSIZE: UINT = WM_USER + 10;
+const RAMEN_WM_SETOUTERPOSITION: UINT = WM_USER + 11;
+const RAMEN_WM_SETMININNERSIZE: UINT = WM_USER + 12;
+const RAMEN_WM_SETMAXINNERSIZE: UINT = WM_USER + 13;
+const RAMEN_WM_SETASPECTRATIO: UINT = WM_USER + 14;
+const RAMEN_WM_GETWINDOWSTYLE: UINT = WM_USER + 15;
+const RAMEN_WM_GETFOCUSSTATE: UINT = WM_USER + 16;
+const RAMEN_WM_SETBORDERLESS: UINT = WM_USER + 17;
+const RAMEN_WM_SETCURSORICON: UINT = WM_USER + 18;
+const RAMEN_WM_SETMAXIMIZED: UINT = WM_USER + 19;
+const RAMEN_WM_SETMINIMIZED: UINT = WM_USER + 20;
 
 #[derive(Debug)]
 pub struct InternalError {
@@ -183,6 +198,7 @@ impl WindowStyle {
 
 struct WindowUserData {
     close_reason: Option<CloseReason>,
+    cursor_icon: CursorIcon,
     #[cfg(feature = "cursor-lock")]
     cursor_constrain_escaped: bool,
     #[cfg(feature = "cursor-lock")]
@@ -190,11 +206,30 @@ struct WindowUserData {
     destroy_flag: AtomicBool,
     event_queue: Mutex<Vec<Event>>,
     focus_state: bool,
+    fullscreen: Option<FullscreenState>,
+    high_surrogate: Option<u16>,
+    ime_allowed: bool,
+    ime_position: Point,
+    maximized: AtomicBool,
+    minimized: AtomicBool,
+    mouse_inside: bool,
+    outer_position: Mutex<Point>,
+    size_limits: SizeLimits,
     window_style: WindowStyle,
 
     dpi_data: WindowUserDpiData,
 }
 
+/// What's needed to leave fullscreen again.
+struct FullscreenState {
+    fullscreen: Fullscreen,
+    windowed_rect: RECT,
+    // Whether the window should be maximized once it's windowed again
+    maximized: bool,
+    // The adapter whose video mode was changed, for `Fullscreen::Exclusive`
+    changed_device: Option<[WCHAR; CCHDEVICENAME]>,
+}
+
 #[derive(Copy, Clone, Default)]
 struct WindowUserDpiData {
     client: (u32, u32),
@@ -203,10 +238,34 @@ struct WindowUserDpiData {
     scale_factor: f64,
 }
 
+impl WindowUserDpiData {
+    /// Gets the client size in the `Size` convention the window was created with, along with the scale.
+    fn inner_size(&self) -> (Size, Scale) {
+        let (width, height) = self.client;
+        let inner_size = Size::Physical(width, height);
+        if self.is_logical {
+            (inner_size.to_logical(self.scale_factor), self.scale_factor)
+        } else {
+            (inner_size, self.scale_factor)
+        }
+    }
+
+    /// Converts a physical position to the `Point` convention the window was created with.
+    fn point(&self, x: LONG, y: LONG) -> Point {
+        let point = Point::Physical(x, y);
+        if self.is_logical {
+            point.to_logical(self.scale_factor)
+        } else {
+            point
+        }
+    }
+}
+
 impl Default for WindowUserData {
     fn default() -> Self {
         Self {
             close_reason: None,
+            cursor_icon: CursorIcon::Arrow,
             #[cfg(feature = "cursor-lock")]
             cursor_constrain_escaped: false,
             #[cfg(feature = "cursor-lock")]
@@ -214,6 +273,15 @@ impl Default for WindowUserData {
             destroy_flag: AtomicBool::new(false),
             event_queue: Mutex::new(Vec::with_capacity(EVENT_BUF_INITIAL_SIZE)),
             focus_state: false,
+            fullscreen: None,
+            high_surrogate: None,
+            ime_allowed: false,
+            ime_position: Point::Physical(0, 0),
+            maximized: AtomicBool::new(false),
+            minimized: AtomicBool::new(false),
+            mouse_inside: false,
+            outer_position: Mutex::new(Point::Physical(0, 0)),
+            size_limits: Default::default(),
             window_style: Default::default(),
             dpi_data: Default::default(),
         }
@@ -267,8 +335,22 @@ pub(crate) fn make_window(builder: &WindowBuilder) -> Result<WindowRepr, Error>
         let style = builder.style.dword_style();
         let style_ex = builder.style.dword_style_ex();
 
-        let dpi = util::BASE_DPI;
+        // The window could end up on another monitor, which `WM_CREATE` accounts for
+        let dpi = WIN32.primary_monitor_dpi();
         let (width, height) = WIN32.adjust_window_for_dpi(builder.inner_size, style, style_ex, dpi);
+        let (x, y) = if let Some(position) = builder.position {
+            let (x, y) = position.physical(dpi as f64 / util::BASE_DPI as f64);
+            (x as c_int, y as c_int)
+        } else if builder.centered {
+            let mut info: MONITORINFOEXW = mem::zeroed();
+            info.cbSize = mem::size_of_val(&info) as DWORD;
+            let _ = GetMonitorInfoW(MonitorFromPoint(POINT { x: 0, y: 0 }, MONITOR_DEFAULTTOPRIMARY), &mut info);
+            let work = &info.rcWork;
+            let (work_width, work_height) = util::rect_to_size2d(work);
+            (work.left + (work_width - width) / 2, work.top + (work_height - height) / 2)
+        } else {
+            (CW_USEDEFAULT, CW_USEDEFAULT)
+        };
         let user_data: Box<cell::UnsafeCell<WindowUserData>> = Default::default();
 
         let builder_ptr = (&builder) as *const WindowBuilder;
@@ -287,8 +369,8 @@ pub(crate) fn make_window(builder: &WindowBuilder) -> Result<WindowRepr, Error>
             class_name,
             util::str_to_wide_null(builder.title.as_ref(), &mut title),
             style,
-            CW_USEDEFAULT,
-            CW_USEDEFAULT,
+            x,
+            y,
             width,
             height,
             ptr::null_mut(),
@@ -352,7 +434,8 @@ pub(crate) fn make_window(builder: &WindowBuilder) -> Result<WindowRepr, Error>
                     break 'message_loop
                 },
                 _ => {
-                    // Dispatch message to WindowProc
+                    // Generate `WM_CHAR` from key presses, then dispatch message to WindowProc
+                    let _ = TranslateMessage(&msg);
                     let _ = DispatchMessageW(&msg);
                 },
             }
@@ -381,6 +464,203 @@ pub(crate) fn make_window(builder: &WindowBuilder) -> Result<WindowRepr, Error>
     }
 }
 
+pub(crate) fn monitors() -> Result<Vec<Monitor>, Error> {
+    unsafe extern "system" fn enum_proc(hmonitor: HMONITOR, _hdc: HDC, _clip: *mut RECT, data: LPARAM) -> BOOL {
+        let monitors = &mut *(data as *mut Vec<Monitor>);
+        if let Some(monitor) = make_monitor(hmonitor) {
+            monitors.push(monitor);
+        }
+        TRUE // continue
+    }
+
+    let mut monitors = Vec::new();
+    unsafe {
+        let data = (&mut monitors) as *mut Vec<Monitor> as LPARAM;
+        if EnumDisplayMonitors(ptr::null_mut(), ptr::null(), enum_proc, data) == FALSE {
+            return Err(Error::from_internal(InternalError::from_winapi("EnumDisplayMonitors failed.", GetLastError())))
+        }
+    }
+    Ok(monitors)
+}
+
+/// Gets everything about a monitor, or `None` if it's been disconnected since the handle was acquired.
+unsafe fn make_monitor(hmonitor: HMONITOR) -> Option<Monitor> {
+    let mut info: MONITORINFOEXW = mem::zeroed();
+    info.cbSize = mem::size_of_val(&info) as DWORD;
+    if GetMonitorInfoW(hmonitor, &mut info) == FALSE {
+        return None
+    }
+
+    // The monitor's device name is the adapter's, such as `\\.\DISPLAY1`, which isn't very nice to show anyone
+    let mut device: DISPLAY_DEVICEW = mem::zeroed();
+    device.cb = mem::size_of_val(&device) as DWORD;
+    let name = if EnumDisplayDevicesW(info.szDevice.as_ptr(), 0, &mut device, 0) != FALSE {
+        util::wide_array_to_string(&device.DeviceString)
+    } else {
+        util::wide_array_to_string(&info.szDevice)
+    };
+
+    let mut mode: DEVMODEW = mem::zeroed();
+    mode.dmSize = mem::size_of_val(&mode) as WORD;
+    let has_mode = EnumDisplaySettingsW(info.szDevice.as_ptr(), ENUM_CURRENT_SETTINGS, &mut mode) != FALSE;
+
+    // Frequencies of 0 and 1 both mean "the hardware default", whatever that is
+    let refresh_rate = (has_mode && mode.dmDisplayFrequency > 1).then(|| f64::from(mode.dmDisplayFrequency));
+
+    let bounds = |rect: &RECT| {
+        let (width, height) = util::rect_to_size2d(rect);
+        (Point::Physical(rect.left, rect.top), Size::Physical(width as u32, height as u32))
+    };
+    let (position, size) = bounds(&info.rcMonitor);
+    Some(Monitor {
+        backend: Backend::Win32,
+        id: hmonitor as usize as u64,
+        name,
+        is_primary: info.dwFlags & MONITORINFOF_PRIMARY != 0,
+        position,
+        size,
+        work_area: bounds(&info.rcWork),
+        scale: WIN32.monitor_dpi(hmonitor) as f64 / util::BASE_DPI as f64,
+        refresh_rate,
+    })
+}
+
+pub(crate) fn video_modes(monitor: &Monitor) -> Vec<VideoMode> {
+    let mut modes = Vec::new();
+    unsafe {
+        let mut info: MONITORINFOEXW = mem::zeroed();
+        info.cbSize = mem::size_of_val(&info) as DWORD;
+        if GetMonitorInfoW(monitor.id as usize as HMONITOR, &mut info) == FALSE {
+            return modes
+        }
+        let mut devmode: DEVMODEW = mem::zeroed();
+        devmode.dmSize = mem::size_of_val(&devmode) as WORD;
+        let mut index = 0;
+        while EnumDisplaySettingsW(info.szDevice.as_ptr(), index, &mut devmode) != FALSE {
+            index += 1;
+            let mode = VideoMode {
+                monitor: monitor.clone(),
+                id: 0,
+                size: (devmode.dmPelsWidth, devmode.dmPelsHeight),
+                bit_depth: devmode.dmBitsPerPel as u16,
+                refresh_rate: devmode.dmDisplayFrequency.into(),
+            };
+            // The same mode is listed again for every scaling and orientation setting
+            if !modes.contains(&mode) {
+                modes.push(mode);
+            }
+        }
+    }
+    modes
+}
+
+/// Enters, changes or leaves fullscreen, putting the video mode and window rect back as needed.
+unsafe fn set_fullscreen(hwnd: HWND, user_data: &mut WindowUserData, fullscreen: Option<Fullscreen>) {
+    if user_data.fullscreen.as_ref().map(|state| &state.fullscreen) == fullscreen.as_ref() {
+        return
+    }
+
+    // Every change starts from the original video mode, so a failed switch doesn't leave a changed one behind
+    let (windowed_rect, maximized) = match user_data.fullscreen.take() {
+        Some(state) => {
+            if let Some(device) = &state.changed_device {
+                util::reset_video_mode(device);
+            }
+            (state.windowed_rect, state.maximized)
+        },
+        None => {
+            let mut rect: RECT = mem::zeroed();
+            let _ = GetWindowRect(hwnd, &mut rect);
+            (rect, IsZoomed(hwnd) != FALSE)
+        },
+    };
+
+    let fullscreen = match fullscreen {
+        Some(fullscreen) => fullscreen,
+        None => {
+            let (width, height) = util::rect_to_size2d(&windowed_rect);
+            user_data.window_style.set_for(hwnd);
+            let _ = SetWindowPos(
+                hwnd,
+                ptr::null_mut(),
+                windowed_rect.left,
+                windowed_rect.top,
+                width,
+                height,
+                SWP_FRAMECHANGED | SWP_NOACTIVATE | SWP_NOOWNERZORDER | SWP_NOZORDER,
+            );
+            set_maximized(hwnd, maximized);
+            return
+        },
+    };
+
+    // If the mode can't be set, the window still covers the monitor at whatever mode it's at
+    let (hmonitor, changed_device) = match &fullscreen {
+        Fullscreen::Borderless(Some(monitor)) => (monitor.id as usize as HMONITOR, None),
+        Fullscreen::Borderless(None) => (MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST), None),
+        Fullscreen::Exclusive(mode) => {
+            let hmonitor = mode.monitor.id as usize as HMONITOR;
+            (hmonitor, util::set_video_mode(hmonitor, mode))
+        },
+    };
+    let mut info: MONITORINFOEXW = mem::zeroed();
+    info.cbSize = mem::size_of_val(&info) as DWORD;
+    if GetMonitorInfoW(hmonitor, &mut info) == FALSE {
+        let _ = GetMonitorInfoW(MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST), &mut info);
+    }
+
+    // Set beforehand, so `WM_GETMINMAXINFO` doesn't hold the window to its size limits
+    user_data.fullscreen = Some(FullscreenState { fullscreen, windowed_rect, maximized, changed_device });
+
+    // This is what `WindowStyle::borderless` would give, minus the resize border eating into the monitor.
+    // The window style itself is left alone, it's what gets restored afterwards.
+    let visible = get_window_data(hwnd, GWL_STYLE) as DWORD & WS_VISIBLE;
+    let _ = set_window_data(hwnd, GWL_STYLE, (WS_POPUP | visible) as usize);
+    let rect = &info.rcMonitor;
+    let (width, height) = util::rect_to_size2d(rect);
+    let _ = SetWindowPos(hwnd, ptr::null_mut(), rect.left, rect.top, width, height, SWP_FRAMECHANGED | SWP_NOOWNERZORDER);
+}
+
+/// Maximizes or restores the window, if it isn't that already.
+unsafe fn set_maximized(hwnd: HWND, maximized: bool) {
+    if maximized != (IsZoomed(hwnd) != FALSE) {
+        let _ = ShowWindow(hwnd, if maximized { SW_MAXIMIZE } else { SW_RESTORE });
+    }
+}
+
+/// Fits the window into its size limits after they change.
+/// Nothing's done while fullscreen, as `WM_GETMINMAXINFO` applies them when leaving it.
+unsafe fn update_size_limits(hwnd: HWND, user_data: &mut WindowUserData) {
+    let scale = user_data.dpi_data.scale_factor;
+    let client = user_data.size_limits.clamp(user_data.dpi_data.client, scale);
+    if user_data.fullscreen.is_none() && client != user_data.dpi_data.client {
+        let style = user_data.window_style.dword_style();
+        let style_ex = user_data.window_style.dword_style_ex();
+        let size = Size::Physical(client.0, client.1);
+        let (width, height) = WIN32.adjust_window_for_dpi(size, style, style_ex, user_data.dpi_data.dpi);
+        let _ = SetWindowPos(
+            hwnd,
+            ptr::null_mut(),
+            0,
+            0,
+            width,
+            height,
+            SWP_NOACTIVATE | SWP_NOMOVE | SWP_NOOWNERZORDER | SWP_NOZORDER,
+        );
+    }
+}
+
+impl Window {
+    /// Gets a copy of the window style as last set, from the window thread.
+    fn window_style(&self) -> WindowStyle {
+        let mut style = WindowStyle::default();
+        unsafe {
+            let _ = SendMessageW(self.hwnd, RAMEN_WM_GETWINDOWSTYLE, 0, (&mut style) as *mut WindowStyle as LPARAM);
+        }
+        style
+    }
+}
+
 impl WindowImpl for Window {
     #[inline]
     fn events(&self) -> &[Event] {
@@ -400,6 +680,15 @@ impl WindowImpl for Window {
         }
     }
 
+    #[inline]
+    fn controls(&self) -> Option<WindowControls> {
+        self.window_style().controls
+    }
+
+    fn current_monitor(&self) -> Option<Monitor> {
+        unsafe { make_monitor(MonitorFromWindow(self.hwnd, MONITOR_DEFAULTTONEAREST)) }
+    }
+
     fn inner_size(&self) -> (Size, Scale) {
         let mut data = mem::MaybeUninit::<WindowUserDpiData>::uninit();
         let info = unsafe {
@@ -411,13 +700,83 @@ impl WindowImpl for Window {
             );
             &*data.as_ptr()
         };
-        let (width, height) = info.client;
-        let factor = info.scale_factor;
-        let inner_size = Size::Physical(width, height);
-        if info.is_logical {
-            (inner_size.to_logical(factor), factor)
-        } else {
-            (inner_size, factor)
+        info.inner_size()
+    }
+
+    #[inline]
+    fn is_borderless(&self) -> bool {
+        self.window_style().borderless
+    }
+
+    #[inline]
+    fn is_focused(&self) -> bool {
+        unsafe { SendMessageW(self.hwnd, RAMEN_WM_GETFOCUSSTATE, 0, 0) != 0 }
+    }
+
+    #[inline]
+    fn is_maximized(&self) -> bool {
+        let user_data = unsafe { &*self.user_data.get() };
+        user_data.maximized.load(atomic::Ordering::Acquire)
+    }
+
+    #[inline]
+    fn is_minimized(&self) -> bool {
+        let user_data = unsafe { &*self.user_data.get() };
+        user_data.minimized.load(atomic::Ordering::Acquire)
+    }
+
+    #[inline]
+    fn is_resizable(&self) -> bool {
+        self.window_style().resizable
+    }
+
+    #[inline]
+    fn is_visible(&self) -> bool {
+        self.window_style().visible
+    }
+
+    fn outer_position(&self) -> Point {
+        // Only ever locked briefly by the window thread, so there's no need to go through it
+        let user_data = unsafe { &*self.user_data.get() };
+        *mutex_lock(&user_data.outer_position)
+    }
+
+    fn title(&self) -> String {
+        unsafe {
+            // The length doesn't include the null terminator, and might be an overestimate
+            let mut wstr: Vec<WCHAR> = vec![0; GetWindowTextLengthW(self.hwnd).max(0) as usize + 1];
+            let _ = GetWindowTextW(self.hwnd, wstr.as_mut_ptr(), wstr.len() as c_int);
+            util::wide_array_to_string(&wstr)
+        }
+    }
+
+    #[inline]
+    fn set_aspect_ratio(&self, aspect_ratio: Option<(u32, u32)>) {
+        let (x, y) = aspect_ratio.unwrap_or((0, 0));
+        unsafe {
+            let _ = SendMessageW(self.hwnd, RAMEN_WM_SETASPECTRATIO, x as WPARAM, y as LPARAM);
+        }
+    }
+
+    #[inline]
+    fn set_aspect_ratio_async(&self, aspect_ratio: Option<(u32, u32)>) {
+        let (x, y) = aspect_ratio.unwrap_or((0, 0));
+        unsafe {
+            let _ = PostMessageW(self.hwnd, RAMEN_WM_SETASPECTRATIO, x as WPARAM, y as LPARAM);
+        }
+    }
+
+    #[inline]
+    fn set_borderless(&self, borderless: bool) {
+        unsafe {
+            let _ = SendMessageW(self.hwnd, RAMEN_WM_SETBORDERLESS, borderless as WPARAM, 0);
+        }
+    }
+
+    #[inline]
+    fn set_borderless_async(&self, borderless: bool) {
+        unsafe {
+            let _ = PostMessageW(self.hwnd, RAMEN_WM_SETBORDERLESS, borderless as WPARAM, 0);
         }
     }
 
@@ -437,6 +796,24 @@ impl WindowImpl for Window {
         }
     }
 
+    #[inline]
+    fn set_cursor_icon(&self, icon: CursorIcon) {
+        let ptr = Box::into_raw(Box::new(icon));
+        unsafe {
+            let _ = SendMessageW(self.hwnd, RAMEN_WM_SETCURSORICON, 0, ptr as LPARAM);
+        }
+    }
+
+    #[inline]
+    fn set_cursor_icon_async(&self, icon: CursorIcon) {
+        let ptr = Box::into_raw(Box::new(icon));
+        unsafe {
+            if PostMessageW(self.hwnd, RAMEN_WM_SETCURSORICON, 0, ptr as LPARAM) == 0 {
+                mem::drop(Box::from_raw(ptr)); // never going to be received
+            }
+        }
+    }
+
     #[cfg(feature = "cursor-lock")]
     #[inline]
     fn set_cursor_lock(&self, mode: Option<CursorLock>) {
@@ -455,6 +832,144 @@ impl WindowImpl for Window {
         }
     }
 
+    fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) {
+        let ptr = Box::into_raw(Box::new(fullscreen));
+        unsafe {
+            let _ = SendMessageW(self.hwnd, RAMEN_WM_SETFULLSCREEN, 0, ptr as LPARAM);
+        }
+    }
+
+    fn set_fullscreen_async(&self, fullscreen: Option<Fullscreen>) {
+        let ptr = Box::into_raw(Box::new(fullscreen));
+        unsafe {
+            if PostMessageW(self.hwnd, RAMEN_WM_SETFULLSCREEN, 0, ptr as LPARAM) == 0 {
+                mem::drop(Box::from_raw(ptr)); // never going to be received
+            }
+        }
+    }
+
+    #[inline]
+    fn set_ime_allowed(&self, allowed: bool) {
+        unsafe {
+            let _ = SendMessageW(self.hwnd, RAMEN_WM_SETIMEALLOWED, allowed as WPARAM, 0);
+        }
+    }
+
+    #[inline]
+    fn set_ime_allowed_async(&self, allowed: bool) {
+        unsafe {
+            let _ = PostMessageW(self.hwnd, RAMEN_WM_SETIMEALLOWED, allowed as WPARAM, 0);
+        }
+    }
+
+    fn set_ime_position(&self, position: Point) {
+        let ptr = Box::into_raw(Box::new(position));
+        unsafe {
+            let _ = SendMessageW(self.hwnd, RAMEN_WM_SETIMEPOSITION, 0, ptr as LPARAM);
+        }
+    }
+
+    fn set_ime_position_async(&self, position: Point) {
+        let ptr = Box::into_raw(Box::new(position));
+        unsafe {
+            if PostMessageW(self.hwnd, RAMEN_WM_SETIMEPOSITION, 0, ptr as LPARAM) == 0 {
+                mem::drop(Box::from_raw(ptr)); // never going to be received
+            }
+        }
+    }
+
+    fn set_inner_size(&self, inner_size: Size) {
+        let ptr = Box::into_raw(Box::new(inner_size));
+        unsafe {
+            let _ = SendMessageW(self.hwnd, RAMEN_WM_SETINNERSIZE, 0, ptr as LPARAM);
+        }
+    }
+
+    fn set_inner_size_async(&self, inner_size: Size) {
+        let ptr = Box::into_raw(Box::new(inner_size));
+        unsafe {
+            if PostMessageW(self.hwnd, RAMEN_WM_SETINNERSIZE, 0, ptr as LPARAM) == 0 {
+                mem::drop(Box::from_raw(ptr)); // never going to be received
+            }
+        }
+    }
+
+    fn set_max_inner_size(&self, max_inner_size: Option<Size>) {
+        let ptr = Box::into_raw(Box::new(max_inner_size));
+        unsafe {
+            let _ = SendMessageW(self.hwnd, RAMEN_WM_SETMAXINNERSIZE, 0, ptr as LPARAM);
+        }
+    }
+
+    fn set_max_inner_size_async(&self, max_inner_size: Option<Size>) {
+        let ptr = Box::into_raw(Box::new(max_inner_size));
+        unsafe {
+            if PostMessageW(self.hwnd, RAMEN_WM_SETMAXINNERSIZE, 0, ptr as LPARAM) == 0 {
+                mem::drop(Box::from_raw(ptr)); // never going to be received
+            }
+        }
+    }
+
+    #[inline]
+    fn set_maximized(&self, maximized: bool) {
+        unsafe {
+            let _ = SendMessageW(self.hwnd, RAMEN_WM_SETMAXIMIZED, maximized as WPARAM, 0);
+        }
+    }
+
+    #[inline]
+    fn set_maximized_async(&self, maximized: bool) {
+        unsafe {
+            let _ = PostMessageW(self.hwnd, RAMEN_WM_SETMAXIMIZED, maximized as WPARAM, 0);
+        }
+    }
+
+    fn set_min_inner_size(&self, min_inner_size: Option<Size>) {
+        let ptr = Box::into_raw(Box::new(min_inner_size));
+        unsafe {
+            let _ = SendMessageW(self.hwnd, RAMEN_WM_SETMININNERSIZE, 0, ptr as LPARAM);
+        }
+    }
+
+    fn set_min_inner_size_async(&self, min_inner_size: Option<Size>) {
+        let ptr = Box::into_raw(Box::new(min_inner_size));
+        unsafe {
+            if PostMessageW(self.hwnd, RAMEN_WM_SETMININNERSIZE, 0, ptr as LPARAM) == 0 {
+                mem::drop(Box::from_raw(ptr)); // never going to be received
+            }
+        }
+    }
+
+    #[inline]
+    fn set_minimized(&self, minimized: bool) {
+        unsafe {
+            let _ = SendMessageW(self.hwnd, RAMEN_WM_SETMINIMIZED, minimized as WPARAM, 0);
+        }
+    }
+
+    #[inline]
+    fn set_minimized_async(&self, minimized: bool) {
+        unsafe {
+            let _ = PostMessageW(self.hwnd, RAMEN_WM_SETMINIMIZED, minimized as WPARAM, 0);
+        }
+    }
+
+    fn set_outer_position(&self, position: Point) {
+        let ptr = Box::into_raw(Box::new(position));
+        unsafe {
+            let _ = SendMessageW(self.hwnd, RAMEN_WM_SETOUTERPOSITION, 0, ptr as LPARAM);
+        }
+    }
+
+    fn set_outer_position_async(&self, position: Point) {
+        let ptr = Box::into_raw(Box::new(position));
+        unsafe {
+            if PostMessageW(self.hwnd, RAMEN_WM_SETOUTERPOSITION, 0, ptr as LPARAM) == 0 {
+                mem::drop(Box::from_raw(ptr)); // never going to be received
+            }
+        }
+    }
+
     #[inline]
     fn set_resizable(&self, resizable: bool) {
         unsafe {
@@ -526,6 +1041,27 @@ unsafe fn user_data<'a>(hwnd: HWND) -> &'a mut WindowUserData {
     &mut *(get_window_data(hwnd, GWL_USERDATA) as *mut WindowUserData)
 }
 
+/// Turns the UTF-16 code units sent with `WM_CHAR` back into characters.
+///
+/// A high surrogate is held onto until the low surrogate following it arrives.
+/// Lone surrogates are invalid characters, and are dropped.
+fn decode_wm_char(high_surrogate: &mut Option<u16>, unit: u16) -> Option<char> {
+    match unit {
+        0xD800..=0xDBFF => {
+            *high_surrogate = Some(unit);
+            None
+        },
+        0xDC00..=0xDFFF => {
+            let high = high_surrogate.take()?;
+            char::decode_utf16([high, unit].iter().copied()).next()?.ok()
+        },
+        _ => {
+            *high_surrogate = None;
+            char::from_u32(unit.into())
+        },
+    }
+}
+
 unsafe extern "system" fn hcbt_destroywnd_hookproc(code: c_int, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
     if code == HCBT_DESTROYWND {
         let hwnd = wparam as HWND;
@@ -556,6 +1092,19 @@ unsafe extern "system" fn window_proc(hwnd: HWND, msg: UINT, wparam: WPARAM, lpa
         mem::drop(lock);
     }
 
+    /// Updates the maximized and minimized state, sending events for whatever changed.
+    /// Minimizing leaves the maximized state alone, which is why that's optional.
+    unsafe fn update_window_state(user_data: &mut WindowUserData, maximized: Option<bool>, minimized: bool) {
+        if let Some(maximized) = maximized {
+            if user_data.maximized.swap(maximized, atomic::Ordering::AcqRel) != maximized {
+                push_event(user_data, Event::Maximize(maximized));
+            }
+        }
+        if user_data.minimized.swap(minimized, atomic::Ordering::AcqRel) != minimized {
+            push_event(user_data, Event::Minimize(minimized));
+        }
+    }
+
     match msg {
         // No-op event with various uses such as pinging the event loop.
         WM_NULL => 0,
@@ -573,23 +1122,54 @@ unsafe extern "system" fn window_proc(hwnd: HWND, msg: UINT, wparam: WPARAM, lpa
                 util::set_close_button(hwnd, false);
             }
 
-            // TODO: Yeah, not this
-            let dpi = util::BASE_DPI;
+            let dpi = WIN32.window_dpi(hwnd);
             let dpi_fac = dpi as f64 / util::BASE_DPI as f64;
 
+            user_data.cursor_icon = builder.cursor_icon;
+            user_data.size_limits = builder.size_limits;
             user_data.dpi_data = WindowUserDpiData {
-                client: builder.inner_size.physical(dpi_fac),
-                dpi: dpi,
+                client: builder.size_limits.clamp(builder.inner_size.physical(dpi_fac), dpi_fac),
+                dpi,
                 is_logical: matches!(builder.inner_size, Size::Logical(..)),
                 scale_factor: dpi_fac,
             };
 
+            // The window was sized for the primary monitor, which might not be the one it's on,
+            // and without the size limits
+            let mut client: RECT = mem::zeroed();
+            let _ = GetClientRect(hwnd, &mut client);
+            let expected = user_data.dpi_data.client;
+            if util::rect_to_size2d(&client) != (expected.0 as LONG, expected.1 as LONG) {
+                let style = builder.style.dword_style();
+                let style_ex = builder.style.dword_style_ex();
+                let size = Size::Physical(expected.0, expected.1);
+                let (width, height) = WIN32.adjust_window_for_dpi(size, style, style_ex, dpi);
+                let _ = SetWindowPos(
+                    hwnd,
+                    ptr::null_mut(),
+                    0,
+                    0,
+                    width,
+                    height,
+                    SWP_NOACTIVATE | SWP_NOMOVE | SWP_NOZORDER,
+                );
+            }
+
+            // Windows get an IME by default, which we only want when asked for
+            util::set_ime_allowed(hwnd, false);
+
+            // For `RawMouseMotion`, as `WM_MOUSEMOVE` is accelerated & stops at the edge of the screen
+            util::register_raw_mouse();
+
             // Copy style, cursor lock mode, etc
             user_data.window_style = builder.style.clone();
             #[cfg(feature = "cursor-lock")]
             {
                 user_data.cursor_lock = builder.cursor_lock;
             }
+            if builder.fullscreen.is_some() {
+                set_fullscreen(hwnd, user_data, builder.fullscreen.clone());
+            }
 
             0 // OK
         },
@@ -597,7 +1177,12 @@ unsafe extern "system" fn window_proc(hwnd: HWND, msg: UINT, wparam: WPARAM, lpa
         // Received as the window is being destroyed after it has been removed from the screen.
         // Nothing can be done once this stage is hit naturally, which is why the CBT hook exists.
         WM_DESTROY => {
-            if user_data(hwnd).destroy_flag.load(atomic::Ordering::Acquire) {
+            let user_data = user_data(hwnd);
+            if user_data.destroy_flag.load(atomic::Ordering::Acquire) {
+                // The video mode outlives the window otherwise
+                if let Some(device) = user_data.fullscreen.take().and_then(|state| state.changed_device) {
+                    util::reset_video_mode(&device);
+                }
                 PostQuitMessage(0);
             }
             0
@@ -605,16 +1190,91 @@ unsafe extern "system" fn window_proc(hwnd: HWND, msg: UINT, wparam: WPARAM, lpa
 
         // Received after the window has been moved, sent from DefWndProc's `WM_WINDOWPOSCHANGED`.
         // Since the window is on its own thread, this won't block and is just instead sent 1000 times.
+        // lParam: The position of the client area, which isn't what we're after.
         WM_MOVE => {
-            // TODO: Do it
+            // Minimized windows are moved off to (-32000, -32000), which isn't worth telling anyone about
+            if IsIconic(hwnd) == 0 {
+                let user_data = user_data(hwnd);
+                let mut rect: RECT = mem::zeroed();
+                let _ = GetWindowRect(hwnd, &mut rect);
+                let position = user_data.dpi_data.point(rect.left, rect.top);
+                *mutex_lock(&user_data.outer_position) = position;
+                push_event(user_data, Event::Move(position));
+            }
             0
         },
 
         // << Event 0x0004 non-existent >>
 
+        // Received when the window is about to be sized, to find out how big or small it's allowed to be.
+        // wParam: Unused.
+        // lParam: `*mut MINMAXINFO`, filled in with the defaults, which can be changed.
+        WM_GETMINMAXINFO => {
+            // This comes in before `WM_NCCREATE`, when there's no user data yet
+            let user_data = match (get_window_data(hwnd, GWL_USERDATA) as *const WindowUserData).as_ref() {
+                Some(user_data) if user_data.fullscreen.is_none() => user_data,
+                _ => return DefWindowProcW(hwnd, msg, wparam, lparam),
+            };
+            let info = &mut *(lparam as *mut MINMAXINFO);
+            let style = user_data.window_style.dword_style();
+            let style_ex = user_data.window_style.dword_style_ex();
+            let dpi = user_data.dpi_data.dpi;
+            if let Some(min) = user_data.size_limits.min {
+                let (width, height) = WIN32.adjust_window_for_dpi(min, style, style_ex, dpi);
+                info.ptMinTrackSize = POINT { x: width, y: height };
+            }
+            if let Some(max) = user_data.size_limits.max {
+                let (width, height) = WIN32.adjust_window_for_dpi(max, style, style_ex, dpi);
+                info.ptMaxTrackSize = POINT { x: width, y: height };
+            }
+            0
+        },
+
+        // Received while the user is dragging a resize border, which is where the aspect ratio is kept.
+        // wParam: The edge or corner being dragged, such as `WMSZ_BOTTOMRIGHT`.
+        // lParam: `*mut RECT`, the window rect about to be used, which can be changed.
+        WM_SIZING => {
+            let user_data = user_data(hwnd);
+            let (x, y) = match user_data.size_limits.aspect_ratio {
+                Some((x, y)) => (f64::from(x), f64::from(y)),
+                None => return DefWindowProcW(hwnd, msg, wparam, lparam),
+            };
+            let style = user_data.window_style.dword_style();
+            let style_ex = user_data.window_style.dword_style_ex();
+            let frame = WIN32.adjust_window_for_dpi(Size::Physical(0, 0), style, style_ex, user_data.dpi_data.dpi);
+            let rect = &mut *(lparam as *mut RECT);
+            let (width, height) = util::rect_to_size2d(rect);
+            let (width, height) = (f64::from((width - frame.0).max(0)), f64::from((height - frame.1).max(0)));
+
+            // Dragging the top or bottom edge changes the width, everything else the height
+            match wparam {
+                WMSZ_TOP | WMSZ_BOTTOM => rect.right = rect.left + (height * x / y).round() as LONG + frame.0,
+                WMSZ_TOPLEFT | WMSZ_TOPRIGHT => rect.top = rect.bottom - (width * y / x).round() as LONG - frame.1,
+                _ => rect.bottom = rect.top + (width * y / x).round() as LONG + frame.1,
+            }
+            TRUE as LRESULT
+        },
+
         // Received *after* the window has been resized, sent from DefWndProc's `WM_WINDOWPOSCHANGED`.
+        // wParam: The kind of resize, such as `SIZE_MINIMIZED`.
+        // lParam: The new client area width (low word) and height (high word).
         WM_SIZE => {
-            // TODO: Do it
+            let user_data = user_data(hwnd);
+            match wparam {
+                SIZE_RESTORED => update_window_state(user_data, Some(false), false),
+                SIZE_MINIMIZED => update_window_state(user_data, None, true),
+                SIZE_MAXIMIZED => update_window_state(user_data, Some(true), false),
+                _ => (), // other windows being maximized, which we don't care about
+            }
+
+            // Minimized windows have a client area of 0x0, which isn't worth telling anyone about
+            if wparam != SIZE_MINIMIZED {
+                let client = ((lparam & 0xFFFF) as u32, ((lparam >> 16) & 0xFFFF) as u32);
+                if client != user_data.dpi_data.client {
+                    user_data.dpi_data.client = client;
+                    push_event(user_data, Event::Resize(user_data.dpi_data.inner_size()));
+                }
+            }
             0
         },
 
@@ -633,6 +1293,7 @@ unsafe extern "system" fn window_proc(hwnd: HWND, msg: UINT, wparam: WPARAM, lpa
             // Note that the second event means focused & minimized at the same time. Fantastic.
             let focus = wparam & 0xFFFF != 0;
             let is_minimize = (wparam >> 16) & 0xFFFF != 0;
+            update_window_state(user_data, None, is_minimize);
             match (focus, is_minimize) {
                 (true, true) => return 0, // nonsense
                 (state, _) => push_event(user_data, Event::Focus(state)),
@@ -716,7 +1377,96 @@ unsafe extern "system" fn window_proc(hwnd: HWND, msg: UINT, wparam: WPARAM, lpa
             DefWindowProcW(hwnd, msg, wparam, lparam)
         },
 
-        // TODO: what do you think
+        // Received when a key is pressed or released, the SYS variants being for when Alt or F10 is involved.
+        // wParam: The virtual-key code.
+        // lParam: Repeat count (bits 0-15), scancode (16-23), extended key flag (24), previous key state (30).
+        WM_KEYDOWN | WM_SYSKEYDOWN | WM_KEYUP | WM_SYSKEYUP => {
+            let mut scancode = ((lparam >> 16) & 0xFF) as u32;
+            if lparam & (1 << 24) != 0 {
+                scancode |= 0xE000;
+            }
+            let key = Key::from_scancode(scancode);
+            let virtual_key = wparam as u32;
+            let event = match msg {
+                WM_KEYDOWN | WM_SYSKEYDOWN if lparam & (1 << 30) != 0 => Event::KeyboardRepeat { key, virtual_key },
+                WM_KEYDOWN | WM_SYSKEYDOWN => Event::KeyboardDown { key, virtual_key },
+                _ => Event::KeyboardUp { key, virtual_key },
+            };
+            push_event(user_data(hwnd), event);
+
+            // DefWindowProc turns these into system menu commands, such as Alt+F4 to close
+            if matches!(msg, WM_SYSKEYDOWN | WM_SYSKEYUP) {
+                DefWindowProcW(hwnd, msg, wparam, lparam)
+            } else {
+                0
+            }
+        },
+
+        // Received when a key press is translated into a character by `TranslateMessage`.
+        // wParam: A UTF-16 code unit - characters outside the BMP are sent as two messages, one per surrogate.
+        // lParam: Same as `WM_KEYDOWN`.
+        WM_CHAR => {
+            let user_data = user_data(hwnd);
+            if let Some(chr) = decode_wm_char(&mut user_data.high_surrogate, wparam as u16) {
+                push_event(user_data, Event::Input(chr));
+            }
+            0
+        },
+
+        // Received when the IME starts composing, before any `WM_IME_COMPOSITION`.
+        // Not passing this to DefWindowProc stops the IME from drawing its own composition window.
+        WM_IME_STARTCOMPOSITION => {
+            let user_data = user_data(hwnd);
+            let position = user_data.ime_position.physical(user_data.dpi_data.scale_factor);
+            util::set_ime_position(hwnd, (position.0 as LONG, position.1 as LONG));
+            0
+        },
+
+        // Received when the composition changes or is committed.
+        // wParam: The last character changed.
+        // lParam: Which `GCS_*` parts of the composition have changed.
+        WM_IME_COMPOSITION => {
+            let user_data = user_data(hwnd);
+            let himc = ImmGetContext(hwnd);
+            if !himc.is_null() {
+                let flags = lparam as DWORD;
+                if flags & GCS_RESULTSTR != 0 {
+                    let result = util::ime_composition_string(himc, GCS_RESULTSTR);
+                    push_event(user_data, Event::ImeCommit(String::from_utf16_lossy(&result)));
+                }
+                if flags & GCS_COMPSTR != 0 {
+                    let text = util::ime_composition_string(himc, GCS_COMPSTR);
+
+                    // The cursor position is in UTF-16 code units
+                    let cursor = if flags & GCS_CURSORPOS != 0 {
+                        let pos = ImmGetCompositionStringW(himc, GCS_CURSORPOS, ptr::null_mut(), 0);
+                        let pos = (pos.max(0) as usize).min(text.len());
+                        let byte_pos = String::from_utf16_lossy(&text[..pos]).len();
+                        Some((byte_pos, byte_pos))
+                    } else {
+                        None
+                    };
+                    push_event(user_data, Event::ImePreedit {
+                        text: String::from_utf16_lossy(&text),
+                        cursor_range: cursor,
+                    });
+                }
+                let _ = ImmReleaseContext(hwnd, himc);
+            }
+
+            // Passing this on would also send the result as `WM_IME_CHAR`s, and those as `WM_CHAR`s
+            0
+        },
+
+        // Received when composition ends, whether it was committed or cancelled.
+        WM_IME_ENDCOMPOSITION => {
+            push_event(user_data(hwnd), Event::ImePreedit { text: String::new(), cursor_range: None });
+            0
+        },
+
+        // Received when the cursor moves within the client area.
+        // wParam: Which buttons and modifiers are down.
+        // lParam: Signed client area coordinates (x in the low word, y in the high word).
         WM_MOUSEMOVE => {
             let user_data = user_data(hwnd);
 
@@ -734,11 +1484,103 @@ unsafe extern "system" fn window_proc(hwnd: HWND, msg: UINT, wparam: WPARAM, lpa
                 user_data.cursor_constrain_escaped = false;
             }
 
-            let _ = user_data; // soon used
+            // There's no message for the cursor entering, so the first move is it
+            if !user_data.mouse_inside {
+                user_data.mouse_inside = true;
+                util::track_mouse_leave(hwnd);
+                push_event(user_data, Event::MouseEnter);
+            }
+
+            let (x, y) = ((lparam & 0xFFFF) as i16, ((lparam >> 16) & 0xFFFF) as i16);
+            let point = user_data.dpi_data.point(x.into(), y.into());
+            push_event(user_data, Event::MouseMove(point));
 
             0
         },
 
+        // Received with unprocessed device input, as registered with `RegisterRawInputDevices`.
+        // wParam: `RIM_INPUT` if the window is in the foreground, `RIM_INPUTSINK` otherwise.
+        // lParam: The `HRAWINPUT` to read the data from.
+        WM_INPUT => {
+            if let Some((dx, dy)) = util::raw_mouse_motion(lparam as HRAWINPUT) {
+                push_event(user_data(hwnd), Event::RawMouseMotion { dx: dx.into(), dy: dy.into() });
+            }
+
+            // DefWindowProc does the cleanup
+            DefWindowProcW(hwnd, msg, wparam, lparam)
+        },
+
+        // Received when the cursor moves over the window and isn't captured, to find out what it should look like.
+        // wParam: The window the cursor is over, which might be a child.
+        // lParam: The hit-test code in the low word, the message that triggered this in the high word.
+        // Return TRUE to stop anything else from setting the cursor.
+        WM_SETCURSOR => {
+            if lparam & 0xFFFF == HTCLIENT {
+                util::set_cursor_icon(user_data(hwnd).cursor_icon);
+                TRUE as LRESULT
+            } else {
+                // Resize borders and the like
+                DefWindowProcW(hwnd, msg, wparam, lparam)
+            }
+        },
+
+        // Received when the cursor leaves the client area, if requested with `TrackMouseEvent`.
+        // wParam & lParam are unused.
+        WM_MOUSELEAVE => {
+            let user_data = user_data(hwnd);
+            user_data.mouse_inside = false;
+            push_event(user_data, Event::MouseLeave);
+            0
+        },
+
+        // Received when a mouse button is pressed or released within the client area.
+        // wParam & lParam: Same as `WM_MOUSEMOVE`.
+        WM_LBUTTONDOWN | WM_LBUTTONUP | WM_RBUTTONDOWN | WM_RBUTTONUP | WM_MBUTTONDOWN | WM_MBUTTONUP => {
+            let event = match msg {
+                WM_LBUTTONDOWN => Event::MouseDown(MouseButton::Left),
+                WM_LBUTTONUP => Event::MouseUp(MouseButton::Left),
+                WM_RBUTTONDOWN => Event::MouseDown(MouseButton::Right),
+                WM_RBUTTONUP => Event::MouseUp(MouseButton::Right),
+                WM_MBUTTONDOWN => Event::MouseDown(MouseButton::Middle),
+                _ => Event::MouseUp(MouseButton::Middle),
+            };
+            push_event(user_data(hwnd), event);
+            0
+        },
+
+        // Same as above, but for the side buttons.
+        // wParam: Which button it was in the high word (`XBUTTON1` or `XBUTTON2`).
+        WM_XBUTTONDOWN | WM_XBUTTONUP => {
+            let button = match ((wparam >> 16) & 0xFFFF) as WORD {
+                XBUTTON1 => MouseButton::X1,
+                XBUTTON2 => MouseButton::X2,
+                _ => return DefWindowProcW(hwnd, msg, wparam, lparam),
+            };
+            let event = if msg == WM_XBUTTONDOWN {
+                Event::MouseDown(button)
+            } else {
+                Event::MouseUp(button)
+            };
+            push_event(user_data(hwnd), event);
+            TRUE as LRESULT // unlike the other buttons, MSDN says to return TRUE here
+        },
+
+        // Received when the vertical or horizontal wheel is scrolled while the window is focused.
+        // wParam: The distance in the high word, in multiples of `WHEEL_DELTA` for regular wheels.
+        // lParam: Signed screen coordinates of the cursor.
+        WM_MOUSEWHEEL | WM_MOUSEHWHEEL => {
+            let distance = ((wparam >> 16) & 0xFFFF) as c_short;
+            let notches = f64::from(distance) / f64::from(WHEEL_DELTA);
+            let delta = if msg == WM_MOUSEWHEEL {
+                (0.0, notches)
+            } else {
+                (notches, 0.0)
+            };
+            let precise = distance % WHEEL_DELTA != 0;
+            push_event(user_data(hwnd), Event::MouseWheel { delta, precise });
+            0
+        },
+
         // MSDN: Sent one time to a window, after it has exited the moving or sizing modal loop.
         // wParam & lParam are unused.
         WM_EXITSIZEMOVE => {
@@ -752,6 +1594,41 @@ unsafe extern "system" fn window_proc(hwnd: HWND, msg: UINT, wparam: WPARAM, lpa
             0
         },
 
+        // Received when the window is moved onto a monitor with a different DPI, or its monitor's DPI changes.
+        // wParam: The new DPI in both the low word (X) and high word (Y), which are always the same.
+        // lParam: `*const RECT`, a suggested new window rect which keeps the logical size the same.
+        WM_DPICHANGED => {
+            let user_data = user_data(hwnd);
+            let dpi = (wparam & 0xFFFF) as UINT;
+            if dpi != user_data.dpi_data.dpi {
+                user_data.dpi_data.dpi = dpi;
+                user_data.dpi_data.scale_factor = dpi as f64 / util::BASE_DPI as f64;
+                push_event(user_data, Event::ScaleFactor(user_data.dpi_data.scale_factor));
+            }
+
+            // This sends `WM_SIZE` & `WM_MOVE`, which convert with the new scale
+            let rect = &*(lparam as *const RECT);
+            let (width, height) = util::rect_to_size2d(rect);
+            let _ = SetWindowPos(
+                hwnd,
+                ptr::null_mut(),
+                rect.left,
+                rect.top,
+                width,
+                height,
+                SWP_NOACTIVATE | SWP_NOZORDER,
+            );
+            0
+        },
+
+        // Sent to every top-level window when the display resolution changes, which includes monitors being added or removed.
+        // wParam: The new bit depth of the desktop.
+        // lParam: The new resolution of the primary monitor, width in the low word and height in the high word.
+        WM_DISPLAYCHANGE => {
+            push_event(user_data(hwnd), Event::MonitorsChanged);
+            0
+        },
+
         // Custom event: Run arbitrary functions.
         // wParam: Function pointer of type `*mut &mut dyn FnMut()`.
         // lParam: Unused, set to zero.
@@ -806,9 +1683,11 @@ unsafe extern "system" fn window_proc(hwnd: HWND, msg: UINT, wparam: WPARAM, lpa
                     util::set_close_button(hwnd, close);
                 }
 
-                // Set styles, refresh
-                user_data.window_style.set_for(hwnd);
-                util::ping_window_frame(hwnd);
+                // Set styles, refresh (fullscreen windows get them back when leaving fullscreen)
+                if user_data.fullscreen.is_none() {
+                    user_data.window_style.set_for(hwnd);
+                    util::ping_window_frame(hwnd);
+                }
             }
             0
         },
@@ -821,7 +1700,9 @@ unsafe extern "system" fn window_proc(hwnd: HWND, msg: UINT, wparam: WPARAM, lpa
             let resizable = wparam != 0;
             if user_data.window_style.resizable != resizable {
                 user_data.window_style.resizable = resizable;
-                user_data.window_style.set_for(hwnd);
+                if user_data.fullscreen.is_none() {
+                    user_data.window_style.set_for(hwnd);
+                }
             }
             0
         },
@@ -850,6 +1731,225 @@ unsafe extern "system" fn window_proc(hwnd: HWND, msg: UINT, wparam: WPARAM, lpa
             0
         },
 
+        // Custom event: Set whether the IME can be used.
+        // wParam: Boolean, 0 or 1.
+        // lParam: Unused, set to zero.
+        RAMEN_WM_SETIMEALLOWED => {
+            let user_data = user_data(hwnd);
+            let allowed = wparam != 0;
+            if user_data.ime_allowed != allowed {
+                user_data.ime_allowed = allowed;
+                util::set_ime_allowed(hwnd, allowed);
+            }
+            0
+        },
+
+        // Custom event: Set the IME candidate window position.
+        // wParam: Unused, set to zero.
+        // lParam: `Box<Point>` pointer, taken ownership of.
+        RAMEN_WM_SETIMEPOSITION => {
+            let user_data = user_data(hwnd);
+            user_data.ime_position = *Box::from_raw(lparam as *mut Point);
+            let position = user_data.ime_position.physical(user_data.dpi_data.scale_factor);
+            util::set_ime_position(hwnd, (position.0 as LONG, position.1 as LONG));
+            0
+        },
+
+        // Custom event: Set the fullscreen mode.
+        // wParam: Unused, set to zero.
+        // lParam: `Box<Option<Fullscreen>>` pointer, taken ownership of.
+        RAMEN_WM_SETFULLSCREEN => {
+            let fullscreen = *Box::from_raw(lparam as *mut Option<Fullscreen>);
+            set_fullscreen(hwnd, user_data(hwnd), fullscreen);
+            0
+        },
+
+        // Custom event: Set the inner size.
+        // wParam: Unused, set to zero.
+        // lParam: `Box<Size>` pointer, taken ownership of.
+        RAMEN_WM_SETINNERSIZE => {
+            let user_data = user_data(hwnd);
+            let inner_size = *Box::from_raw(lparam as *mut Size);
+            let scale = user_data.dpi_data.scale_factor;
+            let client = user_data.size_limits.clamp(inner_size.physical(scale), scale);
+            let style = user_data.window_style.dword_style();
+            let style_ex = user_data.window_style.dword_style_ex();
+            let size = Size::Physical(client.0, client.1);
+            let (width, height) = WIN32.adjust_window_for_dpi(size, style, style_ex, user_data.dpi_data.dpi);
+            if let Some(state) = user_data.fullscreen.as_mut() {
+                // Applied when leaving fullscreen
+                state.windowed_rect.right = state.windowed_rect.left + width;
+                state.windowed_rect.bottom = state.windowed_rect.top + height;
+            } else {
+                let _ = SetWindowPos(
+                    hwnd,
+                    ptr::null_mut(),
+                    0,
+                    0,
+                    width,
+                    height,
+                    SWP_NOACTIVATE | SWP_NOMOVE | SWP_NOOWNERZORDER | SWP_NOZORDER,
+                );
+            }
+            0
+        },
+
+        // Custom event: Set the outer position.
+        // wParam: Unused, set to zero.
+        // lParam: `Box<Point>` pointer, taken ownership of.
+        RAMEN_WM_SETOUTERPOSITION => {
+            let user_data = user_data(hwnd);
+            let position = *Box::from_raw(lparam as *mut Point);
+            let (x, y) = position.physical(user_data.dpi_data.scale_factor);
+            let (x, y) = (x as LONG, y as LONG);
+            if let Some(state) = user_data.fullscreen.as_mut() {
+                // Applied when leaving fullscreen
+                let (width, height) = util::rect_to_size2d(&state.windowed_rect);
+                state.windowed_rect = RECT { left: x, top: y, right: x + width, bottom: y + height };
+            } else {
+                let _ = SetWindowPos(
+                    hwnd,
+                    ptr::null_mut(),
+                    x,
+                    y,
+                    0,
+                    0,
+                    SWP_NOACTIVATE | SWP_NOOWNERZORDER | SWP_NOSIZE | SWP_NOZORDER,
+                );
+            }
+            0
+        },
+
+        // Custom event: Set the smallest inner size.
+        // wParam: Unused, set to zero.
+        // lParam: `Box<Option<Size>>` pointer, taken ownership of.
+        RAMEN_WM_SETMININNERSIZE => {
+            let user_data = user_data(hwnd);
+            user_data.size_limits.min = *Box::from_raw(lparam as *mut Option<Size>);
+            update_size_limits(hwnd, user_data);
+            0
+        },
+
+        // Custom event: Set the largest inner size.
+        // wParam: Unused, set to zero.
+        // lParam: `Box<Option<Size>>` pointer, taken ownership of.
+        RAMEN_WM_SETMAXINNERSIZE => {
+            let user_data = user_data(hwnd);
+            user_data.size_limits.max = *Box::from_raw(lparam as *mut Option<Size>);
+            update_size_limits(hwnd, user_data);
+            0
+        },
+
+        // Custom event: Set the aspect ratio.
+        // wParam: The width part of the ratio, or zero for `None`.
+        // lParam: The height part of the ratio, or zero for `None`.
+        RAMEN_WM_SETASPECTRATIO => {
+            let user_data = user_data(hwnd);
+            user_data.size_limits.aspect_ratio = if wparam != 0 { Some((wparam as u32, lparam as u32)) } else { None };
+            update_size_limits(hwnd, user_data);
+            0
+        },
+
+        // Custom event: Get the WindowStyle struct.
+        // wParam: Unused, set to zero.
+        // lParam: *mut WindowStyle (out).
+        RAMEN_WM_GETWINDOWSTYLE => {
+            let user_data = user_data(hwnd);
+            *(lparam as *mut WindowStyle) = user_data.window_style.clone();
+            0
+        },
+
+        // Custom event: Get whether the window has focus.
+        // wParam: Unused, set to zero.
+        // lParam: Unused, set to zero.
+        // Return value: Non-zero if focused, else zero.
+        RAMEN_WM_GETFOCUSSTATE => user_data(hwnd).focus_state as LRESULT,
+
+        // Custom event: Set the cursor icon.
+        // wParam: Unused, set to zero.
+        // lParam: `Box<CursorIcon>` pointer, taken ownership of.
+        RAMEN_WM_SETCURSORICON => {
+            let user_data = user_data(hwnd);
+            user_data.cursor_icon = *Box::from_raw(lparam as *mut CursorIcon);
+
+            // Otherwise it'd only change once the cursor moves
+            if user_data.mouse_inside {
+                util::set_cursor_icon(user_data.cursor_icon);
+            }
+            0
+        },
+
+        // Custom event: Maximize or restore the window.
+        // wParam: If non-zero, maximize, otherwise restore.
+        // lParam: Unused, set to zero.
+        RAMEN_WM_SETMAXIMIZED => {
+            let user_data = user_data(hwnd);
+            let maximized = wparam != 0;
+            if let Some(state) = user_data.fullscreen.as_mut() {
+                // Applied when leaving fullscreen
+                state.maximized = maximized;
+            } else {
+                set_maximized(hwnd, maximized);
+            }
+            0
+        },
+
+        // Custom event: Minimize or restore the window.
+        // wParam: If non-zero, minimize, otherwise restore.
+        // lParam: Unused, set to zero.
+        RAMEN_WM_SETMINIMIZED => {
+            // Fullscreen windows can be minimized as they are, restoring them goes back to fullscreen
+            if (wparam != 0) != (IsIconic(hwnd) != FALSE) {
+                let _ = ShowWindow(hwnd, if wparam != 0 { SW_MINIMIZE } else { SW_RESTORE });
+            }
+            0
+        },
+
+        // Custom event: Set whether the window is borderless, keeping the client size.
+        // wParam: If non-zero, borderless, otherwise bordered.
+        // lParam: Unused, set to zero.
+        RAMEN_WM_SETBORDERLESS => {
+            let user_data = user_data(hwnd);
+            let borderless = wparam != 0;
+            if user_data.window_style.borderless != borderless {
+                let dpi = user_data.dpi_data.dpi;
+                let old_frame = WIN32.adjust_window_for_dpi(
+                    Size::Physical(0, 0),
+                    user_data.window_style.dword_style(),
+                    user_data.window_style.dword_style_ex(),
+                    dpi,
+                );
+                user_data.window_style.borderless = borderless;
+                let style = user_data.window_style.dword_style();
+                let style_ex = user_data.window_style.dword_style_ex();
+                if let Some(state) = user_data.fullscreen.as_mut() {
+                    // Applied when leaving fullscreen, swapping out the old frame for the new one
+                    let new_frame = WIN32.adjust_window_for_dpi(Size::Physical(0, 0), style, style_ex, dpi);
+                    state.windowed_rect.right += new_frame.0 - old_frame.0;
+                    state.windowed_rect.bottom += new_frame.1 - old_frame.1;
+                } else if IsZoomed(hwnd) != FALSE {
+                    // Maximized windows fill the work area whatever their frame is
+                    user_data.window_style.set_for(hwnd);
+                    util::ping_window_frame(hwnd);
+                } else {
+                    let (client_width, client_height) = user_data.dpi_data.client;
+                    let size = Size::Physical(client_width, client_height);
+                    let (width, height) = WIN32.adjust_window_for_dpi(size, style, style_ex, dpi);
+                    user_data.window_style.set_for(hwnd);
+                    let _ = SetWindowPos(
+                        hwnd,
+                        ptr::null_mut(),
+                        0,
+                        0,
+                        width,
+                        height,
+                        SWP_FRAMECHANGED | SWP_NOACTIVATE | SWP_NOMOVE | SWP_NOOWNERZORDER | SWP_NOZORDER,
+                    );
+                }
+            }
+            0
+        },
+
         _ => DefWindowProcW(hwnd, msg, wparam, lparam),
     }
 }
@@ -862,3 +1962,37 @@ impl ops::Drop for Window {
         let _ = self.thread.take().map(thread::JoinHandle::join);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::decode_wm_char;
+
+    fn decode(units: &[u16]) -> Vec<char> {
+        let mut high_surrogate = None;
+        units.iter().filter_map(|&unit| decode_wm_char(&mut high_surrogate, unit)).collect()
+    }
+
+    #[test]
+    fn surrogate_pair() {
+        assert_eq!(decode(&[0xD83C, 0xDF5C]), ['\u{1F35C}']);
+        assert_eq!(decode(&[0x61, 0xD83C, 0xDF5C, 0x62]), ['a', '\u{1F35C}', 'b']);
+    }
+
+    #[test]
+    fn lone_high_surrogate() {
+        assert_eq!(decode(&[0xD83C, 0x61]), ['a']);
+        assert_eq!(decode(&[0xD83C, 0xD83C, 0xDF5C]), ['\u{1F35C}']);
+    }
+
+    #[test]
+    fn lone_low_surrogate() {
+        assert_eq!(decode(&[0xDF5C]), []);
+        assert_eq!(decode(&[0x61, 0xDF5C, 0x62]), ['a', 'b']);
+    }
+
+    #[test]
+    fn control_characters() {
+        // These are sent for backspace, enter and Ctrl+C, and are passed along as they are
+        assert_eq!(decode(&[0x08, 0x0D, 0x03]), ['\u{8}', '\r', '\u{3}']);
+    }
+}
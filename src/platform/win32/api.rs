@@ -25,7 +25,9 @@ def_handle!(HBRUSH, HBRUSH__);
 def_handle!(HDC, HDC__);
 def_handle!(HHOOK, HHOOK__);
 def_handle!(HICON, HICON__);
+def_handle!(HIMC, HIMC__);
 def_handle!(HMENU, HMENU__);
+def_handle!(HRAWINPUT, HRAWINPUT__);
 def_handle!(HMODULE, HMODULE__);
 pub type HCURSOR = HICON;
 
@@ -68,6 +70,7 @@ pub type SHORT = c_short;
 pub type UINT = c_uint;
 pub type UINT_PTR = usize;
 pub type ULONG_PTR = usize;
+pub type ULONG = c_ulong;
 pub type USHORT = c_ushort;
 pub type WCHAR = wchar_t;
 pub type WORD = c_ushort;
@@ -75,32 +78,67 @@ pub type WPARAM = UINT_PTR;
 
 // Function typedefs
 pub type HOOKPROC = unsafe extern "system" fn(c_int, WPARAM, LPARAM) -> LRESULT;
+pub type MONITORENUMPROC = unsafe extern "system" fn(HMONITOR, HDC, *mut RECT, LPARAM) -> BOOL;
 pub type WNDPROC = unsafe extern "system" fn(HWND, UINT, WPARAM, LPARAM) -> LRESULT;
 
 // Constants
 pub const _WIN32_WINNT_VISTA: WORD = 0x0600;
 pub const _WIN32_WINNT_WINBLUE: WORD = 0x0603;
+pub const CCHDEVICENAME: usize = 32;
+pub const CCHFORMNAME: usize = 32;
 pub const CCHILDREN_TITLEBAR: usize = 5;
+pub const CDS_FULLSCREEN: DWORD = 0x00000004;
+pub const CFS_CANDIDATEPOS: DWORD = 0x0040;
+pub const CFS_POINT: DWORD = 0x0002;
 pub const CP_UTF8: DWORD = 65001;
 pub const CS_OWNDC: UINT = 0x0020;
 pub const CW_USEDEFAULT: c_int = 0x80000000;
+pub const DISP_CHANGE_SUCCESSFUL: LONG = 0;
+pub const DM_BITSPERPEL: DWORD = 0x00040000;
+pub const DM_DISPLAYFREQUENCY: DWORD = 0x00400000;
+pub const DM_PELSHEIGHT: DWORD = 0x00100000;
+pub const DM_PELSWIDTH: DWORD = 0x00080000;
 pub const DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2: DPI_AWARENESS_CONTEXT = -4isize as _;
 pub const E_INVALIDARG: HRESULT = 0x80070057;
+pub const ENUM_CURRENT_SETTINGS: DWORD = 0xFFFFFFFF;
 pub const ERROR_SUCCESS: DWORD = 0; // lol
 pub const FALSE: BOOL = 0;
 pub const FORMAT_MESSAGE_ALLOCATE_BUFFER: DWORD = 0x00000100;
 pub const FORMAT_MESSAGE_FROM_SYSTEM: DWORD = 0x00001000;
 pub const FORMAT_MESSAGE_IGNORE_INSERTS: DWORD = 0x00000200;
 pub const GCL_CBCLSEXTRA: c_int = -20;
+pub const GCS_COMPSTR: DWORD = 0x0008;
+pub const GCS_CURSORPOS: DWORD = 0x0080;
+pub const GCS_RESULTSTR: DWORD = 0x0800;
 pub const GWL_EXSTYLE: c_int = -20;
 pub const GWL_STYLE: c_int = -16;
 pub const GWL_USERDATA: c_int = -21;
 pub const HCBT_DESTROYWND: c_int = 4;
+pub const HTCLIENT: LRESULT = 1;
+pub const IACE_DEFAULT: DWORD = 0x0010;
+pub const IDC_APPSTARTING: LPCWSTR = 32650 as LPCWSTR;
+pub const IDC_ARROW: LPCWSTR = 32512 as LPCWSTR;
+pub const IDC_CROSS: LPCWSTR = 32515 as LPCWSTR;
+pub const IDC_HAND: LPCWSTR = 32649 as LPCWSTR;
+pub const IDC_HELP: LPCWSTR = 32651 as LPCWSTR;
+pub const IDC_IBEAM: LPCWSTR = 32513 as LPCWSTR;
+pub const IDC_NO: LPCWSTR = 32648 as LPCWSTR;
+pub const IDC_SIZEALL: LPCWSTR = 32646 as LPCWSTR;
+pub const IDC_SIZENESW: LPCWSTR = 32643 as LPCWSTR;
+pub const IDC_SIZENS: LPCWSTR = 32645 as LPCWSTR;
+pub const IDC_SIZENWSE: LPCWSTR = 32642 as LPCWSTR;
+pub const IDC_SIZEWE: LPCWSTR = 32644 as LPCWSTR;
+pub const IDC_WAIT: LPCWSTR = 32514 as LPCWSTR;
 pub const LANG_NEUTRAL: USHORT = 0x00;
+pub const LOGPIXELSX: c_int = 88;
+pub const MDT_EFFECTIVE_DPI: u32 = 0;
 pub const MF_BYCOMMAND: UINT = 0x00000000;
 pub const MF_DISABLED: UINT = 0x00000002;
 pub const MF_ENABLED: UINT = 0x00000000;
 pub const MF_GRAYED: UINT = 0x00000001;
+pub const MONITORINFOF_PRIMARY: DWORD = 0x00000001;
+pub const MONITOR_DEFAULTTONEAREST: DWORD = 0x00000002;
+pub const MONITOR_DEFAULTTOPRIMARY: DWORD = 0x00000001;
 pub const PROCESS_PER_MONITOR_DPI_AWARE: PROCESS_DPI_AWARENESS = 2;
 pub const PROCESS_SYSTEM_DPI_AWARE: PROCESS_DPI_AWARENESS = 1;
 pub const SUBLANG_DEFAULT: USHORT = 0x01;
@@ -108,8 +146,14 @@ pub const S_OK: HRESULT = 0;
 pub const SC_CLOSE: WPARAM = 0xF060;
 pub const SM_SWAPBUTTON: c_int = 23;
 pub const SW_HIDE: c_int = 0;
+pub const SW_MAXIMIZE: c_int = 3;
+pub const SW_MINIMIZE: c_int = 6;
+pub const SW_RESTORE: c_int = 9;
 pub const SW_SHOW: c_int = 5;
 pub const SWP_ASYNCWINDOWPOS: UINT = 0x4000;
+pub const SIZE_RESTORED: WPARAM = 0;
+pub const SIZE_MINIMIZED: WPARAM = 1;
+pub const SIZE_MAXIMIZED: WPARAM = 2;
 pub const SWP_DEFERERASE: UINT = 0x2000;
 pub const SWP_DRAWFRAME: UINT = SWP_FRAMECHANGED;
 pub const SWP_FRAMECHANGED: UINT = 0x0020;
@@ -124,6 +168,12 @@ pub const SWP_NOSENDCHANGING: UINT = 0x0400;
 pub const SWP_NOSIZE: UINT = 0x0001;
 pub const SWP_NOZORDER: UINT = 0x0004;
 pub const SWP_SHOWWINDOW: UINT = 0x0040;
+pub const HID_USAGE_GENERIC_MOUSE: USHORT = 0x02;
+pub const HID_USAGE_PAGE_GENERIC: USHORT = 0x01;
+pub const MOUSE_MOVE_ABSOLUTE: USHORT = 0x01;
+pub const RID_INPUT: UINT = 0x10000003;
+pub const RIM_TYPEMOUSE: DWORD = 0;
+pub const TME_LEAVE: DWORD = 0x00000002;
 pub const TRUE: BOOL = 1;
 pub const VER_BUILDNUMBER: DWORD = 0x0000004;
 pub const VER_GREATER_EQUAL: BYTE = 3;
@@ -135,6 +185,7 @@ pub const WH_CBT: c_int = 5;
 pub const WM_NULL: UINT = 0x0000;
 pub const WM_CREATE: UINT = 0x0001;
 pub const WM_EXITSIZEMOVE: UINT = 0x0232;
+pub const WM_SIZING: UINT = 0x0214;
 pub const WM_DESTROY: UINT = 0x0002;
 pub const WM_SIZE: UINT = 0x0005;
 pub const WM_ACTIVATE: UINT = 0x0006;
@@ -142,9 +193,21 @@ pub const WM_SETTEXT: UINT = 0x000C;
 pub const WM_MOVE: UINT = 0x0003;
 pub const WM_CLOSE: UINT = 0x0010;
 pub const WM_SHOWWINDOW: UINT = 0x0018;
+pub const WM_GETMINMAXINFO: UINT = 0x0024;
+pub const WM_SETCURSOR: UINT = 0x0020;
+pub const WM_DISPLAYCHANGE: UINT = 0x007E;
 pub const WM_NCCREATE: UINT = 0x0081;
 pub const WM_NCDESTROY: UINT = 0x0082;
 pub const WM_SYSCOMMAND: UINT = 0x0112;
+pub const WM_IME_STARTCOMPOSITION: UINT = 0x010D;
+pub const WM_IME_ENDCOMPOSITION: UINT = 0x010E;
+pub const WM_IME_COMPOSITION: UINT = 0x010F;
+pub const WM_INPUT: UINT = 0x00FF;
+pub const WM_KEYDOWN: UINT = 0x0100;
+pub const WM_KEYUP: UINT = 0x0101;
+pub const WM_CHAR: UINT = 0x0102;
+pub const WM_SYSKEYDOWN: UINT = 0x0104;
+pub const WM_SYSKEYUP: UINT = 0x0105;
 pub const WM_USER: UINT = 0x0400;
 pub const WS_BORDER: DWORD = 0x00800000;
 pub const WS_CAPTION: DWORD = 0x00C00000;
@@ -163,6 +226,25 @@ pub const WS_MAXIMIZEBOX: DWORD = 0x00010000;
 pub const WS_MINIMIZE: DWORD = 0x20000000;
 pub const WS_MINIMIZEBOX: DWORD = 0x00020000;
 pub const WM_MOUSEMOVE: UINT = 0x0200;
+pub const WM_LBUTTONDOWN: UINT = 0x0201;
+pub const WM_LBUTTONUP: UINT = 0x0202;
+pub const WM_RBUTTONDOWN: UINT = 0x0204;
+pub const WM_RBUTTONUP: UINT = 0x0205;
+pub const WM_MBUTTONDOWN: UINT = 0x0207;
+pub const WM_MBUTTONUP: UINT = 0x0208;
+pub const WM_MOUSEWHEEL: UINT = 0x020A;
+pub const WM_XBUTTONDOWN: UINT = 0x020B;
+pub const WM_XBUTTONUP: UINT = 0x020C;
+pub const WM_MOUSEHWHEEL: UINT = 0x020E;
+pub const WM_MOUSELEAVE: UINT = 0x02A3;
+pub const WM_DPICHANGED: UINT = 0x02E0;
+pub const WHEEL_DELTA: c_short = 120;
+pub const WMSZ_TOP: WPARAM = 3;
+pub const WMSZ_TOPLEFT: WPARAM = 4;
+pub const WMSZ_TOPRIGHT: WPARAM = 5;
+pub const WMSZ_BOTTOM: WPARAM = 6;
+pub const XBUTTON1: WORD = 0x0001;
+pub const XBUTTON2: WORD = 0x0002;
 pub const WS_OVERLAPPED: DWORD = 0x00000000;
 pub const WS_OVERLAPPEDWINDOW: DWORD =
     WS_OVERLAPPED | WS_CAPTION | WS_SYSMENU | WS_THICKFRAME | WS_MINIMIZEBOX | WS_MAXIMIZEBOX;
@@ -178,6 +260,19 @@ pub const WS_VSCROLL: DWORD = 0x00200000;
 
 // Structs
 #[repr(C)]
+pub struct CANDIDATEFORM {
+    pub dwIndex: DWORD,
+    pub dwStyle: DWORD,
+    pub ptCurrentPos: POINT,
+    pub rcArea: RECT,
+}
+#[repr(C)]
+pub struct COMPOSITIONFORM {
+    pub dwStyle: DWORD,
+    pub ptCurrentPos: POINT,
+    pub rcArea: RECT,
+}
+#[repr(C)]
 pub struct POINT {
     pub x: LONG,
     pub y: LONG,
@@ -190,6 +285,63 @@ pub struct RECT {
     pub bottom: LONG,
 }
 #[repr(C)]
+pub struct DEVMODEW {
+    pub dmDeviceName: [WCHAR; CCHDEVICENAME],
+    pub dmSpecVersion: WORD,
+    pub dmDriverVersion: WORD,
+    pub dmSize: WORD,
+    pub dmDriverExtra: WORD,
+    pub dmFields: DWORD,
+    pub dmPosition: POINT, // union with printer fields, this is the display part
+    pub dmDisplayOrientation: DWORD,
+    pub dmDisplayFixedOutput: DWORD,
+    pub dmColor: c_short,
+    pub dmDuplex: c_short,
+    pub dmYResolution: c_short,
+    pub dmTTOption: c_short,
+    pub dmCollate: c_short,
+    pub dmFormName: [WCHAR; CCHFORMNAME],
+    pub dmLogPixels: WORD,
+    pub dmBitsPerPel: DWORD,
+    pub dmPelsWidth: DWORD,
+    pub dmPelsHeight: DWORD,
+    pub dmDisplayFlags: DWORD, // union with `dmNup`
+    pub dmDisplayFrequency: DWORD,
+    pub dmICMMethod: DWORD,
+    pub dmICMIntent: DWORD,
+    pub dmMediaType: DWORD,
+    pub dmDitherType: DWORD,
+    pub dmReserved1: DWORD,
+    pub dmReserved2: DWORD,
+    pub dmPanningWidth: DWORD,
+    pub dmPanningHeight: DWORD,
+}
+#[repr(C)]
+pub struct DISPLAY_DEVICEW {
+    pub cb: DWORD,
+    pub DeviceName: [WCHAR; 32],
+    pub DeviceString: [WCHAR; 128],
+    pub StateFlags: DWORD,
+    pub DeviceID: [WCHAR; 128],
+    pub DeviceKey: [WCHAR; 128],
+}
+#[repr(C)]
+pub struct MINMAXINFO {
+    pub ptReserved: POINT,
+    pub ptMaxSize: POINT,
+    pub ptMaxPosition: POINT,
+    pub ptMinTrackSize: POINT,
+    pub ptMaxTrackSize: POINT,
+}
+#[repr(C)]
+pub struct MONITORINFOEXW {
+    pub cbSize: DWORD,
+    pub rcMonitor: RECT,
+    pub rcWork: RECT,
+    pub dwFlags: DWORD,
+    pub szDevice: [WCHAR; CCHDEVICENAME],
+}
+#[repr(C)]
 pub struct MSG {
     pub hwnd: HWND,
     pub message: UINT,
@@ -213,12 +365,47 @@ pub struct OSVERSIONINFOEXW {
     pub wReserved: BYTE,
 }
 #[repr(C)]
+pub struct RAWINPUTDEVICE {
+    pub usUsagePage: USHORT,
+    pub usUsage: USHORT,
+    pub dwFlags: DWORD,
+    pub hwndTarget: HWND,
+}
+#[repr(C)]
+pub struct RAWINPUTHEADER {
+    pub dwType: DWORD,
+    pub dwSize: DWORD,
+    pub hDevice: HANDLE,
+    pub wParam: WPARAM,
+}
+#[repr(C)]
+pub struct RAWMOUSE {
+    pub usFlags: USHORT,
+    pub ulButtons: ULONG, // union with `usButtonFlags` & `usButtonData`
+    pub ulRawButtons: ULONG,
+    pub lLastX: LONG,
+    pub lLastY: LONG,
+    pub ulExtraInformation: ULONG,
+}
+#[repr(C)]
+pub struct RAWINPUT {
+    pub header: RAWINPUTHEADER,
+    pub mouse: RAWMOUSE, // union with `RAWKEYBOARD` & `RAWHID`, both of which are smaller
+}
+#[repr(C)]
 pub struct TITLEBARINFO {
     pub cbSize: DWORD,
     pub rcTitleBar: RECT,
     pub rgstate: [DWORD; CCHILDREN_TITLEBAR + 1],
 }
 #[repr(C)]
+pub struct TRACKMOUSEEVENT {
+    pub cbSize: DWORD,
+    pub dwFlags: DWORD,
+    pub hwndTrack: HWND,
+    pub dwHoverTime: DWORD,
+}
+#[repr(C)]
 pub struct WNDCLASSEXW {
     pub cbSize: UINT,
     pub style: UINT,
@@ -275,6 +462,19 @@ extern "system" {
         lpUsedDefaultChar: *mut BOOL,
     ) -> c_int;
 }
+#[link(name = "Gdi32")]
+extern "system" {
+    pub fn GetDeviceCaps(hdc: HDC, index: c_int) -> c_int;
+}
+#[link(name = "Imm32")]
+extern "system" {
+    pub fn ImmAssociateContextEx(hWnd: HWND, hIMC: HIMC, dwFlags: DWORD) -> BOOL;
+    pub fn ImmGetContext(hWnd: HWND) -> HIMC;
+    pub fn ImmGetCompositionStringW(hIMC: HIMC, dwIndex: DWORD, lpBuf: LPVOID, dwBufLen: DWORD) -> LONG;
+    pub fn ImmReleaseContext(hWnd: HWND, hIMC: HIMC) -> BOOL;
+    pub fn ImmSetCandidateWindow(hIMC: HIMC, lpCandidate: *mut CANDIDATEFORM) -> BOOL;
+    pub fn ImmSetCompositionWindow(hIMC: HIMC, lpCompForm: *mut COMPOSITIONFORM) -> BOOL;
+}
 #[link(name = "User32")]
 extern "system" {
     // Window class management
@@ -299,7 +499,16 @@ extern "system" {
     pub fn AdjustWindowRectEx(lpRect: *mut RECT, dwStyle: DWORD, bMenu: BOOL, dwExStyle: DWORD) -> BOOL;
     pub fn ClientToScreen(hWnd: HWND, lpPoint: *mut POINT) -> BOOL;
     pub fn GetClientRect(hWnd: HWND, lpRect: *mut RECT) -> BOOL;
+    pub fn GetWindowRect(hWnd: HWND, lpRect: *mut RECT) -> BOOL;
+    pub fn GetWindowTextLengthW(hWnd: HWND) -> c_int;
+    pub fn GetWindowTextW(hWnd: HWND, lpString: LPWSTR, nMaxCount: c_int) -> c_int;
+    pub fn IsIconic(hWnd: HWND) -> BOOL;
+    pub fn IsZoomed(hWnd: HWND) -> BOOL;
     pub fn GetTitleBarInfo(hwnd: HWND, pti: *mut TITLEBARINFO) -> BOOL;
+    pub fn EnumDisplayMonitors(hdc: HDC, lprcClip: *const RECT, lpfnEnum: MONITORENUMPROC, dwData: LPARAM) -> BOOL;
+    pub fn GetMonitorInfoW(hMonitor: HMONITOR, lpmi: *mut MONITORINFOEXW) -> BOOL;
+    pub fn MonitorFromPoint(pt: POINT, dwFlags: DWORD) -> HMONITOR;
+    pub fn MonitorFromWindow(hwnd: HWND, dwFlags: DWORD) -> HMONITOR;
     pub fn SetWindowPos(hWnd: HWND, hWndInsertAfter: HWND, X: c_int, Y: c_int, cx: c_int, cy: c_int, uFlags: UINT) -> BOOL;
     pub fn DestroyWindow(hWnd: HWND) -> BOOL;
 
@@ -314,6 +523,7 @@ extern "system" {
     pub fn PostMessageW(hWnd: HWND, Msg: UINT, wParam: WPARAM, lParam: LPARAM) -> BOOL;
     pub fn SendMessageW(hWnd: HWND, Msg: UINT, wParam: WPARAM, lParam: LPARAM) -> LRESULT;
     pub fn DispatchMessageW(lpmsg: *const MSG) -> LRESULT;
+    pub fn TranslateMessage(lpMsg: *const MSG) -> BOOL;
     pub fn PostQuitMessage(nExitCode: c_int);
 
     // Message loop utility
@@ -323,14 +533,43 @@ extern "system" {
     // Keyboard & mouse related
     pub fn ClipCursor(lpRect: *const RECT) -> BOOL;
     pub fn SetCursorPos(X: c_int, Y: c_int) -> BOOL;
+    pub fn SetCursor(hCursor: HCURSOR) -> HCURSOR;
+    pub fn LoadCursorW(hInstance: HINSTANCE, lpCursorName: LPCWSTR) -> HCURSOR;
     pub fn GetCursorPos(lpPoint: *mut POINT) -> BOOL;
     pub fn SetCapture(hWnd: HWND) -> HWND;
     pub fn GetCapture() -> HWND;
     pub fn ReleaseCapture() -> BOOL;
+    pub fn TrackMouseEvent(lpEventTrack: *mut TRACKMOUSEEVENT) -> BOOL;
+    pub fn RegisterRawInputDevices(pRawInputDevices: *const RAWINPUTDEVICE, uiNumDevices: UINT, cbSize: UINT) -> BOOL;
+    pub fn GetRawInputData(
+        hRawInput: HRAWINPUT,
+        uiCommand: UINT,
+        pData: LPVOID,
+        pcbSize: *mut UINT,
+        cbSizeHeader: UINT,
+    ) -> UINT;
     pub fn GetAsyncKeyState(vKey: c_int) -> SHORT;
     pub fn GetSystemMetrics(nIndex: c_int) -> c_int;
 
+    // Display settings
+    pub fn EnumDisplayDevicesW(
+        lpDevice: LPCWSTR,
+        iDevNum: DWORD,
+        lpDisplayDevice: *mut DISPLAY_DEVICEW,
+        dwFlags: DWORD,
+    ) -> BOOL;
+    pub fn EnumDisplaySettingsW(lpszDeviceName: LPCWSTR, iModeNum: DWORD, lpDevMode: *mut DEVMODEW) -> BOOL;
+    pub fn ChangeDisplaySettingsExW(
+        lpszDeviceName: LPCWSTR,
+        lpDevMode: *const DEVMODEW,
+        hwnd: HWND,
+        dwflags: DWORD,
+        lParam: LPVOID,
+    ) -> LONG;
+
     // Misc legacy garbage
+    pub fn GetDC(hWnd: HWND) -> HDC;
+    pub fn ReleaseDC(hWnd: HWND, hDC: HDC) -> c_int;
     pub fn EnableMenuItem(hMenu: HMENU, uIDEnableItem: UINT, uEnable: UINT) -> BOOL;
     pub fn GetSystemMenu(hWnd: HWND, bRevert: BOOL) -> HMENU;
 
@@ -466,6 +705,10 @@ dyn_link! {
                 dpi: UINT,
             ) -> BOOL;
 
+            /// (Win10 1607+)
+            /// Gets the DPI of the monitor a window is on, or the system DPI if it isn't per-monitor aware.
+            fn GetDpiForWindow(hwnd: HWND) -> UINT;
+
             /// (Win10 1603+)
             /// Enables automatic scaling of the non-client area as a hack for PMv1 DPI mode.
             fn EnableNonClientDpiScaling(hwnd: HWND) -> BOOL;
@@ -1,5 +1,8 @@
 use super::api::*;
-use crate::{monitor::Size};
+use crate::{
+    monitor::{Size, VideoMode},
+    window::CursorIcon,
+};
 use std::{mem, ptr, slice};
 
 #[cfg(feature = "cursor-lock")]
@@ -94,6 +97,12 @@ pub fn str_to_wide_null(src: &str, buffer: &mut Vec<WCHAR>) -> LPCWSTR {
     }
 }
 
+/// Reads a fixed-size, null-terminated wide string, such as the ones in `MONITORINFOEXW`.
+pub fn wide_array_to_string(src: &[WCHAR]) -> String {
+    let len = src.iter().position(|&c| c == 0x00).unwrap_or(src.len());
+    String::from_utf16_lossy(&src[..len])
+}
+
 pub fn lpcwstr_to_str(src: LPCWSTR, buffer: &mut Vec<u8>) {
     // This is more or less the inverse of `str_to_wide_null`, works the same way
 
@@ -173,6 +182,32 @@ pub unsafe fn ping_window_frame(hwnd: HWND) {
 }
 
 #[inline]
+/// Switches a monitor to a video mode until it's reset, returning the adapter name to reset it with.
+pub unsafe fn set_video_mode(hmonitor: HMONITOR, mode: &VideoMode) -> Option<[WCHAR; CCHDEVICENAME]> {
+    let mut info: MONITORINFOEXW = mem::zeroed();
+    info.cbSize = mem::size_of_val(&info) as DWORD;
+    if GetMonitorInfoW(hmonitor, &mut info) == FALSE {
+        return None
+    }
+    let mut devmode: DEVMODEW = mem::zeroed();
+    devmode.dmSize = mem::size_of_val(&devmode) as WORD;
+    devmode.dmFields = DM_PELSWIDTH | DM_PELSHEIGHT | DM_BITSPERPEL | DM_DISPLAYFREQUENCY;
+    devmode.dmPelsWidth = mode.size.0;
+    devmode.dmPelsHeight = mode.size.1;
+    devmode.dmBitsPerPel = mode.bit_depth.into();
+    devmode.dmDisplayFrequency = mode.refresh_rate.round() as DWORD;
+
+    // `CDS_FULLSCREEN` means the change is temporary, it's not saved to the registry
+    let device = info.szDevice;
+    let result = ChangeDisplaySettingsExW(device.as_ptr(), &devmode, ptr::null_mut(), CDS_FULLSCREEN, ptr::null_mut());
+    (result == DISP_CHANGE_SUCCESSFUL).then(|| device)
+}
+
+/// Resets an adapter to the video mode saved in the registry, undoing `set_video_mode`.
+pub unsafe fn reset_video_mode(device: &[WCHAR; CCHDEVICENAME]) {
+    let _ = ChangeDisplaySettingsExW(device.as_ptr(), ptr::null(), ptr::null_mut(), 0, ptr::null_mut());
+}
+
 pub fn rect_to_size2d(rect: &RECT) -> (LONG, LONG) {
     (rect.right - rect.left, rect.bottom - rect.top)
 }
@@ -186,6 +221,70 @@ pub unsafe fn client_area_screen_space(hwnd: HWND) -> RECT {
 }
 
 
+/// Asks for `WM_INPUT` from mice, which is sent to whichever window has focus.
+/// Registration is per process, so doing it again for every window changes nothing.
+pub unsafe fn register_raw_mouse() {
+    let device = RAWINPUTDEVICE {
+        usUsagePage: HID_USAGE_PAGE_GENERIC,
+        usUsage: HID_USAGE_GENERIC_MOUSE,
+        dwFlags: 0,
+        hwndTarget: ptr::null_mut(),
+    };
+    let _ = RegisterRawInputDevices(&device, 1, mem::size_of::<RAWINPUTDEVICE>() as UINT);
+}
+
+/// Gets the relative motion out of a `WM_INPUT`, if it was from a mouse that moved.
+pub unsafe fn raw_mouse_motion(handle: HRAWINPUT) -> Option<(LONG, LONG)> {
+    let mut input: RAWINPUT = mem::zeroed();
+    let mut size = mem::size_of::<RAWINPUT>() as UINT;
+    let header_size = mem::size_of::<RAWINPUTHEADER>() as UINT;
+    let result = GetRawInputData(handle, RID_INPUT, (&mut input) as *mut _ as LPVOID, &mut size, header_size);
+    if result == UINT::MAX || input.header.dwType != RIM_TYPEMOUSE {
+        return None
+    }
+
+    // Absolute positions come from tablets, touchscreens and remote desktop, which aren't relative motion
+    let mouse = &input.mouse;
+    if mouse.usFlags & MOUSE_MOVE_ABSOLUTE != 0 || (mouse.lLastX == 0 && mouse.lLastY == 0) {
+        None
+    } else {
+        Some((mouse.lLastX, mouse.lLastY))
+    }
+}
+
+/// Sets the cursor to one of the system cursors. It stays until the next `WM_SETCURSOR`.
+pub unsafe fn set_cursor_icon(icon: CursorIcon) {
+    let name = match icon {
+        CursorIcon::Arrow => IDC_ARROW,
+        CursorIcon::Text => IDC_IBEAM,
+        CursorIcon::Hand => IDC_HAND,
+        CursorIcon::Crosshair => IDC_CROSS,
+        CursorIcon::Wait => IDC_WAIT,
+        CursorIcon::Progress => IDC_APPSTARTING,
+        CursorIcon::NotAllowed => IDC_NO,
+        CursorIcon::Help => IDC_HELP,
+        CursorIcon::Move => IDC_SIZEALL,
+        CursorIcon::ResizeHorizontal => IDC_SIZEWE,
+        CursorIcon::ResizeVertical => IDC_SIZENS,
+        CursorIcon::ResizeNeSw => IDC_SIZENESW,
+        CursorIcon::ResizeNwSe => IDC_SIZENWSE,
+    };
+
+    // System cursors are shared, so there's nothing to free
+    let _ = SetCursor(LoadCursorW(ptr::null_mut(), name));
+}
+
+/// Asks for a `WM_MOUSELEAVE` once the cursor leaves the client area. This has to be redone after every one.
+pub unsafe fn track_mouse_leave(hwnd: HWND) {
+    let mut event = TRACKMOUSEEVENT {
+        cbSize: mem::size_of::<TRACKMOUSEEVENT>() as DWORD,
+        dwFlags: TME_LEAVE,
+        hwndTrack: hwnd,
+        dwHoverTime: 0,
+    };
+    let _ = TrackMouseEvent(&mut event);
+}
+
 #[cfg(feature = "cursor-lock")]
 pub unsafe fn update_cursor_lock(hwnd: HWND, kind: Option<CursorLock>, new: bool) {
     match kind {
@@ -293,6 +392,46 @@ impl Win32 {
         }
         rect_to_size2d(&window)
     }
+
+    /// Gets the DPI of a monitor, or the system DPI if the process isn't per-monitor aware.
+    pub unsafe fn monitor_dpi(&self, hmonitor: HMONITOR) -> UINT {
+        match self.dpi_mode {
+            DpiMode::PerMonitorV1 | DpiMode::PerMonitorV2 => {
+                let (mut dpi_x, mut dpi_y) = (0, 0);
+                if self.dl.GetDpiForMonitor(hmonitor, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y) == Some(S_OK) {
+                    dpi_x
+                } else {
+                    system_dpi()
+                }
+            },
+            DpiMode::System => system_dpi(),
+            DpiMode::Unsupported => BASE_DPI,
+        }
+    }
+
+    /// Gets the DPI of the primary monitor, which is where `CW_USEDEFAULT` puts new windows.
+    pub unsafe fn primary_monitor_dpi(&self) -> UINT {
+        self.monitor_dpi(MonitorFromPoint(POINT { x: 0, y: 0 }, MONITOR_DEFAULTTOPRIMARY))
+    }
+
+    /// Gets the DPI of the monitor a window is on.
+    pub unsafe fn window_dpi(&self, hwnd: HWND) -> UINT {
+        match self.dl.GetDpiForWindow(hwnd) {
+            Some(dpi) if dpi != 0 => dpi,
+            _ => self.monitor_dpi(MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST)),
+        }
+    }
+}
+
+/// Gets the DPI the whole desktop is scaled with, which is the most that System-Aware DPI can know.
+unsafe fn system_dpi() -> UINT {
+    let hdc = GetDC(ptr::null_mut());
+    if hdc.is_null() {
+        return BASE_DPI
+    }
+    let dpi = GetDeviceCaps(hdc, LOGPIXELSX);
+    let _ = ReleaseDC(ptr::null_mut(), hdc);
+    if dpi > 0 { dpi as UINT } else { BASE_DPI }
 }
 
 impl Default for Win32 {
@@ -301,3 +440,44 @@ impl Default for Win32 {
         Self::new()
     }
 }
+
+/// Enables or disables the IME for the window - disabled windows only ever get plain key presses.
+pub unsafe fn set_ime_allowed(hwnd: HWND, allowed: bool) {
+    // With no context given, `IACE_DEFAULT` restores the default one and zero removes it
+    let _ = ImmAssociateContextEx(hwnd, ptr::null_mut(), if allowed { IACE_DEFAULT } else { 0 });
+}
+
+/// Moves the IME composition & candidate windows to a point in client coordinates.
+pub unsafe fn set_ime_position(hwnd: HWND, (x, y): (LONG, LONG)) {
+    let himc = ImmGetContext(hwnd);
+    if himc.is_null() {
+        return
+    }
+    let mut composition = COMPOSITIONFORM {
+        dwStyle: CFS_POINT,
+        ptCurrentPos: POINT { x, y },
+        rcArea: mem::zeroed(),
+    };
+    let _ = ImmSetCompositionWindow(himc, &mut composition);
+    let mut candidate = CANDIDATEFORM {
+        dwIndex: 0,
+        dwStyle: CFS_CANDIDATEPOS,
+        ptCurrentPos: POINT { x, y },
+        rcArea: mem::zeroed(),
+    };
+    let _ = ImmSetCandidateWindow(himc, &mut candidate);
+    let _ = ImmReleaseContext(hwnd, himc);
+}
+
+/// Reads one of the `GCS_*STR` strings of the current IME composition.
+pub unsafe fn ime_composition_string(himc: HIMC, index: DWORD) -> Vec<WCHAR> {
+    // Lengths are in bytes, not characters
+    let len = ImmGetCompositionStringW(himc, index, ptr::null_mut(), 0);
+    if len <= 0 {
+        return Vec::new()
+    }
+    let mut buffer = vec![0 as WCHAR; len as usize / mem::size_of::<WCHAR>()];
+    let len = ImmGetCompositionStringW(himc, index, buffer.as_mut_ptr().cast(), len as DWORD);
+    buffer.truncate(len.max(0) as usize / mem::size_of::<WCHAR>());
+    buffer
+}
@@ -0,0 +1,1637 @@
+//! X11 specific implementations and API extensions.
+
+pub(crate) mod api;
+pub(crate) mod util;
+
+use api::*;
+use crate::{
+    error::Error,
+    event::{CloseReason, Event, Key, MouseButton},
+    helpers::{sync::{condvar_notify1, condvar_wait, mutex_lock, Condvar, Mutex}, LinkReport},
+    monitor::{Monitor, Point, Scale, Size, VideoMode},
+    platform::posix::{poll, pollfd, xkb, MessageQueue, POLLIN},
+    window::{CursorIcon, Fullscreen, SizeLimits, WindowBuilder, WindowControls, WindowImpl, WindowStyle},
+};
+use std::{cell, ffi::CStr, fmt, mem, ops, ptr, slice, sync::{self, atomic::{self, AtomicBool}}, thread};
+
+#[cfg(feature = "cursor-lock")]
+use crate::window::CursorLock;
+
+/// The initial capacity of the `Vec<Event>` structures
+///
+/// TODO: This should be bigger than normal if input is enabled
+const EVENT_BUF_INITIAL_SIZE: usize = 512;
+
+/// The events the window thread listens to on its window.
+const EVENT_MASK: c_long = StructureNotifyMask |
+    FocusChangeMask |
+    KeyPressMask |
+    KeyReleaseMask |
+    ButtonPressMask |
+    ButtonReleaseMask |
+    EnterWindowMask |
+    LeaveWindowMask |
+    PointerMotionMask |
+    PropertyChangeMask;
+
+#[derive(Debug)]
+pub struct InternalError {
+    code: c_int,
+    context: &'static str,
+    message: String,
+}
+
+impl std::error::Error for InternalError {}
+impl fmt::Display for InternalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (Code {}; {})", self.message.as_str(), self.code, self.context)
+    }
+}
+
+impl InternalError {
+    pub(crate) fn from_xlib(display: *mut Display, context: &'static str, code: c_int) -> Self {
+        Self {
+            code,
+            context,
+            message: unsafe { util::error_string_repr(display, code) },
+        }
+    }
+
+    pub(crate) fn from_io(context: &'static str, err: std::io::Error) -> Self {
+        Self {
+            code: err.raw_os_error().unwrap_or(0),
+            context,
+            message: err.to_string(),
+        }
+    }
+
+    pub(crate) fn from_link_report(context: &'static str, report: &LinkReport) -> Self {
+        Self {
+            code: 0,
+            context,
+            message: report.to_string(),
+        }
+    }
+
+    pub(crate) fn no_display(context: &'static str) -> Self {
+        Self {
+            code: 0,
+            context,
+            message: String::from("Can't open display (is $DISPLAY set?)"),
+        }
+    }
+}
+
+pub(crate) struct Window {
+    // guts
+    thread: Option<thread::JoinHandle<()>>,
+
+    // api
+    user_data: Box<cell::UnsafeCell<WindowUserData>>,
+    event_buffer: Vec<Event>,
+}
+unsafe impl Send for Window {}
+unsafe impl Sync for Window {}
+
+pub(crate) type WindowRepr = Window;
+
+/// Requests for the window thread, the equivalent of the custom `WM_USER` messages on Win32.
+enum Message {
+    /// Run arbitrary functions.
+    Execute(*mut &'static mut dyn FnMut()),
+
+    /// Destroy the window and exit the window thread.
+    Destroy,
+
+    /// Get the monitor the window is on, written to the pointer.
+    GetCurrentMonitor(*mut Option<Monitor>),
+
+    /// Get the `WindowUserDpiData` struct, written to the pointer.
+    GetDpiData(*mut WindowUserDpiData),
+
+    /// Get whether the window has focus, written to the pointer.
+    GetFocusState(*mut bool),
+
+    /// Get the window title, written to the pointer.
+    GetTitle(*mut String),
+
+    /// Get the `WindowStyle` struct, written to the pointer.
+    GetWindowStyle(*mut WindowStyle),
+
+    /// Set the aspect ratio the inner size is kept at.
+    SetAspectRatio(Option<(u32, u32)>),
+
+    /// Set whether the window is without a border.
+    SetBorderless(bool),
+
+    /// Update window controls.
+    SetControls(Option<WindowControls>),
+
+    /// Set the cursor icon.
+    SetCursorIcon(CursorIcon),
+
+    /// Set the cursor lock.
+    #[cfg(feature = "cursor-lock")]
+    SetCursorLock(Option<CursorLock>),
+
+    /// Enter, change or leave fullscreen.
+    SetFullscreen(Option<Fullscreen>),
+
+    /// Set whether the IME can be used.
+    SetImeAllowed(bool),
+
+    /// Set the IME candidate window position.
+    SetImePosition(Point),
+
+    /// Set the inner size, or the size to go back to after fullscreen.
+    SetInnerSize(Size),
+
+    /// Set the largest inner size.
+    SetMaxInnerSize(Option<Size>),
+
+    /// Maximize or restore the window.
+    SetMaximized(bool),
+
+    /// Set the smallest inner size.
+    SetMinInnerSize(Option<Size>),
+
+    /// Minimize or restore the window.
+    SetMinimized(bool),
+
+    /// Set the outer position, or the position to go back to after fullscreen.
+    SetOuterPosition(Point),
+
+    /// Set whether the window is resizable.
+    SetResizable(bool),
+
+    /// Set the window title.
+    SetTitle(String),
+
+    /// Set whether the window is visible.
+    SetVisible(bool),
+}
+
+struct WindowUserData {
+    cursor: Cursor,
+    cursor_icon: CursorIcon,
+    #[cfg(feature = "cursor-lock")]
+    cursor_constrain_escaped: bool,
+    #[cfg(feature = "cursor-lock")]
+    cursor_lock: Option<CursorLock>,
+    event_queue: Mutex<Vec<Event>>,
+    focus_state: bool,
+    fullscreen: Option<FullscreenState>,
+    ime_allowed: bool,
+    ime_position: Point,
+    maximized: AtomicBool,
+    minimized: AtomicBool,
+    mouse_inside: bool,
+    outer_position: Mutex<Point>,
+    position: (c_int, c_int),
+    size_limits: SizeLimits,
+    title: String,
+    window_style: WindowStyle,
+    xi_opcode: Option<c_int>,
+    screen_change_event: Option<c_int>,
+
+    dpi_data: WindowUserDpiData,
+
+    // Input method state, with the preedit text as sent by `XNPreeditDrawCallback`
+    ic: XIC,
+    im: XIM,
+    ime_callbacks: [XICCallback; 4],
+    ime_caret: usize,
+    ime_composing: bool,
+    ime_preedit: Vec<char>,
+
+    // X11 state, only accessed from the window thread
+    atoms: util::Atoms,
+    compose: Option<xkb::Compose>,
+    detectable_repeat: bool,
+    display: *mut Display,
+    handle: XID,
+    keys_down: [bool; 256],
+    root: XID,
+    screen: c_int,
+
+    message_queue: MessageQueue<Message>,
+}
+
+/// What's needed to leave fullscreen again.
+struct FullscreenState {
+    fullscreen: Fullscreen,
+    windowed_position: (c_int, c_int),
+    windowed_client: (u32, u32),
+    // The CRTC whose video mode was changed and the mode it had, for `Fullscreen::Exclusive`
+    changed_mode: Option<(RRCrtc, RRMode)>,
+}
+
+#[derive(Copy, Clone, Default)]
+struct WindowUserDpiData {
+    client: (u32, u32),
+    is_logical: bool,
+    scale_factor: f64,
+}
+
+impl WindowUserDpiData {
+    /// Gets the client size in the `Size` convention the window was created with, along with the scale.
+    fn inner_size(&self) -> (Size, Scale) {
+        let (width, height) = self.client;
+        let inner_size = Size::Physical(width, height);
+        if self.is_logical {
+            (inner_size.to_logical(self.scale_factor), self.scale_factor)
+        } else {
+            (inner_size, self.scale_factor)
+        }
+    }
+
+    /// Converts a physical position to the `Point` convention the window was created with.
+    fn point(&self, x: c_int, y: c_int) -> Point {
+        let point = Point::Physical(x, y);
+        if self.is_logical {
+            point.to_logical(self.scale_factor)
+        } else {
+            point
+        }
+    }
+}
+
+pub(crate) fn monitors() -> Result<Vec<Monitor>, Error> {
+    let report = XLIB.link_report();
+    if !report.is_complete() {
+        return Err(Error::from_internal(InternalError::from_link_report("Failed to load libX11.", report)))
+    }
+    unsafe {
+        util::init_xlib();
+        let display = XOpenDisplay(ptr::null());
+        if display.is_null() {
+            return Err(Error::from_internal(InternalError::no_display("XOpenDisplay returned NULL.")))
+        }
+        let screen = XDefaultScreen(display);
+        let root = XRootWindow(display, screen);
+        let monitors = util::monitors(display, screen, root, &util::Atoms::new(display));
+        let _ = XCloseDisplay(display);
+        Ok(monitors)
+    }
+}
+
+pub(crate) fn video_modes(monitor: &Monitor) -> Vec<VideoMode> {
+    if !XLIB.link_report().is_complete() {
+        return Vec::new()
+    }
+    unsafe {
+        util::init_xlib();
+        let display = XOpenDisplay(ptr::null());
+        if display.is_null() {
+            return Vec::new()
+        }
+        let screen = XDefaultScreen(display);
+        let modes = util::video_modes(display, screen, XRootWindow(display, screen), monitor);
+        let _ = XCloseDisplay(display);
+        modes
+    }
+}
+
+pub(crate) fn make_window(builder: &WindowBuilder) -> Result<WindowRepr, Error> {
+    // Condvar & mutex pair for receiving the `Result<WindowRepr, Error>` from spawned thread
+    let signal = sync::Arc::new((Mutex::<Option<Result<WindowRepr, Error>>>::new(None), Condvar::new()));
+
+    let builder = builder.clone();
+    let cond_pair = sync::Arc::clone(&signal);
+    let thread_builder = thread::Builder::new()
+        .name(format!("Window Thread (Class \"{}\")", builder.class_name.as_ref()));
+    let window_thread = thread_builder.spawn(move || unsafe {
+        let result = create_window(&builder);
+        let user_data_ptr = result.as_ref().ok().map(|window| window.user_data.get());
+
+        // Yield window struct, signal outer function
+        // NOTE: For future developments, do not insert panics before this,
+        // or the waiting condvar never gets anything and thus the outer fn never returns
+        let (mutex, condvar) = &*cond_pair;
+        let mut lock = mutex_lock(mutex);
+        *lock = Some(result);
+        condvar_notify1(condvar);
+        mem::drop(lock);
+
+        // Release condvar + mutex pair so the `Arc` contents are deallocated once the outer fn returns
+        mem::drop(cond_pair);
+
+        // Run message loop until exit, if the window was created
+        if let Some(user_data_ptr) = user_data_ptr {
+            let user_data = &mut *user_data_ptr;
+            message_loop(user_data);
+
+            // The video mode outlives the window otherwise
+            if let Some((crtc, mode)) = user_data.fullscreen.take().and_then(|state| state.changed_mode) {
+                util::reset_video_mode(user_data.display, user_data.root, crtc, mode);
+            }
+            if !user_data.ic.is_null() {
+                XDestroyIC(user_data.ic);
+                let _ = XCloseIM(user_data.im);
+            }
+            let _ = XDestroyWindow(user_data.display, user_data.handle);
+            let _ = XCloseDisplay(user_data.display);
+        }
+    }).expect("Failed to spawn window thread");
+
+    // Wait until the thread is done creating the window or notifying us why it couldn't do that
+    let (mutex, condvar) = &*signal;
+    let mut lock = mutex_lock(mutex);
+    loop {
+        if let Some(result) = lock.take() {
+            break result.map(|mut window| {
+                window.thread = Some(window_thread);
+                window
+            })
+        } else {
+            condvar_wait(condvar, &mut lock);
+        }
+    }
+}
+
+/// Creates the window on the calling thread, which becomes the window thread.
+unsafe fn create_window(builder: &WindowBuilder) -> Result<Window, Error> {
+    let report = XLIB.link_report();
+    if !report.is_complete() {
+        return Err(Error::from_internal(InternalError::from_link_report("Failed to load libX11.", report)))
+    }
+    util::init_xlib();
+
+    let display = XOpenDisplay(ptr::null());
+    if display.is_null() {
+        return Err(Error::from_internal(InternalError::no_display("XOpenDisplay returned NULL.")))
+    }
+
+    let message_queue = match MessageQueue::new() {
+        Ok(queue) => queue,
+        Err(err) => {
+            let _ = XCloseDisplay(display);
+            return Err(Error::from_internal(InternalError::from_io("pipe failed.", err)))
+        },
+    };
+
+    // Without this, held keys send a release before every repeated press
+    let mut detectable_repeat = False;
+    let _ = XkbSetDetectableAutoRepeat(display, True, &mut detectable_repeat);
+
+    let screen = XDefaultScreen(display);
+    let root = XRootWindow(display, screen);
+    let atoms = util::Atoms::new(display);
+
+    // `Xft.dpi` is on the root window, and changes to it are picked up in `handle_event`
+    let _ = XSelectInput(display, root, PropertyChangeMask);
+    let dpi_fac = util::scale_factor(display, root);
+    let (width, height) = builder.size_limits.clamp(builder.inner_size.physical(dpi_fac), dpi_fac);
+    let position = if let Some(position) = builder.position {
+        Some(position.physical(dpi_fac))
+    } else if builder.centered {
+        util::monitors(display, screen, root, &atoms).into_iter().find(Monitor::is_primary).map(|monitor| {
+            let (position, size) = monitor.work_area();
+            let ((x, y), (work_width, work_height)) = (position.physical(1.0), size.physical(1.0));
+            (x + (work_width as c_int - width as c_int) / 2, y + (work_height as c_int - height as c_int) / 2)
+        })
+    } else {
+        None
+    };
+    let (x, y) = position.unwrap_or((0, 0));
+
+    let mut attributes: XSetWindowAttributes = mem::zeroed();
+    attributes.event_mask = EVENT_MASK;
+    let handle = XCreateWindow(
+        display,
+        root,
+        x,
+        y,
+        width.max(1),
+        height.max(1),
+        0,
+        CopyFromParent,
+        InputOutput,
+        ptr::null_mut(), // CopyFromParent
+        CWBorderPixel | CWEventMask,
+        &mut attributes,
+    );
+
+    // Errors are asynchronous, so we need a round-trip to know if creation went through
+    let _ = XSync(display, False);
+    if let Some(code) = util::take_last_error() {
+        let err = InternalError::from_xlib(display, "XCreateWindow failed.", code.into());
+        let _ = XCloseDisplay(display);
+        return Err(Error::from_internal(err))
+    }
+
+    let mut protocols = [atoms.wm_delete_window, atoms.net_wm_ping];
+    let _ = XSetWMProtocols(display, handle, protocols.as_mut_ptr(), protocols.len() as c_int);
+    util::set_title(display, handle, &atoms, builder.title.as_ref());
+    util::set_class_hint(display, handle, builder.class_name.as_ref());
+    util::set_motif_hints(display, handle, &atoms, &builder.style);
+    let cursor = util::load_cursor(display, builder.cursor_icon);
+    let _ = XDefineCursor(display, handle, cursor);
+    let resizable = builder.style.resizable;
+    util::set_size_hints(display, handle, resizable, (width, height), &builder.size_limits, dpi_fac, position);
+    if builder.style.visible {
+        let _ = XMapRaised(display, handle);
+    }
+    let _ = XFlush(display);
+
+    let user_data = Box::new(cell::UnsafeCell::new(WindowUserData {
+        cursor,
+        cursor_icon: builder.cursor_icon,
+        #[cfg(feature = "cursor-lock")]
+        cursor_constrain_escaped: false,
+        #[cfg(feature = "cursor-lock")]
+        cursor_lock: builder.cursor_lock,
+        event_queue: Mutex::new(Vec::with_capacity(EVENT_BUF_INITIAL_SIZE)),
+        focus_state: false,
+        fullscreen: None,
+        ime_allowed: false,
+        ime_position: Point::Physical(0, 0),
+        maximized: AtomicBool::new(false),
+        minimized: AtomicBool::new(false),
+        mouse_inside: false,
+        outer_position: Mutex::new(Point::Physical(x, y)),
+        position: (x, y),
+        size_limits: builder.size_limits,
+        title: String::from(builder.title.as_ref()),
+        window_style: builder.style.clone(),
+        xi_opcode: util::select_raw_motion(display, root),
+        screen_change_event: util::select_screen_change(display, root),
+
+        dpi_data: WindowUserDpiData {
+            client: (width, height),
+            is_logical: matches!(builder.inner_size, Size::Logical(..)),
+            scale_factor: dpi_fac,
+        },
+
+        ic: ptr::null_mut(),
+        im: ptr::null_mut(),
+        ime_callbacks: [
+            XICCallback { client_data: ptr::null_mut(), callback: Some(ime_preedit_start) },
+            XICCallback { client_data: ptr::null_mut(), callback: Some(ime_preedit_done) },
+            XICCallback { client_data: ptr::null_mut(), callback: Some(ime_preedit_draw) },
+            XICCallback { client_data: ptr::null_mut(), callback: Some(ime_preedit_caret) },
+        ],
+        ime_caret: 0,
+        ime_composing: false,
+        ime_preedit: Vec::new(),
+
+        atoms,
+        compose: xkb::Compose::from_locale(),
+        detectable_repeat: detectable_repeat != False,
+        display,
+        handle,
+        keys_down: [false; 256],
+        root,
+        screen,
+
+        message_queue,
+    }));
+
+    // The IME callbacks point to the user data, so this has to wait until it's boxed
+    create_ic(&mut *user_data.get());
+    if builder.fullscreen.is_some() {
+        set_fullscreen(&mut *user_data.get(), builder.fullscreen.clone());
+    }
+
+    Ok(Window {
+        thread: None,
+        user_data,
+        event_buffer: Vec::with_capacity(EVENT_BUF_INITIAL_SIZE),
+    })
+}
+
+unsafe fn message_loop(user_data: &mut WindowUserData) {
+    let display = user_data.display;
+    let mut poll_fds = [
+        pollfd { fd: XConnectionNumber(display), events: POLLIN, revents: 0 },
+        pollfd { fd: user_data.message_queue.wake_fd(), events: POLLIN, revents: 0 },
+    ];
+    let mut event = mem::MaybeUninit::<XEvent>::zeroed().assume_init();
+
+    'message_loop: loop {
+        // Handle everything the X server sent (`XPending` also flushes our requests)
+        while XPending(display) != 0 {
+            let _ = XNextEvent(display, &mut event);
+
+            // Events the input method wants for itself, such as key presses while composing
+            if XFilterEvent(&mut event, 0) != False {
+                continue
+            }
+            handle_event(user_data, &event);
+        }
+
+        // Handle everything the `Window` sent
+        for message in user_data.message_queue.take() {
+            let destroy = matches!(message, Message::Destroy);
+            process_message(user_data, message);
+            user_data.message_queue.mark_processed();
+            if destroy {
+                break 'message_loop
+            }
+        }
+
+        // Processing messages can involve round-trips which read events into the queue,
+        // so only sleep if there's truly nothing left to do
+        if XPending(display) != 0 {
+            continue
+        }
+        let _ = poll(poll_fds.as_mut_ptr(), poll_fds.len() as c_ulong, -1);
+    }
+}
+
+/// Re-reads the scale factor, resizing windows with a logical size to keep it.
+unsafe fn update_scale_factor(user_data: &mut WindowUserData) {
+    let scale_factor = util::scale_factor(user_data.display, user_data.root);
+    if scale_factor == user_data.dpi_data.scale_factor {
+        return
+    }
+    let (size, _) = user_data.dpi_data.inner_size();
+    user_data.dpi_data.scale_factor = scale_factor;
+    push_event(user_data, Event::ScaleFactor(scale_factor));
+
+    // The resulting `ConfigureNotify` sends the `Resize`
+    if user_data.fullscreen.is_none() {
+        let client = if user_data.dpi_data.is_logical {
+            let (width, height) = size.physical(scale_factor);
+            (width.max(1), height.max(1))
+        } else {
+            user_data.dpi_data.client
+        };
+        let client = user_data.size_limits.clamp(client, scale_factor);
+        let (display, handle, resizable) = (user_data.display, user_data.handle, user_data.window_style.resizable);
+        util::set_size_hints(display, handle, resizable, client, &user_data.size_limits, scale_factor, None);
+        if client != user_data.dpi_data.client {
+            let _ = XResizeWindow(display, handle, client.0, client.1);
+        }
+    }
+}
+
+#[inline]
+fn push_event(user_data: &mut WindowUserData, event: Event) {
+    let mut lock = mutex_lock(&user_data.event_queue);
+    lock.push(event);
+    mem::drop(lock);
+}
+
+#[allow(non_upper_case_globals)]
+unsafe fn handle_event(user_data: &mut WindowUserData, event: &XEvent) {
+    match event.type_ {
+        ClientMessage => {
+            let event = &event.client_message;
+            if event.message_type == user_data.atoms.wm_protocols {
+                let protocol = event.data[0] as Atom;
+                if protocol == user_data.atoms.wm_delete_window {
+                    // The window manager doesn't tell us why, it could be the close button or Alt+F4
+                    push_event(user_data, Event::CloseRequest(CloseReason::Unknown));
+                } else if protocol == user_data.atoms.net_wm_ping {
+                    util::reply_ping(user_data.display, user_data.root, event);
+                }
+            }
+        },
+
+        // Received when the window loses or gains focus.
+        FocusIn | FocusOut => {
+            let event = &event.focus_change;
+
+            // Grabs (including our own cursor lock) send focus events that don't change anything
+            if event.mode == NotifyGrab || event.mode == NotifyUngrab || event.detail == NotifyPointer {
+                return
+            }
+            let focus = event.type_ == FocusIn;
+            if focus == user_data.focus_state {
+                return
+            }
+            user_data.focus_state = focus;
+            push_event(user_data, Event::Focus(focus));
+            update_ic_focus(user_data);
+
+            // Releases happening while unfocused are never received
+            if !focus {
+                user_data.keys_down = [false; 256];
+                if let Some(compose) = user_data.compose.as_mut() {
+                    compose.reset();
+                }
+            }
+
+            #[cfg(feature = "cursor-lock")]
+            {
+                if focus && user_data.cursor_lock.is_some() {
+                    user_data.cursor_constrain_escaped = !util::update_cursor_lock(
+                        user_data.display,
+                        user_data.handle,
+                        user_data.cursor_lock,
+                        user_data.dpi_data.client,
+                        false,
+                    );
+                } else if !focus && user_data.cursor_lock.is_some() {
+                    let _ = util::update_cursor_lock(user_data.display, user_data.handle, None, (0, 0), true);
+                }
+            }
+        },
+
+        KeyPress | KeyRelease => {
+            let mut event = event.key;
+            let keycode = event.keycode as usize & 0xFF;
+
+            // Input methods send their committed text as a key press with no key
+            if keycode == 0 {
+                if event.type_ == KeyPress && !user_data.ic.is_null() {
+                    ime_input(user_data, &mut event);
+                }
+                return
+            }
+
+            // Without detectable auto-repeat, a repeat is a release immediately followed by a press
+            // with the same timestamp - skip the release so the press is seen as a repeat
+            if event.type_ == KeyRelease && !user_data.detectable_repeat &&
+                XEventsQueued(user_data.display, QueuedAfterReading) != 0
+            {
+                let mut next = mem::MaybeUninit::<XEvent>::zeroed().assume_init();
+                let _ = XPeekEvent(user_data.display, &mut next);
+                if next.type_ == KeyPress && next.key.keycode == event.keycode && next.key.time == event.time {
+                    return
+                }
+            }
+
+            // X servers use evdev codes offset by 8, as 0-7 are reserved
+            let key = Key::from_evdev((keycode as u32).wrapping_sub(8));
+            let virtual_key = XLookupKeysym(&mut event, 0) as u32;
+            let was_down = mem::replace(&mut user_data.keys_down[keycode], event.type_ == KeyPress);
+            push_event(user_data, match (event.type_ == KeyPress, was_down) {
+                (true, false) => Event::KeyboardDown { key, virtual_key },
+                (true, true) => Event::KeyboardRepeat { key, virtual_key },
+                (false, _) => Event::KeyboardUp { key, virtual_key },
+            });
+            if event.type_ == KeyPress {
+                if user_data.ime_allowed && !user_data.ic.is_null() {
+                    ime_input(user_data, &mut event);
+                } else if let Some(chr) = key_input(user_data, &mut event) {
+                    push_event(user_data, Event::Input(chr));
+                }
+            }
+        },
+
+        MotionNotify => {
+            #[cfg(feature = "cursor-lock")]
+            {
+                // If the lock couldn't be acquired before (usually the window manager had a grab
+                // because the user was dragging the window around), it's re-acquired here.
+                let event = &event.motion;
+                let (width, height) = user_data.dpi_data.client;
+                let at_center = event.x == (width / 2) as c_int && event.y == (height / 2) as c_int;
+                let relock = match user_data.cursor_lock {
+                    Some(CursorLock::Constrain) => user_data.cursor_constrain_escaped,
+                    Some(CursorLock::Center) => !at_center || user_data.cursor_constrain_escaped,
+                    None => false,
+                };
+                if relock && user_data.focus_state {
+                    user_data.cursor_constrain_escaped = !util::update_cursor_lock(
+                        user_data.display,
+                        user_data.handle,
+                        user_data.cursor_lock,
+                        user_data.dpi_data.client,
+                        false,
+                    );
+                }
+            }
+
+            let event = &event.motion;
+            let point = user_data.dpi_data.point(event.x, event.y);
+            push_event(user_data, Event::MouseMove(point));
+        },
+
+        // Received when the window is moved, resized or restacked
+        ConfigureNotify => {
+            let event = &event.configure;
+            let client = (event.width as u32, event.height as u32);
+            if client != user_data.dpi_data.client {
+                user_data.dpi_data.client = client;
+                push_event(user_data, Event::Resize(user_data.dpi_data.inner_size()));
+            }
+
+            let position = util::outer_position(user_data.display, user_data.handle, user_data.root, &user_data.atoms);
+            if position != user_data.position {
+                user_data.position = position;
+                let point = user_data.dpi_data.point(position.0, position.1);
+                *mutex_lock(&user_data.outer_position) = point;
+                push_event(user_data, Event::Move(point));
+            }
+        },
+
+        // Received when a property changes on the window or the root window.
+        PropertyNotify => {
+            let event = &event.property;
+            if event.window == user_data.root && event.atom == XA_RESOURCE_MANAGER {
+                update_scale_factor(user_data);
+            } else if event.window == user_data.handle && event.atom == user_data.atoms.net_wm_state {
+                update_window_state(user_data);
+            }
+        },
+
+        // XInput 2 events, of which only raw motion is selected
+        GenericEvent => {
+            let mut cookie = event.cookie;
+            if Some(cookie.extension) == user_data.xi_opcode && XGetEventData(user_data.display, &mut cookie) != False {
+                if cookie.evtype == XI_RawMotion && user_data.focus_state {
+                    let (dx, dy) = util::raw_motion_delta(&*(cookie.data as *const XIRawEvent));
+                    if dx != 0.0 || dy != 0.0 {
+                        push_event(user_data, Event::RawMouseMotion { dx, dy });
+                    }
+                }
+                XFreeEventData(user_data.display, &mut cookie);
+            }
+        },
+
+        // Grabs (by us, for the cursor lock, or by the window manager) send these as well,
+        // so they're only passed on when they change something
+        EnterNotify | LeaveNotify => {
+            let event = &event.crossing;
+            let inside = event.type_ == EnterNotify;
+            if inside != user_data.mouse_inside {
+                user_data.mouse_inside = inside;
+                if inside {
+                    push_event(user_data, Event::MouseEnter);
+                    let point = user_data.dpi_data.point(event.x, event.y);
+                    push_event(user_data, Event::MouseMove(point));
+                } else {
+                    push_event(user_data, Event::MouseLeave);
+                }
+            }
+        },
+
+        // The core protocol reports the scroll wheel as buttons 4 to 7, with a press and release for every notch
+        ButtonPress | ButtonRelease => {
+            let event = &event.button;
+            let down = event.type_ == ButtonPress;
+            let button = match event.button {
+                1 => MouseButton::Left,
+                2 => MouseButton::Middle,
+                3 => MouseButton::Right,
+                4..=7 => {
+                    if down {
+                        let delta = match event.button {
+                            4 => (0.0, 1.0),
+                            5 => (0.0, -1.0),
+                            6 => (-1.0, 0.0),
+                            _ => (1.0, 0.0),
+                        };
+                        push_event(user_data, Event::MouseWheel { delta, precise: false });
+                    }
+                    return
+                },
+                8 => MouseButton::X1,
+                9 => MouseButton::X2,
+                _ => return,
+            };
+            push_event(user_data, if down { Event::MouseDown(button) } else { Event::MouseUp(button) });
+        },
+
+        // XRandR events don't have a fixed type, see `util::select_screen_change`
+        ty if Some(ty) == user_data.screen_change_event => {
+            // This keeps Xlib's idea of the screen size up to date
+            let _ = XRRUpdateConfiguration(event as *const XEvent as *mut XEvent);
+            push_event(user_data, Event::MonitorsChanged);
+        },
+
+        _ => (),
+    }
+}
+
+/// Gets the character typed by a key press, going through compose sequences if they're available.
+unsafe fn key_input(user_data: &mut WindowUserData, event: &mut XKeyEvent) -> Option<char> {
+    // The returned Latin-1 text isn't of much use, but the keysym has the modifiers applied
+    let mut keysym: KeySym = 0;
+    let mut buffer = [0 as c_char; 16];
+    let len = XLookupString(event, buffer.as_mut_ptr(), buffer.len() as c_int, &mut keysym, ptr::null_mut());
+    let keysym = keysym as xkb::xkb_keysym_t;
+
+    let chr = match user_data.compose.as_mut().map(|compose| compose.feed(keysym)) {
+        Some(xkb::ComposeStatus::Composing) => return None,
+        Some(xkb::ComposeStatus::Composed(composed)) => return xkb::keysym_to_char(composed),
+        Some(xkb::ComposeStatus::Ignored) | None => match xkb::keysym_to_char(keysym) {
+            Some(chr) => chr,
+
+            // Without `libxkbcommon`, the Latin-1 text is all we've got (and already has Ctrl applied)
+            None => return if len > 0 { Some(buffer[0] as u8 as char) } else { None },
+        },
+    };
+
+    if event.state & ControlMask != 0 {
+        Some(control_char(chr))
+    } else {
+        Some(chr)
+    }
+}
+
+/// Applies Ctrl to a character, the same way Xlib and `libxkbcommon` do it.
+fn control_char(chr: char) -> char {
+    if ('@'..='~').contains(&chr) || chr == ' ' {
+        ((chr as u8) & 0x1F) as char
+    } else {
+        chr
+    }
+}
+
+/// Creates the input context, without which there's no IME. Compose sequences still work through `libxkbcommon`.
+unsafe fn create_ic(user_data: &mut WindowUserData) {
+    let variadic = match XLIB_VARIADIC.as_ref() {
+        Some(variadic) => variadic,
+        None => return,
+    };
+    let display = user_data.display;
+    let handle = user_data.handle;
+
+    // This fails if the process locale isn't supported by Xlib or there's no input method server
+    let im = XOpenIM(display, ptr::null_mut(), ptr::null_mut(), ptr::null_mut());
+    if im.is_null() {
+        return
+    }
+
+    // Preedit callbacks are what give us the text being composed ("on-the-spot" in XIM terms),
+    // otherwise the input method shows it in a window of its own
+    let mut styles: *mut XIMStyles = ptr::null_mut();
+    let mut use_callbacks = false;
+    let mut supported = false;
+    if (variadic.XGetIMValues)(im, XNQueryInputStyle.as_ptr(), &mut styles, ptr::null_mut::<c_char>()).is_null() &&
+        !styles.is_null()
+    {
+        let styles_slice = slice::from_raw_parts((*styles).supported_styles, (*styles).count_styles as usize);
+        use_callbacks = styles_slice.contains(&(XIMPreeditCallbacks | XIMStatusNothing));
+        supported = use_callbacks || styles_slice.contains(&(XIMPreeditNothing | XIMStatusNothing));
+        let _ = XFree(styles.cast());
+    }
+    if !supported {
+        let _ = XCloseIM(im);
+        return
+    }
+
+    let ic = if use_callbacks {
+        let data = (user_data as *mut WindowUserData).cast::<c_char>();
+        for callback in &mut user_data.ime_callbacks {
+            callback.client_data = data;
+        }
+        let [start, done, draw, caret] = &mut user_data.ime_callbacks;
+        let attributes = (variadic.XVaCreateNestedList)(
+            0,
+            XNPreeditStartCallback.as_ptr(), start as *mut XICCallback,
+            XNPreeditDoneCallback.as_ptr(), done as *mut XICCallback,
+            XNPreeditDrawCallback.as_ptr(), draw as *mut XICCallback,
+            XNPreeditCaretCallback.as_ptr(), caret as *mut XICCallback,
+            ptr::null_mut::<c_char>(),
+        );
+        let ic = (variadic.XCreateIC)(
+            im,
+            XNInputStyle.as_ptr(), XIMPreeditCallbacks | XIMStatusNothing,
+            XNClientWindow.as_ptr(), handle,
+            XNFocusWindow.as_ptr(), handle,
+            XNPreeditAttributes.as_ptr(), attributes,
+            ptr::null_mut::<c_char>(),
+        );
+        let _ = XFree(attributes);
+        ic
+    } else {
+        (variadic.XCreateIC)(
+            im,
+            XNInputStyle.as_ptr(), XIMPreeditNothing | XIMStatusNothing,
+            XNClientWindow.as_ptr(), handle,
+            XNFocusWindow.as_ptr(), handle,
+            ptr::null_mut::<c_char>(),
+        )
+    };
+    if ic.is_null() {
+        let _ = XCloseIM(im);
+        return
+    }
+
+    // The input method may need more events than we normally listen to
+    let mut filter_events: c_long = 0;
+    let _ = (variadic.XGetICValues)(ic, XNFilterEvents.as_ptr(), &mut filter_events, ptr::null_mut::<c_char>());
+    let _ = XSelectInput(display, handle, EVENT_MASK | filter_events);
+
+    // Like everywhere else, the IME starts out disallowed
+    XUnsetICFocus(ic);
+    user_data.ic = ic;
+    user_data.im = im;
+}
+
+/// Gives the input context focus while the window is focused and the IME is allowed.
+unsafe fn update_ic_focus(user_data: &mut WindowUserData) {
+    if user_data.ic.is_null() {
+        return
+    }
+    if user_data.focus_state && user_data.ime_allowed {
+        XSetICFocus(user_data.ic);
+        update_ime_position(user_data);
+    } else {
+        XUnsetICFocus(user_data.ic);
+        if user_data.ime_composing {
+            user_data.ime_composing = false;
+            user_data.ime_preedit.clear();
+            push_event(user_data, Event::ImePreedit { text: String::new(), cursor_range: None });
+        }
+    }
+}
+
+/// Tells the input method where the candidate window goes.
+unsafe fn update_ime_position(user_data: &mut WindowUserData) {
+    if let (Some(variadic), false) = (XLIB_VARIADIC.as_ref(), user_data.ic.is_null()) {
+        let (x, y) = user_data.ime_position.physical(user_data.dpi_data.scale_factor);
+        let clamp = |n: i32| n.clamp(c_short::MIN.into(), c_short::MAX.into()) as c_short;
+        let mut spot = XPoint { x: clamp(x), y: clamp(y) };
+        let attributes = (variadic.XVaCreateNestedList)(0, XNSpotLocation.as_ptr(), &mut spot, ptr::null_mut::<c_char>());
+        let _ = (variadic.XSetICValues)(user_data.ic, XNPreeditAttributes.as_ptr(), attributes, ptr::null_mut::<c_char>());
+        let _ = XFree(attributes);
+    }
+}
+
+/// Looks up the text of a key press through the input context.
+/// Text from the input method itself is committed, anything else is typed as usual.
+unsafe fn ime_input(user_data: &mut WindowUserData, event: &mut XKeyEvent) {
+    let mut buffer = vec![0u8; 64];
+    let mut status: Status = 0;
+    let mut len = Xutf8LookupString(
+        user_data.ic,
+        event,
+        buffer.as_mut_ptr().cast(),
+        buffer.len() as c_int,
+        ptr::null_mut(),
+        &mut status,
+    );
+    if status == XBufferOverflow {
+        buffer.resize(len as usize, 0);
+        len = Xutf8LookupString(
+            user_data.ic,
+            event,
+            buffer.as_mut_ptr().cast(),
+            buffer.len() as c_int,
+            ptr::null_mut(),
+            &mut status,
+        );
+    }
+    if status != XLookupChars && status != XLookupBoth {
+        return
+    }
+    buffer.truncate(len.max(0) as usize);
+    let text = String::from_utf8_lossy(&buffer).into_owned();
+    if event.keycode == 0 || user_data.ime_composing {
+        push_event(user_data, Event::ImeCommit(text));
+    } else {
+        for chr in text.chars() {
+            push_event(user_data, Event::Input(chr));
+        }
+    }
+}
+
+fn push_ime_preedit(user_data: &mut WindowUserData) {
+    let caret = user_data.ime_caret.min(user_data.ime_preedit.len());
+    let cursor = user_data.ime_preedit[..caret].iter().map(|c| c.len_utf8()).sum();
+    let text = user_data.ime_preedit.iter().collect();
+    push_event(user_data, Event::ImePreedit { text, cursor_range: Some((cursor, cursor)) });
+}
+
+unsafe extern "C" fn ime_preedit_start(_ic: XIC, client_data: XPointer, _call_data: XPointer) -> c_int {
+    let user_data = &mut *(client_data as *mut WindowUserData);
+    user_data.ime_composing = true;
+    user_data.ime_caret = 0;
+    user_data.ime_preedit.clear();
+    -1 // no length limit
+}
+
+unsafe extern "C" fn ime_preedit_done(_ic: XIC, client_data: XPointer, _call_data: XPointer) -> c_int {
+    let user_data = &mut *(client_data as *mut WindowUserData);
+    user_data.ime_composing = false;
+    user_data.ime_preedit.clear();
+    push_event(user_data, Event::ImePreedit { text: String::new(), cursor_range: None });
+    0
+}
+
+unsafe extern "C" fn ime_preedit_draw(_ic: XIC, client_data: XPointer, call_data: XPointer) -> c_int {
+    let user_data = &mut *(client_data as *mut WindowUserData);
+    let call_data = &*(call_data as *const XIMPreeditDrawCallbackStruct);
+
+    // Replace `chg_length` characters at `chg_first` with the new text, if any
+    let len = user_data.ime_preedit.len();
+    let first = (call_data.chg_first.max(0) as usize).min(len);
+    let last = (first + call_data.chg_length.max(0) as usize).min(len);
+    let text: Vec<char> = match call_data.text.as_ref() {
+        Some(text) if !text.string.is_null() => {
+            if text.encoding_is_wchar != False {
+                let wide = slice::from_raw_parts(text.string as *const u32, text.length as usize);
+                wide.iter().filter_map(|&c| char::from_u32(c)).collect()
+            } else {
+                // This is in the encoding of the locale, which is going to be UTF-8 these days
+                let bytes = CStr::from_ptr(text.string as *const c_char).to_bytes();
+                String::from_utf8_lossy(bytes).chars().take(text.length as usize).collect()
+            }
+        },
+        _ => Vec::new(),
+    };
+    let _ = user_data.ime_preedit.splice(first..last, text);
+    user_data.ime_caret = call_data.caret.max(0) as usize;
+    push_ime_preedit(user_data);
+    0
+}
+
+#[allow(non_upper_case_globals)]
+unsafe extern "C" fn ime_preedit_caret(_ic: XIC, client_data: XPointer, call_data: XPointer) -> c_int {
+    let user_data = &mut *(client_data as *mut WindowUserData);
+    let call_data = &mut *(call_data as *mut XIMPreeditCaretCallbackStruct);
+    let len = user_data.ime_preedit.len();
+    let caret = user_data.ime_caret.min(len);
+    user_data.ime_caret = match call_data.direction {
+        XIMForwardChar => (caret + 1).min(len),
+        XIMBackwardChar => caret.saturating_sub(1),
+        XIMLineStart => 0,
+        XIMLineEnd => len,
+        XIMAbsolutePosition => (call_data.position.max(0) as usize).min(len),
+        _ => caret, // words and lines don't mean much in a single line of preedit text
+    };
+    call_data.position = user_data.ime_caret as c_int;
+    push_ime_preedit(user_data);
+    0
+}
+
+unsafe fn process_message(user_data: &mut WindowUserData, message: Message) {
+    let display = user_data.display;
+    let handle = user_data.handle;
+    match message {
+        Message::Execute(f) => (*f)(),
+
+        // The actual teardown is done once the message loop exits
+        Message::Destroy => (),
+
+        Message::GetCurrentMonitor(out) => *out = current_monitor(user_data),
+
+        Message::GetDpiData(out) => *out = user_data.dpi_data,
+
+        Message::GetFocusState(out) => *out = user_data.focus_state,
+
+        Message::GetTitle(out) => *out = user_data.title.clone(),
+
+        Message::GetWindowStyle(out) => *out = user_data.window_style.clone(),
+
+        // Fullscreen windows get their hints back when leaving fullscreen
+        Message::SetAspectRatio(aspect_ratio) => {
+            user_data.size_limits.aspect_ratio = aspect_ratio;
+            update_size_limits(user_data);
+        },
+
+        // The window manager keeps the client size as it is, so only the frame changes
+        Message::SetBorderless(borderless) => {
+            if user_data.window_style.borderless != borderless {
+                user_data.window_style.borderless = borderless;
+                if user_data.fullscreen.is_none() {
+                    util::set_motif_hints(display, handle, &user_data.atoms, &user_data.window_style);
+                }
+            }
+        },
+
+        Message::SetControls(controls) => {
+            if user_data.window_style.controls != controls {
+                user_data.window_style.controls = controls;
+                if user_data.fullscreen.is_none() {
+                    util::set_motif_hints(display, handle, &user_data.atoms, &user_data.window_style);
+                }
+            }
+        },
+
+        Message::SetCursorIcon(icon) => {
+            if user_data.cursor_icon != icon {
+                // The server keeps the old cursor around for as long as it's defined, so it can be freed right away
+                let cursor = util::load_cursor(display, icon);
+                let _ = XDefineCursor(display, handle, cursor);
+                let _ = XFreeCursor(display, user_data.cursor);
+                user_data.cursor = cursor;
+                user_data.cursor_icon = icon;
+            }
+        },
+
+        #[cfg(feature = "cursor-lock")]
+        Message::SetCursorLock(mode) => {
+            user_data.cursor_lock = mode;
+            if user_data.focus_state || mode.is_none() {
+                user_data.cursor_constrain_escaped =
+                    !util::update_cursor_lock(display, handle, mode, user_data.dpi_data.client, true);
+            }
+        },
+
+        Message::SetFullscreen(fullscreen) => set_fullscreen(user_data, fullscreen),
+
+        Message::SetImeAllowed(allowed) => {
+            if user_data.ime_allowed != allowed {
+                user_data.ime_allowed = allowed;
+                update_ic_focus(user_data);
+            }
+        },
+
+        Message::SetImePosition(position) => {
+            user_data.ime_position = position;
+            update_ime_position(user_data);
+        },
+
+        Message::SetInnerSize(inner_size) => {
+            let scale = user_data.dpi_data.scale_factor;
+            let (width, height) = inner_size.physical(scale);
+            let (width, height) = user_data.size_limits.clamp((width.max(1), height.max(1)), scale);
+            if let Some(state) = user_data.fullscreen.as_mut() {
+                state.windowed_client = (width, height);
+            } else {
+                // The resulting `ConfigureNotify` sends the `Resize`
+                if !user_data.window_style.resizable {
+                    util::set_size_hints(display, handle, false, (width, height), &user_data.size_limits, scale, None);
+                }
+                let _ = XResizeWindow(display, handle, width, height);
+                let _ = XFlush(display);
+            }
+        },
+
+        Message::SetMaxInnerSize(max_inner_size) => {
+            user_data.size_limits.max = max_inner_size;
+            update_size_limits(user_data);
+        },
+
+        Message::SetMaximized(maximized) => {
+            let state = [user_data.atoms.net_wm_state_maximized_horz, user_data.atoms.net_wm_state_maximized_vert];
+            let mapped = user_data.window_style.visible;
+            util::set_wm_state(display, handle, user_data.root, &user_data.atoms, mapped, state, maximized);
+            let _ = XFlush(display);
+        },
+
+        Message::SetMinInnerSize(min_inner_size) => {
+            user_data.size_limits.min = min_inner_size;
+            update_size_limits(user_data);
+        },
+
+        // Minimized windows are still mapped as far as we're concerned, mapping them again restores them
+        Message::SetMinimized(minimized) => {
+            if user_data.window_style.visible && user_data.minimized.load(atomic::Ordering::Acquire) != minimized {
+                if minimized {
+                    let _ = XIconifyWindow(display, handle, user_data.screen);
+                } else {
+                    let _ = XMapRaised(display, handle);
+                }
+                let _ = XFlush(display);
+            }
+        },
+
+        Message::SetOuterPosition(position) => {
+            let (x, y) = position.physical(user_data.dpi_data.scale_factor);
+            if let Some(state) = user_data.fullscreen.as_mut() {
+                state.windowed_position = (x, y);
+            } else {
+                let _ = XMoveWindow(display, handle, x, y);
+                let _ = XFlush(display);
+            }
+        },
+
+        Message::SetResizable(resizable) => {
+            if user_data.window_style.resizable != resizable {
+                user_data.window_style.resizable = resizable;
+                if user_data.fullscreen.is_none() {
+                    util::set_motif_hints(display, handle, &user_data.atoms, &user_data.window_style);
+                    let (client, scale) = (user_data.dpi_data.client, user_data.dpi_data.scale_factor);
+                    util::set_size_hints(display, handle, resizable, client, &user_data.size_limits, scale, None);
+                }
+            }
+        },
+
+        Message::SetTitle(title) => {
+            util::set_title(display, handle, &user_data.atoms, &title);
+            user_data.title = title;
+        },
+
+        Message::SetVisible(visible) => {
+            if visible {
+                let _ = XMapRaised(display, handle);
+            } else {
+                let _ = XWithdrawWindow(display, handle, user_data.screen);
+            }
+            user_data.window_style.visible = visible;
+        },
+    }
+}
+
+/// Re-reads `_NET_WM_STATE` after the window manager changes it, sending events for whatever changed.
+unsafe fn update_window_state(user_data: &mut WindowUserData) {
+    let (maximized, minimized) = util::wm_state(user_data.display, user_data.handle, &user_data.atoms);
+    if user_data.maximized.swap(maximized, atomic::Ordering::AcqRel) != maximized {
+        push_event(user_data, Event::Maximize(maximized));
+    }
+    if user_data.minimized.swap(minimized, atomic::Ordering::AcqRel) != minimized {
+        push_event(user_data, Event::Minimize(minimized));
+    }
+}
+
+/// Updates the size hints after the limits change, fitting the window into them.
+/// Nothing's done while fullscreen, as the limits are applied when leaving it.
+unsafe fn update_size_limits(user_data: &mut WindowUserData) {
+    if user_data.fullscreen.is_some() {
+        return
+    }
+    let (display, handle, resizable) = (user_data.display, user_data.handle, user_data.window_style.resizable);
+    let scale = user_data.dpi_data.scale_factor;
+    let client = user_data.size_limits.clamp(user_data.dpi_data.client, scale);
+    util::set_size_hints(display, handle, resizable, client, &user_data.size_limits, scale, None);
+
+    // The resulting `ConfigureNotify` sends the `Resize`
+    if client != user_data.dpi_data.client {
+        let _ = XResizeWindow(display, handle, client.0, client.1);
+    }
+    let _ = XFlush(display);
+}
+
+/// Gets the monitor the window overlaps the most, with ties (such as being off-screen) going to the primary.
+unsafe fn current_monitor(user_data: &WindowUserData) -> Option<Monitor> {
+    let (x, y) = user_data.position;
+    let (width, height) = user_data.dpi_data.client;
+    let (right, bottom) = (x + width as c_int, y + height as c_int);
+    let overlap = |monitor: &Monitor| {
+        let (left, top) = monitor.position().physical(1.0);
+        let (m_width, m_height) = monitor.size().physical(1.0);
+        let (m_right, m_bottom) = (left + m_width as c_int, top + m_height as c_int);
+        let overlap_x = (right.min(m_right) - x.max(left)).max(0) as u64;
+        let overlap_y = (bottom.min(m_bottom) - y.max(top)).max(0) as u64;
+        (overlap_x * overlap_y, monitor.is_primary())
+    };
+    let monitors = util::monitors(user_data.display, user_data.screen, user_data.root, &user_data.atoms);
+    monitors.into_iter().max_by_key(overlap)
+}
+
+/// Enters, changes or leaves fullscreen, putting the video mode and window geometry back as needed.
+unsafe fn set_fullscreen(user_data: &mut WindowUserData, fullscreen: Option<Fullscreen>) {
+    if user_data.fullscreen.as_ref().map(|state| &state.fullscreen) == fullscreen.as_ref() {
+        return
+    }
+    let (display, handle, root) = (user_data.display, user_data.handle, user_data.root);
+    let mapped = user_data.window_style.visible;
+
+    // Every change starts from the original video mode, so a failed switch doesn't leave a changed one behind
+    let (windowed_position, windowed_client) = match user_data.fullscreen.take() {
+        Some(state) => {
+            if let Some((crtc, mode)) = state.changed_mode {
+                util::reset_video_mode(display, root, crtc, mode);
+            }
+            (state.windowed_position, state.windowed_client)
+        },
+        None => (user_data.position, user_data.dpi_data.client),
+    };
+
+    let fullscreen = match fullscreen {
+        Some(fullscreen) => fullscreen,
+        None => {
+            let state = [user_data.atoms.net_wm_state_fullscreen, 0];
+            util::set_wm_state(display, handle, root, &user_data.atoms, mapped, state, false);
+            util::set_motif_hints(display, handle, &user_data.atoms, &user_data.window_style);
+            let (resizable, scale) = (user_data.window_style.resizable, user_data.dpi_data.scale_factor);
+            util::set_size_hints(display, handle, resizable, windowed_client, &user_data.size_limits, scale, None);
+
+            // The window manager puts the window back by itself, but there might not be one
+            let (x, y) = windowed_position;
+            let _ = XMoveResizeWindow(display, handle, x, y, windowed_client.0, windowed_client.1);
+            let _ = XFlush(display);
+            return
+        },
+    };
+
+    // If the mode can't be set, the window still covers the monitor at whatever mode it's at
+    let (position, size, changed_mode) = match &fullscreen {
+        Fullscreen::Borderless(monitor) => {
+            let monitor = match monitor.clone().or_else(|| current_monitor(user_data)) {
+                Some(monitor) => monitor,
+                None => return,
+            };
+            (monitor.position, monitor.size.physical(1.0), None)
+        },
+        Fullscreen::Exclusive(mode) => {
+            let changed_mode = util::set_video_mode(display, root, mode);
+            let size = if changed_mode.is_some() { mode.size } else { mode.monitor.size.physical(1.0) };
+            (mode.monitor.position, size, changed_mode)
+        },
+    };
+
+    // Window managers fullscreen windows on the monitor they're on, so it's moved there first.
+    // The window is also made borderless, for the ones that don't support `_NET_WM_STATE_FULLSCREEN`.
+    let (x, y) = position.physical(1.0);
+    let style = WindowStyle { borderless: true, ..user_data.window_style.clone() };
+    util::set_motif_hints(display, handle, &user_data.atoms, &style);
+    util::set_size_hints(display, handle, true, size, &SizeLimits::default(), 1.0, None);
+    let _ = XMoveResizeWindow(display, handle, x, y, size.0, size.1);
+    let state = [user_data.atoms.net_wm_state_fullscreen, 0];
+    util::set_wm_state(display, handle, root, &user_data.atoms, mapped, state, true);
+    let _ = XFlush(display);
+
+    user_data.fullscreen = Some(FullscreenState { fullscreen, windowed_position, windowed_client, changed_mode });
+}
+
+impl Window {
+    /// Sends a message to the window thread, blocking until it's been processed.
+    fn send_message(&self, message: Message) {
+        unsafe {
+            let user_data = self.user_data.get();
+
+            // Same as `SendMessageW`, we're already on the window thread so we just process it
+            if (*user_data).message_queue.is_owner_thread() {
+                process_message(&mut *user_data, message);
+            } else {
+                let queue = &(*user_data).message_queue;
+                queue.wait(queue.post(message));
+            }
+        }
+    }
+
+    /// Posts a message to the window thread without waiting for it to be processed.
+    #[inline]
+    fn post_message(&self, message: Message) {
+        let _ = unsafe { &*self.user_data.get() }.message_queue.post(message);
+    }
+
+    /// Gets a copy of the window style as last set, from the window thread.
+    fn window_style(&self) -> WindowStyle {
+        let mut style = WindowStyle::default();
+        self.send_message(Message::GetWindowStyle(&mut style));
+        style
+    }
+}
+
+impl WindowImpl for Window {
+    #[inline]
+    fn events(&self) -> &[Event] {
+        self.event_buffer.as_slice()
+    }
+
+    fn execute(&self, mut f: &mut dyn FnMut()) {
+        // The reference is only sent to the window thread while we block, so it's fine to erase the lifetime
+        let wrap: *mut &mut dyn FnMut() = &mut f;
+        self.send_message(Message::Execute(wrap.cast()));
+    }
+
+    #[inline]
+    fn controls(&self) -> Option<WindowControls> {
+        self.window_style().controls
+    }
+
+    fn current_monitor(&self) -> Option<Monitor> {
+        let mut monitor = None;
+        self.send_message(Message::GetCurrentMonitor(&mut monitor));
+        monitor
+    }
+
+    fn inner_size(&self) -> (Size, Scale) {
+        let mut info = WindowUserDpiData::default();
+        self.send_message(Message::GetDpiData(&mut info));
+        info.inner_size()
+    }
+
+    #[inline]
+    fn is_borderless(&self) -> bool {
+        self.window_style().borderless
+    }
+
+    fn is_focused(&self) -> bool {
+        let mut focused = false;
+        self.send_message(Message::GetFocusState(&mut focused));
+        focused
+    }
+
+    #[inline]
+    fn is_maximized(&self) -> bool {
+        let user_data = unsafe { &*self.user_data.get() };
+        user_data.maximized.load(atomic::Ordering::Acquire)
+    }
+
+    #[inline]
+    fn is_minimized(&self) -> bool {
+        let user_data = unsafe { &*self.user_data.get() };
+        user_data.minimized.load(atomic::Ordering::Acquire)
+    }
+
+    #[inline]
+    fn is_resizable(&self) -> bool {
+        self.window_style().resizable
+    }
+
+    #[inline]
+    fn is_visible(&self) -> bool {
+        self.window_style().visible
+    }
+
+    fn outer_position(&self) -> Point {
+        // Only ever locked briefly by the window thread, so there's no need to go through it
+        let user_data = unsafe { &*self.user_data.get() };
+        *mutex_lock(&user_data.outer_position)
+    }
+
+    fn title(&self) -> String {
+        let mut title = String::new();
+        self.send_message(Message::GetTitle(&mut title));
+        title
+    }
+
+    #[inline]
+    fn set_aspect_ratio(&self, aspect_ratio: Option<(u32, u32)>) {
+        self.send_message(Message::SetAspectRatio(aspect_ratio));
+    }
+
+    #[inline]
+    fn set_aspect_ratio_async(&self, aspect_ratio: Option<(u32, u32)>) {
+        self.post_message(Message::SetAspectRatio(aspect_ratio));
+    }
+
+    #[inline]
+    fn set_borderless(&self, borderless: bool) {
+        self.send_message(Message::SetBorderless(borderless));
+    }
+
+    #[inline]
+    fn set_borderless_async(&self, borderless: bool) {
+        self.post_message(Message::SetBorderless(borderless));
+    }
+
+    #[inline]
+    fn set_controls(&self, controls: Option<WindowControls>) {
+        self.send_message(Message::SetControls(controls));
+    }
+
+    #[inline]
+    fn set_controls_async(&self, controls: Option<WindowControls>) {
+        self.post_message(Message::SetControls(controls));
+    }
+
+    #[inline]
+    fn set_cursor_icon(&self, icon: CursorIcon) {
+        self.send_message(Message::SetCursorIcon(icon));
+    }
+
+    #[inline]
+    fn set_cursor_icon_async(&self, icon: CursorIcon) {
+        self.post_message(Message::SetCursorIcon(icon));
+    }
+
+    #[cfg(feature = "cursor-lock")]
+    #[inline]
+    fn set_cursor_lock(&self, mode: Option<CursorLock>) {
+        self.send_message(Message::SetCursorLock(mode));
+    }
+
+    #[cfg(feature = "cursor-lock")]
+    #[inline]
+    fn set_cursor_lock_async(&self, mode: Option<CursorLock>) {
+        self.post_message(Message::SetCursorLock(mode));
+    }
+
+    #[inline]
+    fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) {
+        self.send_message(Message::SetFullscreen(fullscreen));
+    }
+
+    #[inline]
+    fn set_fullscreen_async(&self, fullscreen: Option<Fullscreen>) {
+        self.post_message(Message::SetFullscreen(fullscreen));
+    }
+
+    #[inline]
+    fn set_ime_allowed(&self, allowed: bool) {
+        self.send_message(Message::SetImeAllowed(allowed));
+    }
+
+    #[inline]
+    fn set_ime_allowed_async(&self, allowed: bool) {
+        self.post_message(Message::SetImeAllowed(allowed));
+    }
+
+    #[inline]
+    fn set_ime_position(&self, position: Point) {
+        self.send_message(Message::SetImePosition(position));
+    }
+
+    #[inline]
+    fn set_ime_position_async(&self, position: Point) {
+        self.post_message(Message::SetImePosition(position));
+    }
+
+    #[inline]
+    fn set_inner_size(&self, inner_size: Size) {
+        self.send_message(Message::SetInnerSize(inner_size));
+    }
+
+    #[inline]
+    fn set_inner_size_async(&self, inner_size: Size) {
+        self.post_message(Message::SetInnerSize(inner_size));
+    }
+
+    #[inline]
+    fn set_max_inner_size(&self, max_inner_size: Option<Size>) {
+        self.send_message(Message::SetMaxInnerSize(max_inner_size));
+    }
+
+    #[inline]
+    fn set_max_inner_size_async(&self, max_inner_size: Option<Size>) {
+        self.post_message(Message::SetMaxInnerSize(max_inner_size));
+    }
+
+    #[inline]
+    fn set_maximized(&self, maximized: bool) {
+        self.send_message(Message::SetMaximized(maximized));
+    }
+
+    #[inline]
+    fn set_maximized_async(&self, maximized: bool) {
+        self.post_message(Message::SetMaximized(maximized));
+    }
+
+    #[inline]
+    fn set_min_inner_size(&self, min_inner_size: Option<Size>) {
+        self.send_message(Message::SetMinInnerSize(min_inner_size));
+    }
+
+    #[inline]
+    fn set_min_inner_size_async(&self, min_inner_size: Option<Size>) {
+        self.post_message(Message::SetMinInnerSize(min_inner_size));
+    }
+
+    #[inline]
+    fn set_minimized(&self, minimized: bool) {
+        self.send_message(Message::SetMinimized(minimized));
+    }
+
+    #[inline]
+    fn set_minimized_async(&self, minimized: bool) {
+        self.post_message(Message::SetMinimized(minimized));
+    }
+
+    #[inline]
+    fn set_outer_position(&self, position: Point) {
+        self.send_message(Message::SetOuterPosition(position));
+    }
+
+    #[inline]
+    fn set_outer_position_async(&self, position: Point) {
+        self.post_message(Message::SetOuterPosition(position));
+    }
+
+    #[inline]
+    fn set_resizable(&self, resizable: bool) {
+        self.send_message(Message::SetResizable(resizable));
+    }
+
+    #[inline]
+    fn set_resizable_async(&self, resizable: bool) {
+        self.post_message(Message::SetResizable(resizable));
+    }
+
+    #[inline]
+    fn set_title(&self, title: &str) {
+        self.send_message(Message::SetTitle(title.into()));
+    }
+
+    #[inline]
+    fn set_title_async(&self, title: &str) {
+        self.post_message(Message::SetTitle(title.into()));
+    }
+
+    #[inline]
+    fn set_visible(&self, visible: bool) {
+        self.send_message(Message::SetVisible(visible));
+    }
+
+    #[inline]
+    fn set_visible_async(&self, visible: bool) {
+        self.post_message(Message::SetVisible(visible));
+    }
+
+    fn swap_events(&mut self) {
+        let user_data = unsafe { &mut *self.user_data.get() };
+        let mut vec_lock = mutex_lock(&user_data.event_queue);
+        mem::swap(&mut self.event_buffer, vec_lock.as_mut());
+        vec_lock.clear();
+        mem::drop(vec_lock);
+    }
+}
+
+impl ops::Drop for Window {
+    fn drop(&mut self) {
+        self.post_message(Message::Destroy);
+        let _ = self.thread.take().map(thread::JoinHandle::join);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::control_char;
+
+    #[test]
+    fn control_chars() {
+        assert_eq!(control_char('c'), '\u{3}');
+        assert_eq!(control_char('C'), '\u{3}');
+        assert_eq!(control_char('@'), '\0');
+        assert_eq!(control_char(' '), '\0');
+        assert_eq!(control_char('['), '\u{1B}');
+    }
+
+    #[test]
+    fn control_leaves_other_chars_alone() {
+        assert_eq!(control_char('1'), '1');
+        assert_eq!(control_char('\u{7F}'), '\u{7F}');
+        assert_eq!(control_char('é'), 'é');
+    }
+}
@@ -0,0 +1,792 @@
+#![allow(bad_style, dead_code, clippy::upper_case_acronyms)]
+
+// Opaque types
+#[doc(hidden)]
+pub enum Display {}
+#[doc(hidden)]
+pub enum Visual {}
+#[doc(hidden)]
+pub enum _XIC {}
+#[doc(hidden)]
+pub enum _XIM {}
+
+use crate::{helpers::LazyCell, platform::posix::{dlopen_lazy, dlsym}};
+use std::mem;
+
+// Typedefs
+pub use std::os::raw::{c_char, c_int, c_long, c_short, c_uchar, c_uint, c_ulong, c_ushort, c_void};
+pub type Atom = c_ulong;
+pub type Bool = c_int;
+pub type Colormap = XID;
+pub type KeySym = XID;
+pub type Cursor = XID;
+pub type Pixmap = XID;
+pub type RRCrtc = XID;
+pub type RRMode = XID;
+pub type RROutput = XID;
+pub type Rotation = c_ushort;
+pub type Status = c_int;
+pub type Time = c_ulong;
+pub type Window = XID;
+pub type XID = c_ulong;
+pub type XIC = *mut _XIC;
+pub type XIM = *mut _XIM;
+pub type XIMFeedback = c_ulong;
+pub type XIMStyle = c_ulong;
+pub type XPointer = *mut c_char;
+pub type XVaNestedList = *mut c_void;
+
+// Function typedefs
+pub type XErrorHandler = Option<unsafe extern "C" fn(*mut Display, *mut XErrorEvent) -> c_int>;
+pub type XICProc = Option<unsafe extern "C" fn(XIC, XPointer, XPointer) -> c_int>;
+pub type XIMProc = Option<unsafe extern "C" fn(XIM, XPointer, XPointer)>;
+
+// Constants
+pub const AllocNone: c_int = 0;
+pub const ControlMask: c_uint = 1 << 2;
+pub const CopyFromParent: c_int = 0;
+pub const CurrentTime: Time = 0;
+pub const False: Bool = 0;
+pub const GrabModeAsync: c_int = 1;
+pub const GrabSuccess: c_int = 0;
+pub const InputOutput: c_uint = 1;
+pub const NotifyGrab: c_int = 1;
+pub const NotifyInferior: c_int = 2;
+pub const NotifyPointer: c_int = 5;
+pub const NotifyUngrab: c_int = 2;
+pub const PropModeReplace: c_int = 0;
+pub const QueuedAfterReading: c_int = 1;
+pub const Success: c_int = 0;
+pub const True: Bool = 1;
+pub const XA_ATOM: Atom = 4;
+pub const AnyPropertyType: Atom = 0;
+pub const XA_CARDINAL: Atom = 6;
+pub const XA_RESOURCE_MANAGER: Atom = 23;
+pub const XA_STRING: Atom = 31;
+pub const XIAllMasterDevices: c_int = 1;
+pub const XI_RawMotion: c_int = 17;
+
+// XRandR
+pub const RR_Connected: c_ushort = 0;
+pub const RR_Interlace: c_ulong = 0x00000010;
+pub const RR_DoubleScan: c_ulong = 0x00000020;
+pub const RRScreenChangeNotify: c_int = 0;
+pub const RRSetConfigSuccess: Status = 0;
+pub const RRScreenChangeNotifyMask: c_int = 1 << 0;
+
+// Cursor font glyphs, from `X11/cursorfont.h`
+pub const XC_X_cursor: c_uint = 0;
+pub const XC_crosshair: c_uint = 34;
+pub const XC_fleur: c_uint = 52;
+pub const XC_hand2: c_uint = 60;
+pub const XC_left_ptr: c_uint = 68;
+pub const XC_question_arrow: c_uint = 92;
+pub const XC_sb_h_double_arrow: c_uint = 108;
+pub const XC_sb_v_double_arrow: c_uint = 116;
+pub const XC_top_left_corner: c_uint = 134;
+pub const XC_top_right_corner: c_uint = 136;
+pub const XC_watch: c_uint = 150;
+pub const XC_xterm: c_uint = 152;
+
+// Input methods
+pub const XIMPreeditCallbacks: XIMStyle = 0x0002;
+pub const XIMPreeditNothing: XIMStyle = 0x0008;
+pub const XIMStatusNothing: XIMStyle = 0x0400;
+pub const XIMForwardChar: c_int = 0;
+pub const XIMBackwardChar: c_int = 1;
+pub const XIMLineStart: c_int = 8;
+pub const XIMLineEnd: c_int = 9;
+pub const XIMAbsolutePosition: c_int = 10;
+pub const XBufferOverflow: Status = -1;
+pub const XLookupChars: Status = 2;
+pub const XLookupBoth: Status = 4;
+pub const XNClientWindow: &[u8] = c_string!("clientWindow");
+pub const XNFilterEvents: &[u8] = c_string!("filterEvents");
+pub const XNFocusWindow: &[u8] = c_string!("focusWindow");
+pub const XNInputStyle: &[u8] = c_string!("inputStyle");
+pub const XNPreeditAttributes: &[u8] = c_string!("preeditAttributes");
+pub const XNPreeditCaretCallback: &[u8] = c_string!("preeditCaretCallback");
+pub const XNPreeditDoneCallback: &[u8] = c_string!("preeditDoneCallback");
+pub const XNPreeditDrawCallback: &[u8] = c_string!("preeditDrawCallback");
+pub const XNPreeditStartCallback: &[u8] = c_string!("preeditStartCallback");
+pub const XNQueryInputStyle: &[u8] = c_string!("queryInputStyle");
+pub const XNSpotLocation: &[u8] = c_string!("spotLocation");
+
+// Event types
+pub const KeyPress: c_int = 2;
+pub const KeyRelease: c_int = 3;
+pub const ButtonPress: c_int = 4;
+pub const ButtonRelease: c_int = 5;
+pub const FocusIn: c_int = 9;
+pub const FocusOut: c_int = 10;
+pub const DestroyNotify: c_int = 17;
+pub const UnmapNotify: c_int = 18;
+pub const MapNotify: c_int = 19;
+pub const ConfigureNotify: c_int = 22;
+pub const PropertyNotify: c_int = 28;
+pub const ClientMessage: c_int = 33;
+pub const GenericEvent: c_int = 35;
+pub const MotionNotify: c_int = 6;
+pub const EnterNotify: c_int = 7;
+pub const LeaveNotify: c_int = 8;
+
+// Event masks
+pub const NoEventMask: c_long = 0;
+pub const KeyPressMask: c_long = 1 << 0;
+pub const KeyReleaseMask: c_long = 1 << 1;
+pub const ButtonPressMask: c_long = 1 << 2;
+pub const ButtonReleaseMask: c_long = 1 << 3;
+pub const EnterWindowMask: c_long = 1 << 4;
+pub const LeaveWindowMask: c_long = 1 << 5;
+pub const PointerMotionMask: c_long = 1 << 6;
+pub const ButtonMotionMask: c_long = 1 << 13;
+pub const ExposureMask: c_long = 1 << 15;
+pub const StructureNotifyMask: c_long = 1 << 17;
+pub const SubstructureNotifyMask: c_long = 1 << 19;
+pub const SubstructureRedirectMask: c_long = 1 << 20;
+pub const FocusChangeMask: c_long = 1 << 21;
+pub const PropertyChangeMask: c_long = 1 << 22;
+
+// Window attribute masks
+pub const CWBackPixel: c_ulong = 1 << 1;
+pub const CWBorderPixel: c_ulong = 1 << 3;
+pub const CWEventMask: c_ulong = 1 << 11;
+pub const CWColormap: c_ulong = 1 << 13;
+
+// Size hint flags
+pub const PPosition: c_long = 1 << 2;
+pub const PSize: c_long = 1 << 3;
+pub const PMinSize: c_long = 1 << 4;
+pub const PMaxSize: c_long = 1 << 5;
+pub const PAspect: c_long = 1 << 7;
+
+// _MOTIF_WM_HINTS (from `MwmUtil.h`, which is not usually installed)
+pub const MWM_HINTS_FUNCTIONS: c_ulong = 1 << 0;
+pub const MWM_HINTS_DECORATIONS: c_ulong = 1 << 1;
+pub const MWM_FUNC_RESIZE: c_ulong = 1 << 1;
+pub const MWM_FUNC_MOVE: c_ulong = 1 << 2;
+pub const MWM_FUNC_MINIMIZE: c_ulong = 1 << 3;
+pub const MWM_FUNC_MAXIMIZE: c_ulong = 1 << 4;
+pub const MWM_FUNC_CLOSE: c_ulong = 1 << 5;
+pub const MWM_DECOR_BORDER: c_ulong = 1 << 1;
+pub const MWM_DECOR_RESIZEH: c_ulong = 1 << 2;
+pub const MWM_DECOR_TITLE: c_ulong = 1 << 3;
+pub const MWM_DECOR_MENU: c_ulong = 1 << 4;
+pub const MWM_DECOR_MINIMIZE: c_ulong = 1 << 5;
+pub const MWM_DECOR_MAXIMIZE: c_ulong = 1 << 6;
+
+// Structs
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct XAnyEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub window: Window,
+}
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct XClientMessageEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub window: Window,
+    pub message_type: Atom,
+    pub format: c_int,
+    pub data: [c_long; 5], // `union { char b[20]; short s[10]; long l[5]; }`
+}
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct XConfigureEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub event: Window,
+    pub window: Window,
+    pub x: c_int,
+    pub y: c_int,
+    pub width: c_int,
+    pub height: c_int,
+    pub border_width: c_int,
+    pub above: Window,
+    pub override_redirect: Bool,
+}
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct XFocusChangeEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub window: Window,
+    pub mode: c_int,
+    pub detail: c_int,
+}
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct XKeyEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub window: Window,
+    pub root: Window,
+    pub subwindow: Window,
+    pub time: Time,
+    pub x: c_int,
+    pub y: c_int,
+    pub x_root: c_int,
+    pub y_root: c_int,
+    pub state: c_uint,
+    pub keycode: c_uint,
+    pub same_screen: Bool,
+}
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct XButtonEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub window: Window,
+    pub root: Window,
+    pub subwindow: Window,
+    pub time: Time,
+    pub x: c_int,
+    pub y: c_int,
+    pub x_root: c_int,
+    pub y_root: c_int,
+    pub state: c_uint,
+    pub button: c_uint,
+    pub same_screen: Bool,
+}
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct XMotionEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub window: Window,
+    pub root: Window,
+    pub subwindow: Window,
+    pub time: Time,
+    pub x: c_int,
+    pub y: c_int,
+    pub x_root: c_int,
+    pub y_root: c_int,
+    pub state: c_uint,
+    pub is_hint: c_char,
+    pub same_screen: Bool,
+}
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct XCrossingEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub window: Window,
+    pub root: Window,
+    pub subwindow: Window,
+    pub time: Time,
+    pub x: c_int,
+    pub y: c_int,
+    pub x_root: c_int,
+    pub y_root: c_int,
+    pub mode: c_int,
+    pub detail: c_int,
+    pub same_screen: Bool,
+    pub focus: Bool,
+    pub state: c_uint,
+}
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct XGenericEventCookie {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub extension: c_int,
+    pub evtype: c_int,
+    pub cookie: c_uint,
+    pub data: *mut c_void,
+}
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct XPropertyEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub window: Window,
+    pub atom: Atom,
+    pub time: Time,
+    pub state: c_int,
+}
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct XErrorEvent {
+    pub type_: c_int,
+    pub display: *mut Display,
+    pub resourceid: XID,
+    pub serial: c_ulong,
+    pub error_code: c_uchar,
+    pub request_code: c_uchar,
+    pub minor_code: c_uchar,
+}
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub union XEvent {
+    pub type_: c_int,
+    pub any: XAnyEvent,
+    pub button: XButtonEvent,
+    pub client_message: XClientMessageEvent,
+    pub configure: XConfigureEvent,
+    pub cookie: XGenericEventCookie,
+    pub crossing: XCrossingEvent,
+    pub focus_change: XFocusChangeEvent,
+    pub key: XKeyEvent,
+    pub motion: XMotionEvent,
+    pub property: XPropertyEvent,
+    pub pad: [c_long; 24],
+}
+#[repr(C)]
+pub struct XIEventMask {
+    pub deviceid: c_int,
+    pub mask_len: c_int,
+    pub mask: *mut c_uchar,
+}
+#[repr(C)]
+pub struct XIValuatorState {
+    pub mask_len: c_int,
+    pub mask: *mut c_uchar,
+    pub values: *mut f64,
+}
+#[repr(C)]
+pub struct XIRawEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub extension: c_int,
+    pub evtype: c_int,
+    pub time: Time,
+    pub deviceid: c_int,
+    pub sourceid: c_int,
+    pub detail: c_int,
+    pub flags: c_int,
+    pub valuators: XIValuatorState,
+    pub raw_values: *mut f64,
+}
+#[repr(C)]
+pub struct XRRModeInfo {
+    pub id: RRMode,
+    pub width: c_uint,
+    pub height: c_uint,
+    pub dotClock: c_ulong,
+    pub hSyncStart: c_uint,
+    pub hSyncEnd: c_uint,
+    pub hTotal: c_uint,
+    pub hSkew: c_uint,
+    pub vSyncStart: c_uint,
+    pub vSyncEnd: c_uint,
+    pub vTotal: c_uint,
+    pub name: *mut c_char,
+    pub nameLength: c_uint,
+    pub modeFlags: c_ulong,
+}
+#[repr(C)]
+pub struct XRRScreenResources {
+    pub timestamp: Time,
+    pub configTimestamp: Time,
+    pub ncrtc: c_int,
+    pub crtcs: *mut RRCrtc,
+    pub noutput: c_int,
+    pub outputs: *mut RROutput,
+    pub nmode: c_int,
+    pub modes: *mut XRRModeInfo,
+}
+#[repr(C)]
+pub struct XRROutputInfo {
+    pub timestamp: Time,
+    pub crtc: RRCrtc,
+    pub name: *mut c_char,
+    pub nameLen: c_int,
+    pub mm_width: c_ulong,
+    pub mm_height: c_ulong,
+    pub connection: c_ushort,
+    pub subpixel_order: c_ushort,
+    pub ncrtc: c_int,
+    pub crtcs: *mut RRCrtc,
+    pub nclone: c_int,
+    pub clones: *mut RROutput,
+    pub nmode: c_int,
+    pub npreferred: c_int,
+    pub modes: *mut RRMode,
+}
+#[repr(C)]
+pub struct XRRCrtcInfo {
+    pub timestamp: Time,
+    pub x: c_int,
+    pub y: c_int,
+    pub width: c_uint,
+    pub height: c_uint,
+    pub mode: RRMode,
+    pub rotation: Rotation,
+    pub noutput: c_int,
+    pub outputs: *mut RROutput,
+    pub rotations: Rotation,
+    pub npossible: c_int,
+    pub possible: *mut RROutput,
+}
+#[repr(C)]
+pub struct XSetWindowAttributes {
+    pub background_pixmap: Pixmap,
+    pub background_pixel: c_ulong,
+    pub border_pixmap: Pixmap,
+    pub border_pixel: c_ulong,
+    pub bit_gravity: c_int,
+    pub win_gravity: c_int,
+    pub backing_store: c_int,
+    pub backing_planes: c_ulong,
+    pub backing_pixel: c_ulong,
+    pub save_under: Bool,
+    pub event_mask: c_long,
+    pub do_not_propagate_mask: c_long,
+    pub override_redirect: Bool,
+    pub colormap: Colormap,
+    pub cursor: Cursor,
+}
+#[repr(C)]
+pub struct XAspectRatio {
+    pub x: c_int,
+    pub y: c_int,
+}
+#[repr(C)]
+pub struct XSizeHints {
+    pub flags: c_long,
+    pub x: c_int,
+    pub y: c_int,
+    pub width: c_int,
+    pub height: c_int,
+    pub min_width: c_int,
+    pub min_height: c_int,
+    pub max_width: c_int,
+    pub max_height: c_int,
+    pub width_inc: c_int,
+    pub height_inc: c_int,
+    pub min_aspect: XAspectRatio,
+    pub max_aspect: XAspectRatio,
+    pub base_width: c_int,
+    pub base_height: c_int,
+    pub win_gravity: c_int,
+}
+#[repr(C)]
+pub struct XClassHint {
+    pub res_name: *mut c_char,
+    pub res_class: *mut c_char,
+}
+#[repr(C)]
+pub struct XPoint {
+    pub x: c_short,
+    pub y: c_short,
+}
+#[repr(C)]
+pub struct XIMStyles {
+    pub count_styles: c_ushort,
+    pub supported_styles: *mut XIMStyle,
+}
+#[repr(C)]
+pub struct XIMCallback {
+    pub client_data: XPointer,
+    pub callback: XIMProc,
+}
+#[repr(C)]
+pub struct XICCallback {
+    pub client_data: XPointer,
+    pub callback: XICProc,
+}
+#[repr(C)]
+pub struct XIMText {
+    pub length: c_ushort,
+    pub feedback: *mut XIMFeedback,
+    pub encoding_is_wchar: Bool,
+    pub string: *mut c_void, // `union { char *multi_byte; wchar_t *wide_char; }`
+}
+#[repr(C)]
+pub struct XIMPreeditDrawCallbackStruct {
+    pub caret: c_int,
+    pub chg_first: c_int,
+    pub chg_length: c_int,
+    pub text: *mut XIMText,
+}
+#[repr(C)]
+pub struct XIMPreeditCaretCallbackStruct {
+    pub position: c_int,
+    pub direction: c_int,
+    pub style: c_int,
+}
+// Dynamically loaded imports, see `dyn_link!`
+dyn_link! {
+    pub(crate) struct Xlib(dlopen_lazy => *mut c_void | dlsym) extern "C" static XLIB {
+        "libX11.so.6" | "libX11.so" {
+            // Display management
+            fn XInitThreads() -> Status;
+            fn XOpenDisplay(display_name: *const c_char) -> *mut Display;
+            fn XCloseDisplay(display: *mut Display) -> c_int;
+            fn XConnectionNumber(display: *mut Display) -> c_int;
+            fn XDefaultScreen(display: *mut Display) -> c_int;
+            fn XRootWindow(display: *mut Display, screen_number: c_int) -> Window;
+            fn XDefaultVisual(display: *mut Display, screen_number: c_int) -> *mut Visual;
+            fn XDefaultDepth(display: *mut Display, screen_number: c_int) -> c_int;
+            fn XBlackPixel(display: *mut Display, screen_number: c_int) -> c_ulong;
+            fn XDisplayWidth(display: *mut Display, screen_number: c_int) -> c_int;
+            fn XDisplayHeight(display: *mut Display, screen_number: c_int) -> c_int;
+            fn XFree(data: *mut c_void) -> c_int;
+
+            // Errors
+            fn XSetErrorHandler(handler: XErrorHandler) -> XErrorHandler;
+            fn XGetErrorText(display: *mut Display, code: c_int, buffer_return: *mut c_char, length: c_int) -> c_int;
+
+            // Window management
+            fn XCreateWindow(
+                display: *mut Display,
+                parent: Window,
+                x: c_int,
+                y: c_int,
+                width: c_uint,
+                height: c_uint,
+                border_width: c_uint,
+                depth: c_int,
+                class: c_uint,
+                visual: *mut Visual,
+                valuemask: c_ulong,
+                attributes: *mut XSetWindowAttributes,
+            ) -> Window;
+            fn XDestroyWindow(display: *mut Display, w: Window) -> c_int;
+            fn XIconifyWindow(display: *mut Display, w: Window, screen_number: c_int) -> Status;
+            fn XMapRaised(display: *mut Display, w: Window) -> c_int;
+            fn XMoveWindow(display: *mut Display, w: Window, x: c_int, y: c_int) -> c_int;
+            fn XMoveResizeWindow(display: *mut Display, w: Window, x: c_int, y: c_int, width: c_uint, height: c_uint) -> c_int;
+            fn XResizeWindow(display: *mut Display, w: Window, width: c_uint, height: c_uint) -> c_int;
+            fn XWithdrawWindow(display: *mut Display, w: Window, screen_number: c_int) -> Status;
+            fn XTranslateCoordinates(
+                display: *mut Display,
+                src_w: Window,
+                dest_w: Window,
+                src_x: c_int,
+                src_y: c_int,
+                dest_x_return: *mut c_int,
+                dest_y_return: *mut c_int,
+                child_return: *mut Window,
+            ) -> Bool;
+
+            // Properties
+            fn XInternAtom(display: *mut Display, atom_name: *const c_char, only_if_exists: Bool) -> Atom;
+            fn XChangeProperty(
+                display: *mut Display,
+                w: Window,
+                property: Atom,
+                type_: Atom,
+                format: c_int,
+                mode: c_int,
+                data: *const c_uchar,
+                nelements: c_int,
+            ) -> c_int;
+            fn XDeleteProperty(display: *mut Display, w: Window, property: Atom) -> c_int;
+            fn XGetWindowProperty(
+                display: *mut Display,
+                w: Window,
+                property: Atom,
+                long_offset: c_long,
+                long_length: c_long,
+                delete: Bool,
+                req_type: Atom,
+                actual_type_return: *mut Atom,
+                actual_format_return: *mut c_int,
+                nitems_return: *mut c_ulong,
+                bytes_after_return: *mut c_ulong,
+                prop_return: *mut *mut c_uchar,
+            ) -> c_int;
+            fn XSetClassHint(display: *mut Display, w: Window, class_hints: *mut XClassHint) -> c_int;
+            fn XSetWMNormalHints(display: *mut Display, w: Window, hints: *mut XSizeHints) -> ();
+            fn XSetWMProtocols(display: *mut Display, w: Window, protocols: *mut Atom, count: c_int) -> Status;
+            fn XStoreName(display: *mut Display, w: Window, window_name: *const c_char) -> c_int;
+
+            // Event loop
+            fn XFlush(display: *mut Display) -> c_int;
+            fn XNextEvent(display: *mut Display, event_return: *mut XEvent) -> c_int;
+            fn XPending(display: *mut Display) -> c_int;
+            fn XEventsQueued(display: *mut Display, mode: c_int) -> c_int;
+            fn XPeekEvent(display: *mut Display, event_return: *mut XEvent) -> c_int;
+            fn XSendEvent(
+                display: *mut Display,
+                w: Window,
+                propagate: Bool,
+                event_mask: c_long,
+                event_send: *mut XEvent,
+            ) -> Status;
+            fn XSync(display: *mut Display, discard: Bool) -> c_int;
+            fn XSelectInput(display: *mut Display, w: Window, event_mask: c_long) -> c_int;
+            fn XFilterEvent(event: *mut XEvent, w: Window) -> Bool;
+            fn XGetEventData(display: *mut Display, cookie: *mut XGenericEventCookie) -> Bool;
+            fn XFreeEventData(display: *mut Display, cookie: *mut XGenericEventCookie) -> ();
+
+            // Extensions
+            fn XQueryExtension(
+                display: *mut Display,
+                name: *const c_char,
+                major_opcode_return: *mut c_int,
+                first_event_return: *mut c_int,
+                first_error_return: *mut c_int,
+            ) -> Bool;
+
+            // Keyboard related
+            fn XLookupKeysym(key_event: *mut XKeyEvent, index: c_int) -> KeySym;
+            fn XLookupString(
+                event_struct: *mut XKeyEvent,
+                buffer_return: *mut c_char,
+                bytes_buffer: c_int,
+                keysym_return: *mut KeySym,
+                status_in_out: *mut c_void,
+            ) -> c_int;
+            fn Xutf8LookupString(
+                ic: XIC,
+                event: *mut XKeyEvent,
+                buffer_return: *mut c_char,
+                bytes_buffer: c_int,
+                keysym_return: *mut KeySym,
+                status_return: *mut Status,
+            ) -> c_int;
+            fn XkbSetDetectableAutoRepeat(display: *mut Display, detectable: Bool, supported_rtrn: *mut Bool) -> Bool;
+
+            // Input methods
+            fn XSetLocaleModifiers(modifier_list: *const c_char) -> *mut c_char;
+            fn XOpenIM(display: *mut Display, rdb: *mut c_void, res_name: *mut c_char, res_class: *mut c_char) -> XIM;
+            fn XCloseIM(im: XIM) -> Status;
+            fn XDestroyIC(ic: XIC) -> ();
+            fn XSetICFocus(ic: XIC) -> ();
+            fn XUnsetICFocus(ic: XIC) -> ();
+
+            // Pointer related
+            fn XCreateFontCursor(display: *mut Display, shape: c_uint) -> Cursor;
+            fn XDefineCursor(display: *mut Display, w: Window, cursor: Cursor) -> c_int;
+            fn XFreeCursor(display: *mut Display, cursor: Cursor) -> c_int;
+            fn XGrabPointer(
+                display: *mut Display,
+                grab_window: Window,
+                owner_events: Bool,
+                event_mask: c_uint,
+                pointer_mode: c_int,
+                keyboard_mode: c_int,
+                confine_to: Window,
+                cursor: Cursor,
+                time: Time,
+            ) -> c_int;
+            fn XUngrabPointer(display: *mut Display, time: Time) -> c_int;
+            fn XWarpPointer(
+                display: *mut Display,
+                src_w: Window,
+                dest_w: Window,
+                src_x: c_int,
+                src_y: c_int,
+                src_width: c_uint,
+                src_height: c_uint,
+                dest_x: c_int,
+                dest_y: c_int,
+            ) -> c_int;
+        },
+    }
+}
+
+dyn_link! {
+    /// Xcursor, which is optional - check the link report before using anything in here.
+    pub(crate) struct Xcursor(dlopen_lazy => *mut c_void | dlsym) extern "C" static XCURSOR {
+        "libXcursor.so.1" | "libXcursor.so" {
+            fn XcursorLibraryLoadCursor(display: *mut Display, file: *const c_char) -> Cursor;
+        },
+    }
+}
+
+dyn_link! {
+    /// XInput 2, which is optional - check the link report before using anything in here.
+    pub(crate) struct Xi(dlopen_lazy => *mut c_void | dlsym) extern "C" static XI {
+        "libXi.so.6" | "libXi.so" {
+            fn XIQueryVersion(display: *mut Display, major_version_inout: *mut c_int, minor_version_inout: *mut c_int) -> Status;
+            fn XISelectEvents(display: *mut Display, win: Window, masks: *mut XIEventMask, num_masks: c_int) -> Status;
+        },
+    }
+}
+
+dyn_link! {
+    /// XRandR, which is optional - check the link report before using anything in here.
+    pub(crate) struct Xrandr(dlopen_lazy => *mut c_void | dlsym) extern "C" static XRANDR {
+        "libXrandr.so.2" | "libXrandr.so" {
+            fn XRRQueryExtension(display: *mut Display, event_base_return: *mut c_int, error_base_return: *mut c_int) -> Bool;
+            fn XRRQueryVersion(display: *mut Display, major_version_return: *mut c_int, minor_version_return: *mut c_int) -> Status;
+            fn XRRGetScreenResourcesCurrent(display: *mut Display, window: Window) -> *mut XRRScreenResources;
+            fn XRRFreeScreenResources(resources: *mut XRRScreenResources) -> ();
+            fn XRRGetOutputInfo(display: *mut Display, resources: *mut XRRScreenResources, output: RROutput) -> *mut XRROutputInfo;
+            fn XRRFreeOutputInfo(output_info: *mut XRROutputInfo) -> ();
+            fn XRRGetCrtcInfo(display: *mut Display, resources: *mut XRRScreenResources, crtc: RRCrtc) -> *mut XRRCrtcInfo;
+            fn XRRFreeCrtcInfo(crtc_info: *mut XRRCrtcInfo) -> ();
+            fn XRRGetOutputPrimary(display: *mut Display, window: Window) -> RROutput;
+            fn XRRSetCrtcConfig(
+                display: *mut Display,
+                resources: *mut XRRScreenResources,
+                crtc: RRCrtc,
+                timestamp: Time,
+                x: c_int,
+                y: c_int,
+                mode: RRMode,
+                rotation: Rotation,
+                outputs: *mut RROutput,
+                noutputs: c_int,
+            ) -> Status;
+            fn XRRSelectInput(display: *mut Display, window: Window, mask: c_int) -> ();
+            fn XRRUpdateConfiguration(event: *mut XEvent) -> c_int;
+        },
+    }
+}
+
+/// Imports that are variadic in C, which `dyn_link!` has no way of wrapping.
+///
+/// Argument lists are name & value pairs terminated by a null name, as with `XCreateIC(im, name, value, ..., NULL)`.
+pub(crate) struct XlibVariadic {
+    pub XCreateIC: unsafe extern "C" fn(im: XIM, ...) -> XIC,
+    pub XGetICValues: unsafe extern "C" fn(ic: XIC, ...) -> *mut c_char,
+    pub XGetIMValues: unsafe extern "C" fn(im: XIM, ...) -> *mut c_char,
+    pub XSetICValues: unsafe extern "C" fn(ic: XIC, ...) -> *mut c_char,
+    pub XVaCreateNestedList: unsafe extern "C" fn(unused: c_int, ...) -> XVaNestedList,
+}
+
+impl XlibVariadic {
+    unsafe fn link() -> Option<Self> {
+        let handle = [c_string!("libX11.so.6"), c_string!("libX11.so")]
+            .iter()
+            .map(|name| dlopen_lazy(name.as_ptr().cast()))
+            .find(|handle| !handle.is_null())?;
+        macro_rules! sym {
+            ($name:ident: $ty:ty) => {{
+                let f = dlsym(handle, c_string!(stringify!($name)).as_ptr().cast());
+                if f.is_null() {
+                    return None
+                }
+                mem::transmute::<*mut c_void, $ty>(f)
+            }};
+        }
+        Some(Self {
+            XCreateIC: sym!(XCreateIC: unsafe extern "C" fn(XIM, ...) -> XIC),
+            XGetICValues: sym!(XGetICValues: unsafe extern "C" fn(XIC, ...) -> *mut c_char),
+            XGetIMValues: sym!(XGetIMValues: unsafe extern "C" fn(XIM, ...) -> *mut c_char),
+            XSetICValues: sym!(XSetICValues: unsafe extern "C" fn(XIC, ...) -> *mut c_char),
+            XVaCreateNestedList: sym!(XVaCreateNestedList: unsafe extern "C" fn(c_int, ...) -> XVaNestedList),
+        })
+    }
+}
+
+pub(crate) static XLIB_VARIADIC: LazyCell<Option<XlibVariadic>> = LazyCell::new(|| unsafe { XlibVariadic::link() });
@@ -0,0 +1,751 @@
+use super::api::*;
+use crate::{
+    monitor::{Monitor, Point, Scale, Size, VideoMode},
+    platform::{posix::str_to_c_null, Backend},
+    window::{CursorIcon, SizeLimits, WindowStyle},
+};
+use std::{cell::Cell, mem, ptr, slice, sync::Once};
+
+#[cfg(feature = "cursor-lock")]
+use crate::window::CursorLock;
+
+/// The base DPI at 100% scaling
+pub const BASE_DPI: c_uint = 96;
+
+/// Guards process-wide Xlib initialization (threading support & error handler)
+static XLIB_INIT: Once = Once::new();
+
+thread_local! {
+    /// The last error code reported to this thread's Xlib error handler, if any.
+    static LAST_ERROR: Cell<Option<c_uchar>> = const { Cell::new(None) };
+}
+
+/// Initializes Xlib for use from multiple threads. Must be called before the first `XOpenDisplay`.
+pub fn init_xlib() {
+    XLIB_INIT.call_once(|| unsafe {
+        let _ = XInitThreads();
+
+        // The default error handler prints the error and exits the process, which is a bit much.
+        let _ = XSetErrorHandler(Some(error_handler));
+
+        // Reads `$XMODIFIERS`, which is how the input method server is picked
+        let _ = XSetLocaleModifiers(c_string!("").as_ptr().cast());
+    });
+}
+
+unsafe extern "C" fn error_handler(_display: *mut Display, event: *mut XErrorEvent) -> c_int {
+    // Xlib calls this on the thread that read the error, which is the one owning the connection
+    let code = (*event).error_code;
+    LAST_ERROR.with(|x| x.set(Some(code)));
+    0
+}
+
+/// Takes the last error code reported on this thread, if there is one.
+/// Call `XSync` beforehand to make sure every request has actually been processed.
+pub fn take_last_error() -> Option<c_uchar> {
+    LAST_ERROR.with(|x| x.take())
+}
+
+pub unsafe fn error_string_repr(display: *mut Display, code: c_int) -> String {
+    let mut buffer = [0 as c_char; 256];
+    let _ = XGetErrorText(display, code, buffer.as_mut_ptr(), buffer.len() as c_int);
+    let bytes = &*(&buffer[..] as *const [c_char] as *const [u8]);
+    let len = bytes.iter().position(|&b| b == 0x00).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..len]).into_owned()
+}
+
+/// Atoms interned once per connection.
+#[derive(Copy, Clone)]
+pub struct Atoms {
+    pub motif_wm_hints: Atom,
+    pub net_current_desktop: Atom,
+    pub net_frame_extents: Atom,
+    pub net_wm_name: Atom,
+    pub net_wm_ping: Atom,
+    pub net_wm_state: Atom,
+    pub net_wm_state_fullscreen: Atom,
+    pub net_wm_state_hidden: Atom,
+    pub net_wm_state_maximized_horz: Atom,
+    pub net_wm_state_maximized_vert: Atom,
+    pub net_workarea: Atom,
+    pub utf8_string: Atom,
+    pub wm_delete_window: Atom,
+    pub wm_protocols: Atom,
+}
+
+impl Atoms {
+    pub unsafe fn new(display: *mut Display) -> Self {
+        let intern = |name: &[u8]| XInternAtom(display, name.as_ptr().cast(), False);
+        Self {
+            motif_wm_hints: intern(c_string!("_MOTIF_WM_HINTS")),
+            net_current_desktop: intern(c_string!("_NET_CURRENT_DESKTOP")),
+            net_frame_extents: intern(c_string!("_NET_FRAME_EXTENTS")),
+            net_wm_name: intern(c_string!("_NET_WM_NAME")),
+            net_wm_ping: intern(c_string!("_NET_WM_PING")),
+            net_wm_state: intern(c_string!("_NET_WM_STATE")),
+            net_wm_state_fullscreen: intern(c_string!("_NET_WM_STATE_FULLSCREEN")),
+            net_wm_state_hidden: intern(c_string!("_NET_WM_STATE_HIDDEN")),
+            net_wm_state_maximized_horz: intern(c_string!("_NET_WM_STATE_MAXIMIZED_HORZ")),
+            net_wm_state_maximized_vert: intern(c_string!("_NET_WM_STATE_MAXIMIZED_VERT")),
+            net_workarea: intern(c_string!("_NET_WORKAREA")),
+            utf8_string: intern(c_string!("UTF8_STRING")),
+            wm_delete_window: intern(c_string!("WM_DELETE_WINDOW")),
+            wm_protocols: intern(c_string!("WM_PROTOCOLS")),
+        }
+    }
+}
+
+/// Sets both the legacy `WM_NAME` and the UTF-8 `_NET_WM_NAME` properties.
+pub unsafe fn set_title(display: *mut Display, window: Window, atoms: &Atoms, title: &str) {
+    let mut buffer = Vec::new();
+    let title_ptr = str_to_c_null(title, &mut buffer);
+    let _ = XStoreName(display, window, title_ptr);
+    let _ = XChangeProperty(
+        display,
+        window,
+        atoms.net_wm_name,
+        atoms.utf8_string,
+        8,
+        PropModeReplace,
+        title_ptr.cast(),
+        (buffer.len() - 1) as c_int,
+    );
+}
+
+/// Loads the cursor for an icon from the user's cursor theme, or the core cursor font if that's unavailable.
+/// The returned cursor has to be freed with `XFreeCursor` once it's not defined on any window.
+pub unsafe fn load_cursor(display: *mut Display, icon: CursorIcon) -> Cursor {
+    // The CSS names come first, then the legacy names older themes still go by
+    let (names, glyph): (&[&[u8]], c_uint) = match icon {
+        CursorIcon::Arrow => (&[c_string!("default"), c_string!("left_ptr")], XC_left_ptr),
+        CursorIcon::Text => (&[c_string!("text"), c_string!("xterm")], XC_xterm),
+        CursorIcon::Hand => (&[c_string!("pointer"), c_string!("hand2")], XC_hand2),
+        CursorIcon::Crosshair => (&[c_string!("crosshair")], XC_crosshair),
+        CursorIcon::Wait => (&[c_string!("wait"), c_string!("watch")], XC_watch),
+        CursorIcon::Progress => (&[c_string!("progress"), c_string!("left_ptr_watch")], XC_watch),
+        CursorIcon::NotAllowed => (&[c_string!("not-allowed"), c_string!("crossed_circle")], XC_X_cursor),
+        CursorIcon::Help => (&[c_string!("help"), c_string!("question_arrow")], XC_question_arrow),
+        CursorIcon::Move => (&[c_string!("move"), c_string!("fleur")], XC_fleur),
+        CursorIcon::ResizeHorizontal => (&[c_string!("ew-resize"), c_string!("sb_h_double_arrow")], XC_sb_h_double_arrow),
+        CursorIcon::ResizeVertical => (&[c_string!("ns-resize"), c_string!("sb_v_double_arrow")], XC_sb_v_double_arrow),
+        CursorIcon::ResizeNeSw => (&[c_string!("nesw-resize"), c_string!("fd_double_arrow")], XC_top_right_corner),
+        CursorIcon::ResizeNwSe => (&[c_string!("nwse-resize"), c_string!("bd_double_arrow")], XC_top_left_corner),
+    };
+    if XCURSOR.link_report().is_complete() {
+        for name in names {
+            let cursor = XcursorLibraryLoadCursor(display, name.as_ptr().cast());
+            if cursor != 0 {
+                return cursor
+            }
+        }
+    }
+    XCreateFontCursor(display, glyph)
+}
+
+/// There's no standard way to set the window controls & border on X11,
+/// but practically every window manager respects the Motif hints, so they're used for both.
+pub unsafe fn set_motif_hints(display: *mut Display, window: Window, atoms: &Atoms, style: &WindowStyle) {
+    let mut functions = MWM_FUNC_MOVE;
+    let mut decorations = 0;
+
+    if !style.borderless {
+        decorations |= MWM_DECOR_BORDER | MWM_DECOR_TITLE;
+    }
+
+    if style.resizable {
+        functions |= MWM_FUNC_RESIZE;
+        if !style.borderless {
+            decorations |= MWM_DECOR_RESIZEH;
+        }
+    }
+
+    if let Some(controls) = &style.controls {
+        if controls.minimize {
+            functions |= MWM_FUNC_MINIMIZE;
+            decorations |= MWM_DECOR_MINIMIZE;
+        }
+        if controls.maximize {
+            functions |= MWM_FUNC_MAXIMIZE;
+            decorations |= MWM_DECOR_MAXIMIZE;
+        }
+        if controls.close {
+            functions |= MWM_FUNC_CLOSE;
+        }
+        decorations |= MWM_DECOR_MENU;
+    }
+
+    if style.borderless {
+        decorations = 0;
+    }
+
+    // flags, functions, decorations, input_mode, status
+    let hints: [c_ulong; 5] = [MWM_HINTS_FUNCTIONS | MWM_HINTS_DECORATIONS, functions, decorations, 0, 0];
+    let _ = XChangeProperty(
+        display,
+        window,
+        atoms.motif_wm_hints,
+        atoms.motif_wm_hints,
+        32,
+        PropModeReplace,
+        hints.as_ptr().cast(),
+        hints.len() as c_int,
+    );
+}
+
+/// Updates `WM_NORMAL_HINTS`, which is the closest thing to a "resizable" flag on X11, and holds the size limits.
+/// A position is only worth passing before mapping, so the window manager doesn't place it somewhere else.
+pub unsafe fn set_size_hints(
+    display: *mut Display,
+    window: Window,
+    resizable: bool,
+    client: (u32, u32),
+    limits: &SizeLimits,
+    scale: Scale,
+    position: Option<(c_int, c_int)>,
+) {
+    let mut hints: XSizeHints = mem::zeroed();
+    if let Some((x, y)) = position {
+        hints.flags |= PPosition;
+        hints.x = x;
+        hints.y = y;
+    }
+    if !resizable {
+        let (width, height) = (client.0 as c_int, client.1 as c_int);
+        hints.flags |= PMinSize | PMaxSize;
+        hints.min_width = width;
+        hints.min_height = height;
+        hints.max_width = width;
+        hints.max_height = height;
+    } else {
+        if let Some((width, height)) = limits.min.map(|size| size.physical(scale)) {
+            hints.flags |= PMinSize;
+            hints.min_width = width as c_int;
+            hints.min_height = height as c_int;
+        }
+        if let Some((width, height)) = limits.max.map(|size| size.physical(scale)) {
+            hints.flags |= PMaxSize;
+            hints.max_width = width as c_int;
+            hints.max_height = height as c_int;
+        }
+        if let Some((x, y)) = limits.aspect_ratio {
+            hints.flags |= PAspect;
+            hints.min_aspect = XAspectRatio { x: x as c_int, y: y as c_int };
+            hints.max_aspect = XAspectRatio { x: x as c_int, y: y as c_int };
+        }
+    }
+    XSetWMNormalHints(display, window, &mut hints);
+}
+
+/// Adds or removes up to two `_NET_WM_STATE` atoms, the second being 0 if there's only one.
+/// Mapped windows have to ask the window manager for it, while unmapped ones set the property it reads once they're mapped.
+pub unsafe fn set_wm_state(
+    display: *mut Display,
+    window: Window,
+    root: Window,
+    atoms: &Atoms,
+    mapped: bool,
+    state: [Atom; 2],
+    enabled: bool,
+) {
+    if mapped {
+        let mut event = XEvent {
+            client_message: XClientMessageEvent {
+                type_: ClientMessage,
+                serial: 0,
+                send_event: True,
+                display,
+                window,
+                message_type: atoms.net_wm_state,
+                format: 32,
+                // `_NET_WM_STATE_ADD` is 1 & `_NET_WM_STATE_REMOVE` is 0, then the property, and "from an application"
+                data: [enabled as c_long, state[0] as c_long, state[1] as c_long, 1, 0],
+            },
+        };
+        let _ = XSendEvent(display, root, False, SubstructureNotifyMask | SubstructureRedirectMask, &mut event);
+        return
+    }
+
+    let mut current = get_atoms(display, window, atoms.net_wm_state, 64);
+    current.retain(|atom| !state.contains(atom));
+    if enabled {
+        current.extend(state.iter().filter(|&&atom| atom != 0));
+    }
+    if current.is_empty() {
+        let _ = XDeleteProperty(display, window, atoms.net_wm_state);
+    } else {
+        let (data, len) = (current.as_ptr().cast(), current.len() as c_int);
+        let _ = XChangeProperty(display, window, atoms.net_wm_state, XA_ATOM, 32, PropModeReplace, data, len);
+    }
+}
+
+/// Gets whether a window is maximized and minimized, going by its `_NET_WM_STATE`.
+pub unsafe fn wm_state(display: *mut Display, window: Window, atoms: &Atoms) -> (bool, bool) {
+    let state = get_atoms(display, window, atoms.net_wm_state, 64);
+    let maximized =
+        state.contains(&atoms.net_wm_state_maximized_horz) && state.contains(&atoms.net_wm_state_maximized_vert);
+    (maximized, state.contains(&atoms.net_wm_state_hidden))
+}
+
+pub unsafe fn set_class_hint(display: *mut Display, window: Window, class_name: &str) {
+    let mut buffer = Vec::new();
+    let name = str_to_c_null(class_name, &mut buffer) as *mut c_char;
+    let mut hint = XClassHint { res_name: name, res_class: name };
+    let _ = XSetClassHint(display, window, &mut hint);
+}
+
+/// Selects raw motion events from every mouse, returning the XInput 2 opcode they're sent with.
+/// These are sent to the root window, so they have to be filtered by focus.
+pub unsafe fn select_raw_motion(display: *mut Display, root: Window) -> Option<c_int> {
+    if !XI.link_report().is_complete() {
+        return None
+    }
+    let (mut opcode, mut first_event, mut first_error) = (0, 0, 0);
+    let name = c_string!("XInputExtension");
+    if XQueryExtension(display, name.as_ptr().cast(), &mut opcode, &mut first_event, &mut first_error) == False {
+        return None
+    }
+    let (mut major, mut minor) = (2, 0);
+    if XIQueryVersion(display, &mut major, &mut minor) != Success {
+        return None
+    }
+    let mut mask = [0u8; 4];
+    mask[(XI_RawMotion >> 3) as usize] |= 1 << (XI_RawMotion & 7);
+    let mut event_mask = XIEventMask {
+        deviceid: XIAllMasterDevices,
+        mask_len: mask.len() as c_int,
+        mask: mask.as_mut_ptr(),
+    };
+    if XISelectEvents(display, root, &mut event_mask, 1) != Success {
+        return None
+    }
+    Some(opcode)
+}
+
+/// Gets the X and Y motion out of a raw event, which are the first two valuators on a mouse.
+pub unsafe fn raw_motion_delta(event: &XIRawEvent) -> (f64, f64) {
+    if event.valuators.mask.is_null() {
+        return (0.0, 0.0)
+    }
+    let mask = slice::from_raw_parts(event.valuators.mask, event.valuators.mask_len as usize);
+
+    // Only the valuators set in the mask have a value, in order
+    let mut values = event.raw_values;
+    let mut delta = [0.0; 2];
+    for (axis, out) in delta.iter_mut().enumerate() {
+        if matches!(mask.get(axis / 8), Some(byte) if byte & (1 << (axis % 8)) != 0) {
+            *out = *values;
+            values = values.add(1);
+        }
+    }
+    (delta[0], delta[1])
+}
+
+/// Gets the position of the window on the root window, including the frame the window manager put around it.
+pub unsafe fn outer_position(display: *mut Display, window: Window, root: Window, atoms: &Atoms) -> (c_int, c_int) {
+    // Window managers reparent windows into their frames, so the position we're told about is within that
+    let (mut x, mut y, mut child) = (0, 0, 0);
+    let _ = XTranslateCoordinates(display, window, root, 0, 0, &mut x, &mut y, &mut child);
+
+    // Left, right, top & bottom
+    if let [left, _, top, _] = get_cardinals(display, window, atoms.net_frame_extents, 4)[..] {
+        x -= left as c_int;
+        y -= top as c_int;
+    }
+    (x, y)
+}
+
+/// Reads a `CARDINAL` array property, which is empty if it isn't set.
+unsafe fn get_cardinals(display: *mut Display, window: Window, property: Atom, max_len: c_long) -> Vec<c_long> {
+    get_longs(display, window, property, XA_CARDINAL, max_len)
+}
+
+/// Reads an `ATOM` array property, which is empty if it isn't set.
+unsafe fn get_atoms(display: *mut Display, window: Window, property: Atom, max_len: c_long) -> Vec<Atom> {
+    get_longs(display, window, property, XA_ATOM, max_len).into_iter().map(|atom| atom as Atom).collect()
+}
+
+/// Reads an array property with a format of 32 and the given type, which is empty if it isn't set.
+unsafe fn get_longs(display: *mut Display, window: Window, property: Atom, kind: Atom, max_len: c_long) -> Vec<c_long> {
+    let mut type_ = 0;
+    let mut format = 0;
+    let mut count = 0;
+    let mut bytes_after = 0;
+    let mut data: *mut c_uchar = ptr::null_mut();
+    let status = XGetWindowProperty(
+        display,
+        window,
+        property,
+        0,
+        max_len,
+        False,
+        kind,
+        &mut type_,
+        &mut format,
+        &mut count,
+        &mut bytes_after,
+        &mut data,
+    );
+    let mut values = Vec::new();
+    if !data.is_null() {
+        if status == Success && type_ == kind && format == 32 {
+            // Format 32 is always `long` sized, regardless of what the name says
+            values.extend_from_slice(slice::from_raw_parts(data as *const c_long, count as usize));
+        }
+        let _ = XFree(data.cast());
+    }
+    values
+}
+
+/// Makes a slice out of a pointer & length pair from Xlib, which are null & zero when empty.
+unsafe fn raw_slice<'a, T>(data: *const T, len: c_int) -> &'a [T] {
+    if data.is_null() || len <= 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(data, len as usize)
+    }
+}
+
+/// Checks for XRandR 1.3, which is the first version with `XRRGetScreenResourcesCurrent`, returning its event base.
+unsafe fn randr_event_base(display: *mut Display) -> Option<c_int> {
+    if !XRANDR.link_report().is_complete() {
+        return None
+    }
+    let (mut event_base, mut error_base) = (0, 0);
+    if XRRQueryExtension(display, &mut event_base, &mut error_base) == False {
+        return None
+    }
+    let (mut major, mut minor) = (0, 0);
+    if XRRQueryVersion(display, &mut major, &mut minor) != 0 && (major, minor) >= (1, 3) {
+        Some(event_base)
+    } else {
+        None
+    }
+}
+
+/// Selects screen change events, returning the event type they're sent with.
+/// These are sent when monitors are plugged in, removed or reconfigured.
+pub unsafe fn select_screen_change(display: *mut Display, root: Window) -> Option<c_int> {
+    let event_base = randr_event_base(display)?;
+    XRRSelectInput(display, root, RRScreenChangeNotifyMask);
+    Some(event_base + RRScreenChangeNotify)
+}
+
+/// Calculates the refresh rate of a mode in hertz, which RandR leaves up to us.
+fn refresh_rate(mode: &XRRModeInfo) -> Option<f64> {
+    let mut lines = f64::from(mode.vTotal);
+    if mode.modeFlags & RR_DoubleScan != 0 {
+        lines *= 2.0;
+    }
+    if mode.modeFlags & RR_Interlace != 0 {
+        lines /= 2.0;
+    }
+    let dots = f64::from(mode.hTotal) * lines;
+    (dots > 0.0 && mode.dotClock != 0).then(|| mode.dotClock as f64 / dots)
+}
+
+/// Gets every monitor that's turned on, or the whole screen as one monitor if XRandR isn't available.
+pub unsafe fn monitors(display: *mut Display, screen: c_int, root: Window, atoms: &Atoms) -> Vec<Monitor> {
+    // Neither of these are per-monitor on X11
+    let scale = scale_factor(display, root);
+    let desktop = get_cardinals(display, root, atoms.net_current_desktop, 1).first().copied().unwrap_or(0);
+    let work_area = get_cardinals(display, root, atoms.net_workarea, c_long::from(i16::MAX));
+    let work_area = work_area.chunks_exact(4).nth(desktop as usize).map(|area| {
+        (area[0] as c_int, area[1] as c_int, area[0] as c_int + area[2] as c_int, area[1] as c_int + area[3] as c_int)
+    });
+
+    let make_monitor = |id: u64, name: String, is_primary: bool, bounds: (c_int, c_int, c_uint, c_uint), refresh_rate| {
+        let (x, y, width, height) = bounds;
+
+        // The work area covers the whole desktop, so it's cut down to each monitor
+        let (right, bottom) = (x + width as c_int, y + height as c_int);
+        let work_area = work_area
+            .map(|(left, top, r, b)| (left.max(x), top.max(y), r.min(right), b.min(bottom)))
+            .filter(|&(left, top, right, bottom)| left < right && top < bottom)
+            .map(|(left, top, right, bottom)| {
+                (Point::Physical(left, top), Size::Physical((right - left) as u32, (bottom - top) as u32))
+            })
+            .unwrap_or((Point::Physical(x, y), Size::Physical(width, height)));
+        Monitor {
+            backend: Backend::X11,
+            id,
+            name,
+            is_primary,
+            position: Point::Physical(x, y),
+            size: Size::Physical(width, height),
+            work_area,
+            scale,
+            refresh_rate,
+        }
+    };
+
+    let mut monitors = Vec::new();
+    if randr_event_base(display).is_some() {
+        let resources = XRRGetScreenResourcesCurrent(display, root);
+        if !resources.is_null() {
+            let primary = XRRGetOutputPrimary(display, root);
+            let modes = raw_slice((*resources).modes, (*resources).nmode);
+            for &output in raw_slice((*resources).outputs, (*resources).noutput) {
+                let info = XRRGetOutputInfo(display, resources, output);
+                if info.is_null() {
+                    continue
+                }
+
+                // Outputs without a CRTC are turned off
+                if (*info).connection == RR_Connected && (*info).crtc != 0 {
+                    let crtc = XRRGetCrtcInfo(display, resources, (*info).crtc);
+                    if !crtc.is_null() {
+                        let name = raw_slice((*info).name as *const u8, (*info).nameLen);
+                        let refresh_rate = modes.iter().find(|mode| mode.id == (*crtc).mode).and_then(refresh_rate);
+                        monitors.push(make_monitor(
+                            output,
+                            String::from_utf8_lossy(name).into_owned(),
+                            output == primary,
+                            ((*crtc).x, (*crtc).y, (*crtc).width, (*crtc).height),
+                            refresh_rate,
+                        ));
+                        XRRFreeCrtcInfo(crtc);
+                    }
+                }
+                XRRFreeOutputInfo(info);
+            }
+            XRRFreeScreenResources(resources);
+        }
+    }
+
+    // RandR being there but having no outputs happens with some virtual servers
+    if monitors.is_empty() {
+        let (width, height) = (XDisplayWidth(display, screen), XDisplayHeight(display, screen));
+        let bounds = (0, 0, width.max(0) as c_uint, height.max(0) as c_uint);
+        monitors.push(make_monitor(0, String::from("Screen"), true, bounds, None));
+    }
+    monitors
+}
+
+/// Gets every video mode a monitor's output supports, which needs XRandR.
+pub unsafe fn video_modes(display: *mut Display, screen: c_int, root: Window, monitor: &Monitor) -> Vec<VideoMode> {
+    let mut video_modes = Vec::new();
+
+    // The fallback monitor isn't an output, see `monitors`
+    if monitor.id == 0 || randr_event_base(display).is_none() {
+        return video_modes
+    }
+    let resources = XRRGetScreenResourcesCurrent(display, root);
+    if resources.is_null() {
+        return video_modes
+    }
+    let output = XRRGetOutputInfo(display, resources, monitor.id);
+    if !output.is_null() {
+        // The depth belongs to the screen, every mode of every output has the same one
+        let bit_depth = XDefaultDepth(display, screen) as u16;
+        let modes = raw_slice((*resources).modes, (*resources).nmode);
+        for &id in raw_slice((*output).modes, (*output).nmode) {
+            // Modes without timings are bogus, and aren't worth switching to
+            let mode = modes.iter().find(|mode| mode.id == id);
+            if let Some((mode, rate)) = mode.and_then(|mode| refresh_rate(mode).map(|rate| (mode, rate))) {
+                video_modes.push(VideoMode {
+                    monitor: monitor.clone(),
+                    id,
+                    size: (mode.width, mode.height),
+                    bit_depth,
+                    refresh_rate: rate,
+                });
+            }
+        }
+        XRRFreeOutputInfo(output);
+    }
+    XRRFreeScreenResources(resources);
+    video_modes
+}
+
+/// Switches the CRTC of a video mode's output to it,
+/// returning the CRTC along with the mode it had to switch it back with `reset_video_mode`.
+pub unsafe fn set_video_mode(display: *mut Display, root: Window, video_mode: &VideoMode) -> Option<(RRCrtc, RRMode)> {
+    if video_mode.monitor.id == 0 || randr_event_base(display).is_none() {
+        return None
+    }
+    let resources = XRRGetScreenResourcesCurrent(display, root);
+    if resources.is_null() {
+        return None
+    }
+    let mut result = None;
+    let output = XRRGetOutputInfo(display, resources, video_mode.monitor.id);
+    if !output.is_null() {
+        let crtc = (*output).crtc;
+        if crtc != 0 {
+            match set_crtc_mode(display, resources, crtc, video_mode.id) {
+                Some(mode) if mode != video_mode.id => result = Some((crtc, mode)),
+                _ => (),
+            }
+        }
+        XRRFreeOutputInfo(output);
+    }
+    XRRFreeScreenResources(resources);
+    result
+}
+
+/// Switches a CRTC back to the mode it had before `set_video_mode`.
+pub unsafe fn reset_video_mode(display: *mut Display, root: Window, crtc: RRCrtc, mode: RRMode) {
+    let resources = XRRGetScreenResourcesCurrent(display, root);
+    if !resources.is_null() {
+        let _ = set_crtc_mode(display, resources, crtc, mode);
+        XRRFreeScreenResources(resources);
+    }
+}
+
+/// Sets the mode of a CRTC, keeping its position, rotation and outputs.
+/// Returns the mode it had before, or `None` if it couldn't be set.
+unsafe fn set_crtc_mode(
+    display: *mut Display,
+    resources: *mut XRRScreenResources,
+    crtc: RRCrtc,
+    mode: RRMode,
+) -> Option<RRMode> {
+    let info = XRRGetCrtcInfo(display, resources, crtc);
+    if info.is_null() {
+        return None
+    }
+    let old_mode = (*info).mode;
+    let status = if old_mode == mode {
+        RRSetConfigSuccess
+    } else {
+        let info = &*info;
+        XRRSetCrtcConfig(display, resources, crtc, CurrentTime, info.x, info.y, mode, info.rotation, info.outputs, info.noutput)
+    };
+    XRRFreeCrtcInfo(info);
+    if status == RRSetConfigSuccess { Some(old_mode) } else { None }
+}
+
+/// Gets the scale factor from `Xft.dpi` in the resource database, which is what desktop environments set.
+/// The property is read directly as `XResourceManagerString` is only fetched once, when connecting.
+pub unsafe fn scale_factor(display: *mut Display, root: Window) -> f64 {
+    let mut type_ = 0;
+    let mut format = 0;
+    let mut count = 0;
+    let mut bytes_after = 0;
+    let mut data: *mut c_uchar = ptr::null_mut();
+    let status = XGetWindowProperty(
+        display,
+        root,
+        XA_RESOURCE_MANAGER,
+        0,
+        c_long::from(i16::MAX), // in 32-bit units, anything bigger is absurd
+        False,
+        XA_STRING,
+        &mut type_,
+        &mut format,
+        &mut count,
+        &mut bytes_after,
+        &mut data,
+    );
+    let mut dpi = None;
+    if !data.is_null() {
+        if status == Success && type_ == XA_STRING && format == 8 {
+            dpi = parse_xft_dpi(slice::from_raw_parts(data, count as usize));
+        }
+        let _ = XFree(data.cast());
+    }
+    dpi.map(|dpi| dpi / f64::from(BASE_DPI)).unwrap_or(1.0)
+}
+
+/// Finds the `Xft.dpi` resource, which is stored as lines of `name:\tvalue`.
+fn parse_xft_dpi(resources: &[u8]) -> Option<f64> {
+    resources
+        .split(|&b| b == b'\n')
+        .filter_map(|line| line.strip_prefix(b"Xft.dpi:"))
+        .filter_map(|value| std::str::from_utf8(value).ok()?.trim().parse::<f64>().ok())
+        .find(|&dpi| dpi > 0.0)
+}
+
+/// Replies to a `_NET_WM_PING` from the window manager, which uses it to detect unresponsive windows.
+pub unsafe fn reply_ping(display: *mut Display, root: Window, event: &XClientMessageEvent) {
+    let mut reply = XEvent { client_message: *event };
+    reply.client_message.window = root;
+    let _ = XSendEvent(
+        display,
+        root,
+        False,
+        SubstructureNotifyMask | SubstructureRedirectMask,
+        &mut reply,
+    );
+}
+
+/// Applies a cursor lock mode. Returns `false` if the pointer couldn't be grabbed,
+/// which happens if another client (usually the window manager) currently holds a grab.
+#[cfg(feature = "cursor-lock")]
+pub unsafe fn update_cursor_lock(
+    display: *mut Display,
+    window: Window,
+    kind: Option<CursorLock>,
+    client: (u32, u32),
+    new: bool,
+) -> bool {
+    match kind {
+        Some(mode) => {
+            // Confining the pointer to the window is only possible with an active grab,
+            // which also keeps fast movements from escaping the window in center mode.
+            let grab = XGrabPointer(
+                display,
+                window,
+                True,
+                (PointerMotionMask | ButtonMotionMask) as c_uint,
+                GrabModeAsync,
+                GrabModeAsync,
+                window,
+                0, // None
+                CurrentTime,
+            );
+            if let CursorLock::Center = mode {
+                let (width, height) = (client.0 as c_int, client.1 as c_int);
+                let _ = XWarpPointer(display, 0, window, 0, 0, 0, 0, width / 2, height / 2);
+            }
+            grab == GrabSuccess
+        },
+        None if new => {
+            let _ = XUngrabPointer(display, CurrentTime);
+            true
+        },
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_xft_dpi;
+
+    #[test]
+    fn xft_dpi() {
+        assert_eq!(parse_xft_dpi(b"Xft.dpi:\t144\n"), Some(144.0));
+        assert_eq!(parse_xft_dpi(b"Xcursor.size:\t24\nXft.dpi:\t96\nXft.hinting:\t1\n"), Some(96.0));
+    }
+
+    #[test]
+    fn xft_dpi_missing() {
+        assert_eq!(parse_xft_dpi(b""), None);
+        assert_eq!(parse_xft_dpi(b"Xcursor.size:\t24\nXft.hinting:\t1\n"), None);
+    }
+
+    #[test]
+    fn xft_dpi_not_a_number() {
+        assert_eq!(parse_xft_dpi(b"Xft.dpi:\tlarge\n"), None);
+        assert_eq!(parse_xft_dpi(b"Xft.dpi:\t\n"), None);
+        assert_eq!(parse_xft_dpi(b"Xft.dpi:\t-96\n"), None);
+    }
+
+    #[test]
+    fn xft_dpi_whitespace() {
+        assert_eq!(parse_xft_dpi(b"Xft.dpi:   120  \n"), Some(120.0));
+        assert_eq!(parse_xft_dpi(b"Xft.dpi:\t120\r\n"), Some(120.0));
+        assert_eq!(parse_xft_dpi(b"Xft.dpi:120"), Some(120.0));
+    }
+
+    #[test]
+    fn xft_dpi_fractional() {
+        assert_eq!(parse_xft_dpi(b"Xft.dpi:\t122.5\n"), Some(122.5));
+    }
+
+    #[test]
+    fn xft_dpi_similar_key() {
+        assert_eq!(parse_xft_dpi(b"Xft.dpiX:\t192\n"), None);
+        assert_eq!(parse_xft_dpi(b"Xft.dpiX:\t192\nXft.dpi:\t144\n"), Some(144.0));
+    }
+}
@@ -4,11 +4,70 @@ use crate::{
     error::Error,
     event::Event,
     helpers::MaybeStatic,
-    monitor::{Scale, Size},
-    platform::imp,
+    monitor::{Monitor, Point, Scale, Size, VideoMode},
+    platform::{imp, Backend},
 };
 use std::borrow::Cow;
 
+/// The standard cursor shape to show over a window, for [`cursor_icon`] / [`set_cursor_icon`].
+///
+/// Each backend picks the closest match it has, which can look a little different between platforms.
+///
+/// [`cursor_icon`]: WindowBuilder::cursor_icon
+/// [`set_cursor_icon`]: Window::set_cursor_icon
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum CursorIcon {
+    /// The normal arrow pointer.
+    Arrow,
+
+    /// An I-beam, for selecting text.
+    Text,
+
+    /// A pointing hand, for links and the like.
+    Hand,
+
+    /// A crosshair, for precise selection.
+    Crosshair,
+
+    /// The application is busy and can't be interacted with.
+    Wait,
+
+    /// The application is busy, but can still be interacted with.
+    Progress,
+
+    /// The action under the cursor isn't allowed.
+    NotAllowed,
+
+    /// Help is available for what's under the cursor.
+    Help,
+
+    /// Something is being moved in any direction.
+    Move,
+
+    /// Something is being resized left or right.
+    ResizeHorizontal,
+
+    /// Something is being resized up or down.
+    ResizeVertical,
+
+    /// Something is being resized along the diagonal from the top-right to the bottom-left.
+    ResizeNeSw,
+
+    /// Something is being resized along the diagonal from the top-left to the bottom-right.
+    ResizeNwSe,
+}
+
+// Deriving this needs `#[default]`, which is newer than the rest of the crate asks for
+#[allow(clippy::derivable_impls)]
+impl Default for CursorIcon {
+    /// Defaults to [`Arrow`](Self::Arrow).
+    #[inline]
+    fn default() -> Self {
+        Self::Arrow
+    }
+}
+
 /// The type of cursor lock to use for [`cursor_lock`] / [`set_cursor_lock`].
 ///
 /// [`cursor_lock`]: WindowBuilder::cursor_lock
@@ -25,9 +84,29 @@ pub enum CursorLock {
 
     /// The cursor is snapped to the center of the window.
     /// Typical setting for games where you move the view around with the mouse.
+    /// The movement itself is best read from [`RawMouseMotion`](crate::event::Event::RawMouseMotion).
     Center = 2,
 }
 
+/// The kind of fullscreen for [`fullscreen`] / [`set_fullscreen`].
+///
+/// [`fullscreen`]: WindowBuilder::fullscreen
+/// [`set_fullscreen`]: Window::set_fullscreen
+#[derive(Clone, Debug, PartialEq)]
+pub enum Fullscreen {
+    /// Covers a monitor with the window, without changing its video mode.
+    /// `None` means the monitor the window is currently on.
+    ///
+    /// The window's previous style, size and position are restored when leaving fullscreen.
+    Borderless(Option<Monitor>),
+
+    /// Switches the monitor of the [`VideoMode`] to it, and covers that monitor with the window.
+    ///
+    /// The monitor's original video mode is restored when leaving fullscreen or dropping the window.
+    /// Wayland doesn't let clients change video modes, so the window is only made fullscreen on the monitor there.
+    Exclusive(VideoMode),
+}
+
 /// Represents a window, of course.
 ///
 /// To create a window, use a [`builder`](Window::builder).
@@ -35,22 +114,152 @@ pub struct Window {
     pub(crate) inner: imp::WindowRepr,
 }
 
-pub(crate) trait WindowImpl {
+/// The functionality a platform needs to provide for a [`Window`].
+///
+/// Every function here backs the [`Window`] function of the same name, see those for what's expected.
+/// This only needs to be implemented for platforms outside of the crate, see
+/// [`BackendImpl`](crate::platform::BackendImpl) for how to plug them in.
+///
+/// Events are double buffered: the platform queues them up as they come, and [`swap_events`](Self::swap_events)
+/// makes everything queued so far available in [`events`](Self::events), starting a new queue.
+///
+/// Any function can be called from any thread, not just the one that created the window.
+/// Setters come in pairs: the blocking one must have applied the change by the time it returns, so that the
+/// getters reflect it right away, while the `_async` one may return before that. Either way, changes must be
+/// applied in the order they were made.
+pub trait WindowImpl: Send + Sync {
+    /// Gets the event buffer made available by the last [`swap_events`](Self::swap_events).
     fn events(&self) -> &[Event];
+
+    /// Runs `f` on whichever thread owns the window, blocking until it returns.
     fn execute(&self, f: &mut dyn FnMut());
+
+    /// Gets the current inner size, along with the scale factor of the window.
+    ///
+    /// The scale factor is what logical sizes are multiplied by to get physical ones, `1.0` being
+    /// 96 DPI (or whatever the platform considers unscaled). The size can be in either convention,
+    /// as long as converting it with the returned scale gives the right result.
     fn inner_size(&self) -> (Size, Scale);
+
+    /// Sets which window controls are available, `None` meaning there's no control menu at all.
     fn set_controls(&self, controls: Option<WindowControls>);
+
+    /// Non-blocking variant of [`set_controls`](Self::set_controls).
     fn set_controls_async(&self, controls: Option<WindowControls>);
+
+    /// Sets how the cursor is constrained, `None` meaning it isn't.
+    ///
+    /// This only applies while the window has focus, and has to be reapplied when it regains it.
     #[cfg(feature = "cursor-lock")]
     fn set_cursor_lock(&self, mode: Option<CursorLock>);
+
+    /// Non-blocking variant of [`set_cursor_lock`](Self::set_cursor_lock).
     #[cfg(feature = "cursor-lock")]
     fn set_cursor_lock_async(&self, mode: Option<CursorLock>);
+
+    /// Sets whether the user can resize the window by dragging its edges.
     fn set_resizable(&self, resizable: bool);
+
+    /// Non-blocking variant of [`set_resizable`](Self::set_resizable).
     fn set_resizable_async(&self, resizable: bool);
+
+    /// Sets the window title, which is kept even while there's no title bar to show it in.
     fn set_title(&self, title: &str);
+
+    /// Non-blocking variant of [`set_title`](Self::set_title).
     fn set_title_async(&self, title: &str);
+
+    /// Shows (`true`) or hides (`false`) the window.
     fn set_visible(&self, visible: bool);
+
+    /// Non-blocking variant of [`set_visible`](Self::set_visible).
     fn set_visible_async(&self, visible: bool);
+
+    // Everything below came after the functions above, and has a default body so that adding to
+    // this trait doesn't break existing implementations: setters do nothing, `_async` setters call
+    // the blocking one, and getters describe a window as the builder makes it by default: visible,
+    // resizable and not tracking anything else.
+
+    fn controls(&self) -> Option<WindowControls> {
+        Some(WindowControls::no_maximize())
+    }
+    fn current_monitor(&self) -> Option<Monitor> {
+        None
+    }
+    fn is_borderless(&self) -> bool {
+        false
+    }
+    fn is_focused(&self) -> bool {
+        false
+    }
+    fn is_maximized(&self) -> bool {
+        false
+    }
+    fn is_minimized(&self) -> bool {
+        false
+    }
+    fn is_resizable(&self) -> bool {
+        true
+    }
+    fn is_visible(&self) -> bool {
+        true
+    }
+    fn outer_position(&self) -> Point {
+        Point::Physical(0, 0)
+    }
+    fn title(&self) -> String {
+        String::new()
+    }
+    fn set_aspect_ratio(&self, _aspect_ratio: Option<(u32, u32)>) {}
+    fn set_aspect_ratio_async(&self, aspect_ratio: Option<(u32, u32)>) {
+        self.set_aspect_ratio(aspect_ratio)
+    }
+    fn set_borderless(&self, _borderless: bool) {}
+    fn set_borderless_async(&self, borderless: bool) {
+        self.set_borderless(borderless)
+    }
+    fn set_cursor_icon(&self, _icon: CursorIcon) {}
+    fn set_cursor_icon_async(&self, icon: CursorIcon) {
+        self.set_cursor_icon(icon)
+    }
+    fn set_fullscreen(&self, _fullscreen: Option<Fullscreen>) {}
+    fn set_fullscreen_async(&self, fullscreen: Option<Fullscreen>) {
+        self.set_fullscreen(fullscreen)
+    }
+    fn set_ime_allowed(&self, _allowed: bool) {}
+    fn set_ime_allowed_async(&self, allowed: bool) {
+        self.set_ime_allowed(allowed)
+    }
+    fn set_ime_position(&self, _position: Point) {}
+    fn set_ime_position_async(&self, position: Point) {
+        self.set_ime_position(position)
+    }
+    fn set_inner_size(&self, _inner_size: Size) {}
+    fn set_inner_size_async(&self, inner_size: Size) {
+        self.set_inner_size(inner_size)
+    }
+    fn set_max_inner_size(&self, _max_inner_size: Option<Size>) {}
+    fn set_max_inner_size_async(&self, max_inner_size: Option<Size>) {
+        self.set_max_inner_size(max_inner_size)
+    }
+    fn set_maximized(&self, _maximized: bool) {}
+    fn set_maximized_async(&self, maximized: bool) {
+        self.set_maximized(maximized)
+    }
+    fn set_min_inner_size(&self, _min_inner_size: Option<Size>) {}
+    fn set_min_inner_size_async(&self, min_inner_size: Option<Size>) {
+        self.set_min_inner_size(min_inner_size)
+    }
+    fn set_minimized(&self, _minimized: bool) {}
+    fn set_minimized_async(&self, minimized: bool) {
+        self.set_minimized(minimized)
+    }
+    fn set_outer_position(&self, _position: Point) {}
+    fn set_outer_position_async(&self, position: Point) {
+        self.set_outer_position(position)
+    }
+
+    /// Makes the events queued since the last call available in [`events`](Self::events).
     fn swap_events(&mut self);
 }
 
@@ -59,6 +268,12 @@ impl Window {
     pub const fn builder() -> WindowBuilder {
         WindowBuilder::new()
     }
+
+    /// Gets the [`Backend`] this window was created with.
+    #[inline]
+    pub fn backend(&self) -> Backend {
+        self.inner.backend()
+    }
 }
 
 impl Window {
@@ -104,6 +319,21 @@ impl Window {
         self.inner.execute(&mut move || f(self));
     }
 
+    /// Gets the availability of the window controls, as set by [`set_controls`](Self::set_controls).
+    #[inline]
+    pub fn controls(&self) -> Option<WindowControls> {
+        self.inner.controls()
+    }
+
+    /// Gets the monitor the window is on, or the one it's mostly on if it spans several.
+    ///
+    /// This is `None` if the window isn't on any monitor, such as while it's hidden on Wayland,
+    /// or if the backend doesn't have monitors at all.
+    #[inline]
+    pub fn current_monitor(&self) -> Option<Monitor> {
+        self.inner.current_monitor()
+    }
+
     /// Gets the inner size of the window.
     ///
     /// ```no_run
@@ -119,6 +349,91 @@ impl Window {
         self.inner.inner_size()
     }
 
+    /// Gets whether the window is without a border.
+    #[inline]
+    pub fn is_borderless(&self) -> bool {
+        self.inner.is_borderless()
+    }
+
+    /// Gets whether the window has keyboard focus, as last reported by [`Event::Focus`].
+    #[inline]
+    pub fn is_focused(&self) -> bool {
+        self.inner.is_focused()
+    }
+
+    /// Gets whether the window is maximized.
+    ///
+    /// A maximized window that's been minimized is still maximized, as that's what it goes back to.
+    #[inline]
+    pub fn is_maximized(&self) -> bool {
+        self.inner.is_maximized()
+    }
+
+    /// Gets whether the window is minimized.
+    ///
+    /// Wayland doesn't tell clients when they're minimized, so this is always `false` there.
+    #[inline]
+    pub fn is_minimized(&self) -> bool {
+        self.inner.is_minimized()
+    }
+
+    /// Gets whether the window is resizable by dragging the edges, as set by [`set_resizable`](Self::set_resizable).
+    #[inline]
+    pub fn is_resizable(&self) -> bool {
+        self.inner.is_resizable()
+    }
+
+    /// Gets whether the window is visible, as set by [`set_visible`](Self::set_visible).
+    ///
+    /// Being minimized or covered by other windows doesn't make a window hidden.
+    #[inline]
+    pub fn is_visible(&self) -> bool {
+        self.inner.is_visible()
+    }
+
+    /// Gets the position of the top-left corner of the window, including its decorations, on the desktop.
+    ///
+    /// This is kept up to date as the window moves, so it's cheap to call, unlike [`inner_size`](Self::inner_size).
+    /// It's logical if the window was created with a logical inner size, otherwise physical.
+    ///
+    /// Wayland doesn't let clients know where they are, so it's always at the origin there.
+    #[inline]
+    pub fn outer_position(&self) -> Point {
+        self.inner.outer_position()
+    }
+
+    /// Sets the ratio of width to height the inner size is kept at while resizing, such as `Some((16, 9))`.
+    ///
+    /// Ratios with a zero in them are treated as `None`.
+    #[inline]
+    pub fn set_aspect_ratio(&self, aspect_ratio: Option<(u32, u32)>) {
+        self.inner.set_aspect_ratio(aspect_ratio.filter(|&(x, y)| x != 0 && y != 0))
+    }
+
+    /// Non-blocking variant of [`set_aspect_ratio`](Self::set_aspect_ratio).
+    #[inline]
+    pub fn set_aspect_ratio_async(&self, aspect_ratio: Option<(u32, u32)>) {
+        self.inner.set_aspect_ratio_async(aspect_ratio.filter(|&(x, y)| x != 0 && y != 0))
+    }
+
+    /// Sets whether the window is without a border.
+    ///
+    /// The inner size is kept the same, so the window grows or shrinks by the size of the border.
+    /// While fullscreen, this takes effect after leaving fullscreen.
+    ///
+    /// On Wayland, the border comes from the compositor, which needs it to support xdg-decoration.
+    /// Compositors without it (such as GNOME's) decide for themselves, which is usually no border at all.
+    #[inline]
+    pub fn set_borderless(&self, borderless: bool) {
+        self.inner.set_borderless(borderless)
+    }
+
+    /// Non-blocking variant of [`set_borderless`](Self::set_borderless).
+    #[inline]
+    pub fn set_borderless_async(&self, borderless: bool) {
+        self.inner.set_borderless_async(borderless)
+    }
+
     /// Sets the availability of the window controls.
     ///  `None` indicates that no control menu is desired.
     #[inline]
@@ -132,6 +447,21 @@ impl Window {
         self.inner.set_controls_async(controls)
     }
 
+    /// Sets the cursor shape shown while the cursor is over the inner area of the window.
+    ///
+    /// On Wayland, this needs the compositor to support cursor-shape-v1,
+    /// otherwise the cursor is left as whatever the compositor last showed.
+    #[inline]
+    pub fn set_cursor_icon(&self, icon: CursorIcon) {
+        self.inner.set_cursor_icon(icon)
+    }
+
+    /// Non-blocking variant of [`set_cursor_icon`](Self::set_cursor_icon).
+    #[inline]
+    pub fn set_cursor_icon_async(&self, icon: CursorIcon) {
+        self.inner.set_cursor_icon_async(icon)
+    }
+
     /// Sets the cursor lock mode. See [`CursorLock`] for more info.
     #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "cursor-lock")))]
     #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "cursor-lock"))]
@@ -148,6 +478,158 @@ impl Window {
         self.inner.set_cursor_lock_async(mode)
     }
 
+    /// Sets the window's fullscreen mode, or leaves fullscreen if `None`. See [`Fullscreen`] for more info.
+    ///
+    /// ```no_run
+    /// use ramen::{monitor::Monitor, window::{Fullscreen, Window}};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let window = Window::builder().build()?;
+    /// let mode = Monitor::primary()?.and_then(|monitor| monitor.video_modes().into_iter().next());
+    /// if let Some(mode) = mode {
+    ///     window.set_fullscreen(Some(Fullscreen::Exclusive(mode)));
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) {
+        self.inner.set_fullscreen(fullscreen)
+    }
+
+    /// Non-blocking variant of [`set_fullscreen`](Self::set_fullscreen).
+    #[inline]
+    pub fn set_fullscreen_async(&self, fullscreen: Option<Fullscreen>) {
+        self.inner.set_fullscreen_async(fullscreen)
+    }
+
+    /// Sets whether input methods (IMEs) can be used to type into the window. Disallowed by default.
+    ///
+    /// While allowed, text composed with an IME is received as [`ImePreedit`] and [`ImeCommit`] events,
+    /// and key presses the IME uses for composition may not be received at all.
+    /// This is best left disabled unless a text field has focus, so gameplay controls aren't eaten by the IME.
+    ///
+    /// [`ImePreedit`]: crate::event::Event::ImePreedit
+    /// [`ImeCommit`]: crate::event::Event::ImeCommit
+    #[inline]
+    pub fn set_ime_allowed(&self, allowed: bool) {
+        self.inner.set_ime_allowed(allowed)
+    }
+
+    /// Non-blocking variant of [`set_ime_allowed`](Self::set_ime_allowed).
+    #[inline]
+    pub fn set_ime_allowed_async(&self, allowed: bool) {
+        self.inner.set_ime_allowed_async(allowed)
+    }
+
+    /// Sets where the IME candidate window should appear, relative to the top-left of the inner area.
+    ///
+    /// This is usually the bottom-left of the text caret, so the candidates follow what's being typed.
+    #[inline]
+    pub fn set_ime_position(&self, position: Point) {
+        self.inner.set_ime_position(position)
+    }
+
+    /// Non-blocking variant of [`set_ime_position`](Self::set_ime_position).
+    #[inline]
+    pub fn set_ime_position_async(&self, position: Point) {
+        self.inner.set_ime_position_async(position)
+    }
+
+    /// Sets the inner size of the window, which is the area inside its decorations.
+    ///
+    /// Logical sizes are scaled by the window's current scale factor.
+    /// While fullscreen, this is the size the window goes back to afterwards.
+    ///
+    /// Whether the size is kept logical or physical when the scale factor changes is up to the
+    /// builder's [`inner_size`](WindowBuilder::inner_size), this doesn't change it.
+    #[inline]
+    pub fn set_inner_size(&self, inner_size: Size) {
+        self.inner.set_inner_size(inner_size)
+    }
+
+    /// Non-blocking variant of [`set_inner_size`](Self::set_inner_size).
+    #[inline]
+    pub fn set_inner_size_async(&self, inner_size: Size) {
+        self.inner.set_inner_size_async(inner_size)
+    }
+
+    /// Sets the largest inner size the window can be resized to, or `None` for no limit.
+    ///
+    /// Logical sizes are scaled by the window's current scale factor, and follow it as it changes.
+    #[inline]
+    pub fn set_max_inner_size(&self, max_inner_size: Option<Size>) {
+        self.inner.set_max_inner_size(max_inner_size)
+    }
+
+    /// Non-blocking variant of [`set_max_inner_size`](Self::set_max_inner_size).
+    #[inline]
+    pub fn set_max_inner_size_async(&self, max_inner_size: Option<Size>) {
+        self.inner.set_max_inner_size_async(max_inner_size)
+    }
+
+    /// Maximizes the window (`true`), or restores it from being maximized (`false`).
+    ///
+    /// This is a request the window manager can refuse, see [`Event::Maximize`] for when it's done.
+    /// On Win32, maximizing a hidden window shows it.
+    #[inline]
+    pub fn set_maximized(&self, maximized: bool) {
+        self.inner.set_maximized(maximized)
+    }
+
+    /// Non-blocking variant of [`set_maximized`](Self::set_maximized).
+    #[inline]
+    pub fn set_maximized_async(&self, maximized: bool) {
+        self.inner.set_maximized_async(maximized)
+    }
+
+    /// Sets the smallest inner size the window can be resized to, or `None` for no limit.
+    ///
+    /// Logical sizes are scaled by the window's current scale factor, and follow it as it changes.
+    #[inline]
+    pub fn set_min_inner_size(&self, min_inner_size: Option<Size>) {
+        self.inner.set_min_inner_size(min_inner_size)
+    }
+
+    /// Non-blocking variant of [`set_min_inner_size`](Self::set_min_inner_size).
+    #[inline]
+    pub fn set_min_inner_size_async(&self, min_inner_size: Option<Size>) {
+        self.inner.set_min_inner_size_async(min_inner_size)
+    }
+
+    /// Minimizes the window (`true`), or restores it from being minimized (`false`).
+    ///
+    /// This is a request the window manager can refuse, see [`Event::Minimize`] for when it's done.
+    /// On Win32, minimizing a hidden window shows it, while hidden windows can't be minimized on X11.
+    /// Wayland only lets clients minimize themselves, so restoring does nothing there.
+    #[inline]
+    pub fn set_minimized(&self, minimized: bool) {
+        self.inner.set_minimized(minimized)
+    }
+
+    /// Non-blocking variant of [`set_minimized`](Self::set_minimized).
+    #[inline]
+    pub fn set_minimized_async(&self, minimized: bool) {
+        self.inner.set_minimized_async(minimized)
+    }
+
+    /// Moves the top-left corner of the window, including its decorations, to a position on the desktop.
+    ///
+    /// Logical positions are scaled by the window's current scale factor.
+    /// While fullscreen, this is the position the window goes back to afterwards.
+    ///
+    /// Wayland doesn't let clients position themselves, so this does nothing there.
+    #[inline]
+    pub fn set_outer_position(&self, position: Point) {
+        self.inner.set_outer_position(position)
+    }
+
+    /// Non-blocking variant of [`set_outer_position`](Self::set_outer_position).
+    #[inline]
+    pub fn set_outer_position_async(&self, position: Point) {
+        self.inner.set_outer_position_async(position)
+    }
+
     /// Sets whether the window is resizable by dragging the edges.
     #[inline]
     pub fn set_resizable(&self, resizable: bool) {
@@ -188,6 +670,12 @@ impl Window {
         self.inner.set_visible_async(visible);
     }
 
+    /// Gets the window title.
+    #[inline]
+    pub fn title(&self) -> String {
+        self.inner.title()
+    }
+
     /// Acquires events that have occured since the last call to [`swap_events`](Self::swap_events), if ever.
     ///
     /// The buffer containing those events is accessible via
@@ -203,10 +691,16 @@ impl Window {
 /// To create a builder, use [`Window::builder`].
 #[derive(Clone)]
 pub struct WindowBuilder {
+    pub(crate) backend: Option<Backend>,
+    pub(crate) centered: bool,
     pub(crate) class_name: MaybeStatic<str>,
+    pub(crate) cursor_icon: CursorIcon,
     #[cfg(feature = "cursor-lock")]
     pub(crate) cursor_lock: Option<CursorLock>,
+    pub(crate) fullscreen: Option<Fullscreen>,
     pub(crate) inner_size: Size,
+    pub(crate) position: Option<Point>,
+    pub(crate) size_limits: SizeLimits,
     pub(crate) style: WindowStyle,
     pub(crate) title: MaybeStatic<str>,
 }
@@ -214,10 +708,16 @@ pub struct WindowBuilder {
 impl WindowBuilder {
     pub(crate) const fn new() -> Self {
         Self {
+            backend: None,
+            centered: false,
             class_name: MaybeStatic::Static("ramen_window_class"),
+            cursor_icon: CursorIcon::Arrow,
             #[cfg(feature = "cursor-lock")]
             cursor_lock: None,
+            fullscreen: None,
             inner_size: Size::Logical(800.0, 608.0),
+            position: None,
+            size_limits: SizeLimits { min: None, max: None, aspect_ratio: None },
             style: WindowStyle {
                 borderless: false,
                 controls: Some(WindowControls::no_maximize()),
@@ -239,6 +739,41 @@ impl WindowBuilder {
 }
 
 impl WindowBuilder {
+    /// Sets the ratio of width to height the inner size is kept at while resizing, such as `Some((16, 9))`.
+    ///
+    /// Ratios with a zero in them are treated as `None`.
+    ///
+    /// Defaults to `None`.
+    #[inline]
+    pub fn aspect_ratio(&mut self, aspect_ratio: Option<(u32, u32)>) -> &mut Self {
+        self.size_limits.aspect_ratio = aspect_ratio.filter(|&(x, y)| x != 0 && y != 0);
+        self
+    }
+
+    /// Sets the [`Backend`] to create the window with, instead of detecting one at runtime.
+    /// `None` means to use [`Backend::detect`].
+    ///
+    /// Defaults to `None`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ramen::platform::Backend;
+    /// use ramen::window::Window;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let window = Window::builder()
+    ///     .backend(Some(Backend::Headless))
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn backend(&mut self, backend: Option<Backend>) -> &mut Self {
+        self.backend = backend;
+        self
+    }
+
     /// Sets whether the window is initially without a border.
     ///
     /// Defaults to `false`.
@@ -248,10 +783,23 @@ impl WindowBuilder {
         self
     }
 
+    /// Sets whether the window is initially centered on the primary monitor, overriding [`position`](Self::position).
+    ///
+    /// Defaults to `false`.
+    #[inline]
+    pub fn centered(&mut self, centered: bool) -> &mut Self {
+        self.centered = centered;
+        if centered {
+            self.position = None;
+        }
+        self
+    }
+
     /// Sets the platform-specific window class name.
     ///
     /// - Win32: `lpszClassName` in
-    /// [`WNDCLASSEXW`](https://docs.microsoft.com/en-us/windows/win32/api/winuser/ns-winuser-wndclassexw)
+    ///   [`WNDCLASSEXW`](https://docs.microsoft.com/en-us/windows/win32/api/winuser/ns-winuser-wndclassexw)
+    /// - X11: Both `res_name` and `res_class` in the `WM_CLASS` property
     /// - TODO: Other platforms!
     ///
     /// Defaults to `"ramen_window_class"`.
@@ -276,6 +824,15 @@ impl WindowBuilder {
         self
     }
 
+    /// Sets the initial cursor shape shown over the inner area of the window.
+    ///
+    /// Defaults to [`CursorIcon::Arrow`].
+    #[inline]
+    pub fn cursor_icon(&mut self, icon: CursorIcon) -> &mut Self {
+        self.cursor_icon = icon;
+        self
+    }
+
     #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "cursor-lock")))]
     #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "cursor-lock"))]
     #[inline]
@@ -284,8 +841,22 @@ impl WindowBuilder {
         self
     }
 
+    /// Sets whether the window is initially fullscreen, and how. See [`Fullscreen`] for more info.
+    ///
+    /// The inner size still applies, it's what the window goes back to when leaving fullscreen.
+    ///
+    /// Defaults to `None`.
+    #[inline]
+    pub fn fullscreen(&mut self, fullscreen: Option<Fullscreen>) -> &mut Self {
+        self.fullscreen = fullscreen;
+        self
+    }
+
     /// Sets the initial inner size of the window.
     ///
+    /// A logical size is kept when the scale factor changes, resizing the window, while a physical
+    /// size stays as it is. This holds for the rest of the window's lifetime.
+    ///
     /// Defaults to `Size::Logical(800.0, 608.0)`.
     // TODO: explain "if physical no scaling" etc
     #[inline]
@@ -294,6 +865,37 @@ impl WindowBuilder {
         self
     }
 
+    /// Sets the largest inner size the window can be resized to, or `None` for no limit.
+    ///
+    /// Defaults to `None`.
+    #[inline]
+    pub fn max_inner_size(&mut self, max_inner_size: Option<Size>) -> &mut Self {
+        self.size_limits.max = max_inner_size;
+        self
+    }
+
+    /// Sets the smallest inner size the window can be resized to, or `None` for no limit.
+    ///
+    /// Defaults to `None`.
+    #[inline]
+    pub fn min_inner_size(&mut self, min_inner_size: Option<Size>) -> &mut Self {
+        self.size_limits.min = min_inner_size;
+        self
+    }
+
+    /// Sets the initial position of the top-left corner of the window, including its decorations,
+    /// overriding [`centered`](Self::centered). Logical positions are scaled by the primary monitor's scale factor.
+    ///
+    /// Wayland doesn't let clients position themselves, so this does nothing there.
+    ///
+    /// Defaults to letting the system decide.
+    #[inline]
+    pub fn position(&mut self, position: Point) -> &mut Self {
+        self.position = Some(position);
+        self.centered = false;
+        self
+    }
+
     /// Sets whether the window is initially resizable.
     ///
     /// Defaults to `true`.
@@ -346,6 +948,114 @@ impl WindowBuilder {
     }
 }
 
+/// Getters for the builder's settings, mostly of use to implementors of
+/// [`BackendImpl`](crate::platform::BackendImpl).
+impl WindowBuilder {
+    /// Gets the ratio of width to height the inner size is kept at.
+    #[inline]
+    pub fn get_aspect_ratio(&self) -> Option<(u32, u32)> {
+        self.size_limits.aspect_ratio
+    }
+
+    /// Gets the backend set with [`backend`](Self::backend).
+    #[inline]
+    pub fn get_backend(&self) -> Option<Backend> {
+        self.backend
+    }
+
+    /// Gets whether the window is initially centered on the primary monitor.
+    #[inline]
+    pub fn get_centered(&self) -> bool {
+        self.centered
+    }
+
+    /// Gets whether the window is initially without a border.
+    #[inline]
+    pub fn get_borderless(&self) -> bool {
+        self.style.borderless
+    }
+
+    /// Gets the platform-specific window class name.
+    #[inline]
+    pub fn get_class_name(&self) -> &str {
+        self.class_name.as_ref()
+    }
+
+    /// Gets the initial window controls.
+    #[inline]
+    pub fn get_controls(&self) -> Option<&WindowControls> {
+        self.style.controls.as_ref()
+    }
+
+    /// Gets the initial cursor shape.
+    #[inline]
+    pub fn get_cursor_icon(&self) -> CursorIcon {
+        self.cursor_icon
+    }
+
+    /// Gets the initial cursor lock mode.
+    #[cfg_attr(feature = "nightly-docs", doc(cfg(feature = "cursor-lock")))]
+    #[cfg_attr(not(feature = "nightly-docs"), cfg(feature = "cursor-lock"))]
+    #[inline]
+    pub fn get_cursor_lock(&self) -> Option<CursorLock> {
+        self.cursor_lock
+    }
+
+    /// Gets the initial fullscreen mode.
+    #[inline]
+    pub fn get_fullscreen(&self) -> Option<&Fullscreen> {
+        self.fullscreen.as_ref()
+    }
+
+    /// Gets the initial inner size of the window.
+    #[inline]
+    pub fn get_inner_size(&self) -> Size {
+        self.inner_size
+    }
+
+    /// Gets the largest inner size the window can be resized to.
+    #[inline]
+    pub fn get_max_inner_size(&self) -> Option<Size> {
+        self.size_limits.max
+    }
+
+    /// Gets the smallest inner size the window can be resized to.
+    #[inline]
+    pub fn get_min_inner_size(&self) -> Option<Size> {
+        self.size_limits.min
+    }
+
+    /// Gets the initial position of the window.
+    #[inline]
+    pub fn get_position(&self) -> Option<Point> {
+        self.position
+    }
+
+    /// Gets whether the window is initially resizable.
+    #[inline]
+    pub fn get_resizable(&self) -> bool {
+        self.style.resizable
+    }
+
+    /// Gets whether the window controls and titlebar have a right-to-left layout.
+    #[inline]
+    pub fn get_rtl_layout(&self) -> bool {
+        self.style.rtl_layout
+    }
+
+    /// Gets the initial window title.
+    #[inline]
+    pub fn get_title(&self) -> &str {
+        self.title.as_ref()
+    }
+
+    /// Gets whether the window is initially visible.
+    #[inline]
+    pub fn get_visible(&self) -> bool {
+        self.style.visible
+    }
+}
+
 impl Default for WindowBuilder {
     /// Identical to construction via [`Window::builder`].
     fn default() -> Self {
@@ -381,10 +1091,12 @@ impl WindowControls {
         Self::new(true, false, true)
     }
 
+    #[cfg_attr(not(windows), allow(dead_code))]
     pub(crate) fn to_bits(&self) -> u32 {
         (self.minimize as u32) << 2 | (self.maximize as u32) << 1 | self.close as u32
     }
 
+    #[cfg_attr(not(windows), allow(dead_code))]
     pub(crate) fn from_bits(x: u32) -> Self {
         Self {
             minimize: x & (1 << 2) != 0,
@@ -401,6 +1113,39 @@ impl Default for WindowControls {
     }
 }
 
+/// Limits on the inner size, which are kept in the `Size` convention they were given in.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct SizeLimits {
+    pub min: Option<Size>,
+    pub max: Option<Size>,
+    pub aspect_ratio: Option<(u32, u32)>,
+}
+
+impl SizeLimits {
+    /// Fits a physical inner size within the limits.
+    ///
+    /// With an aspect ratio, the width is fitted first and the height follows it, after which the width is
+    /// adjusted again if the height is out of bounds. If the limits can't all be met, the maximum wins.
+    pub(crate) fn clamp(&self, client: (u32, u32), scale: Scale) -> (u32, u32) {
+        let (min_width, min_height) = self.min.map(|size| size.physical(scale)).unwrap_or((0, 0));
+        let (max_width, max_height) = self.max.map(|size| size.physical(scale)).unwrap_or((u32::MAX, u32::MAX));
+        let width = client.0.max(min_width).min(max_width);
+        match self.aspect_ratio {
+            Some((x, y)) => {
+                let (x, y) = (f64::from(x), f64::from(y));
+                let height = (f64::from(width) * y / x).round() as u32;
+                let limited = height.max(min_height).min(max_height);
+                if limited == height {
+                    (width, height)
+                } else {
+                    (((f64::from(limited) * x / y).round() as u32).min(max_width), limited)
+                }
+            },
+            None => (width, client.1.max(min_height).min(max_height)),
+        }
+    }
+}
+
 #[derive(Default, Clone)]
 pub(crate) struct WindowStyle {
     pub borderless: bool,
@@ -412,3 +1157,59 @@ pub(crate) struct WindowStyle {
     #[cfg(windows)]
     pub tool_window: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::SizeLimits;
+    use crate::monitor::Size;
+
+    #[test]
+    fn size_limits_none() {
+        let limits = SizeLimits::default();
+        assert_eq!(limits.clamp((1920, 1080), 1.0), (1920, 1080));
+        assert_eq!(limits.clamp((0, 0), 2.0), (0, 0));
+    }
+
+    #[test]
+    fn size_limits_min_max() {
+        let limits = SizeLimits {
+            min: Some(Size::Physical(320, 240)),
+            max: Some(Size::Logical(800.0, 600.0)),
+            aspect_ratio: None,
+        };
+        assert_eq!(limits.clamp((100, 1000), 1.0), (320, 600));
+        assert_eq!(limits.clamp((2000, 100), 2.0), (1600, 240));
+    }
+
+    #[test]
+    fn size_limits_ratio() {
+        let limits = SizeLimits { min: None, max: None, aspect_ratio: Some((16, 9)) };
+        assert_eq!(limits.clamp((1920, 0), 1.0), (1920, 1080));
+        assert_eq!(limits.clamp((1000, 1000), 1.0), (1000, 563));
+    }
+
+    #[test]
+    fn size_limits_ratio_min() {
+        let limits = SizeLimits { min: Some(Size::Physical(640, 480)), max: None, aspect_ratio: Some((16, 9)) };
+        assert_eq!(limits.clamp((100, 100), 1.0), (853, 480));
+        assert_eq!(limits.clamp((1920, 1080), 1.0), (1920, 1080));
+    }
+
+    #[test]
+    fn size_limits_ratio_max() {
+        let limits = SizeLimits { min: None, max: Some(Size::Physical(1000, 500)), aspect_ratio: Some((16, 9)) };
+        assert_eq!(limits.clamp((1920, 1080), 1.0), (889, 500));
+        assert_eq!(limits.clamp((800, 800), 1.0), (800, 450));
+    }
+
+    #[test]
+    fn size_limits_conflicting() {
+        // 16:9 can't fit between these, so the maximum wins
+        let limits = SizeLimits {
+            min: Some(Size::Physical(100, 400)),
+            max: Some(Size::Physical(500, 500)),
+            aspect_ratio: Some((16, 9)),
+        };
+        assert_eq!(limits.clamp((300, 300), 1.0), (500, 400));
+    }
+}